@@ -0,0 +1,39 @@
+//! Decodes arbitrary input with both `rmpv` and `rmp-serde`, checking that they agree on every
+//! value both accept. Values are compared as in `decoders_agree_on_corrupted_input` in
+//! `rmpv-tests/tests/differential.rs`, which documents why.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rmpv::decode::read_value;
+use rmpv::encode::write_value;
+use rmpv::Value;
+
+fn is_utf8(val: &Value) -> bool {
+    match *val {
+        Value::String(ref s) => s.is_str(),
+        Value::Array(ref vec) => vec.iter().all(is_utf8),
+        Value::Map(ref vec) => vec.iter().all(|(k, v)| is_utf8(k) && is_utf8(v)),
+        _ => true,
+    }
+}
+
+fn encode(val: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_value(&mut buf, val).unwrap();
+    buf
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (lhs, rhs) = match (read_value(&mut &data[..]), rmp_serde::from_slice::<Value>(data)) {
+        (Ok(lhs), Ok(rhs)) => (lhs, rhs),
+        _ => return,
+    };
+    if !is_utf8(&lhs) {
+        return;
+    }
+
+    let buf = encode(&lhs);
+    assert_eq!(buf, encode(&rhs));
+    assert_eq!(buf, rmp_serde::to_vec(&lhs).unwrap());
+});
@@ -0,0 +1,59 @@
+//! Runs the low-level decoders of `rmp` over arbitrary input, checking that those walking values
+//! agree with `validate` on where a well-formed value ends.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rmp::decode::{self, Bytes, PushDecoder, PushStatus, Reader, ValidationOptions};
+
+/// Nesting allowed by `validate`, well below what the other decoders allow by default.
+const MAX_DEPTH: usize = 64;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode::is_canonical(data);
+    let _ = decode::read_str_from_slice(data);
+    let _ = decode::read_bin_ref(&mut Bytes::new(data));
+    let _ = decode::read_ext_ref(&mut Bytes::new(data));
+    let _ = decode::read_timestamp(&mut Bytes::new(data));
+    if let Ok(array) = decode::read_array_ref(&mut Bytes::new(data)) {
+        assert_eq!(array.len(), array.count());
+    }
+    if let Ok(map) = decode::read_map_ref(&mut Bytes::new(data)) {
+        assert_eq!(map.len(), map.count());
+    }
+
+    let opts = ValidationOptions::new().max_depth(MAX_DEPTH);
+    let len = match decode::validate_with_options(data, &opts) {
+        Ok(len) => len,
+        Err(..) => return,
+    };
+
+    let mut rd = Bytes::new(data);
+    decode::skip_value(&mut rd).unwrap();
+    assert_eq!(len as u64, rd.position());
+
+    let mut rd = Reader::new(Bytes::new(data));
+    loop {
+        rd.next_event().unwrap();
+        if rd.depth() == 0 {
+            break;
+        }
+    }
+    // The data of a top-level string, binary or extension is left in the reader.
+    assert_eq!(len as u64, rd.get_ref().position() + u64::from(rd.data_left()));
+
+    // Feed the input in small chunks, so that headers and data get split.
+    let mut dec = PushDecoder::new();
+    let mut pos = 0;
+    loop {
+        let end = data.len().min(pos + 3);
+        let (consumed, status) = dec.feed(&data[pos..end]).unwrap();
+        pos += consumed;
+        match status {
+            PushStatus::Value => break,
+            PushStatus::NeedMore(..) => assert!(end < data.len()),
+            PushStatus::Event(..) | PushStatus::Data(..) => {}
+        }
+    }
+    assert_eq!(len, pos);
+});
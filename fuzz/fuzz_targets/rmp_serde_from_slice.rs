@@ -0,0 +1,81 @@
+//! Deserializes arbitrary input into representative derived types with `rmp-serde`, checking that
+//! whatever deserializes survives a roundtrip.
+
+#![no_main]
+
+use std::collections::BTreeMap;
+
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_bytes::ByteBuf;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Newtype(u32);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Tuple(i8, String, Option<u64>);
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Enum {
+    Unit,
+    Newtype(i64),
+    Tuple(u8, bool),
+    Struct { a: f32, b: Vec<Enum> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Struct {
+    unit: Unit,
+    newtype: Newtype,
+    tuple: Tuple,
+    variants: Vec<Enum>,
+    map: BTreeMap<String, Option<f64>>,
+    bytes: ByteBuf,
+    big: u128,
+    nested: Option<Box<Struct>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Borrowed<'a> {
+    s: &'a str,
+    #[serde(with = "serde_bytes")]
+    b: &'a [u8],
+}
+
+type Encode<T> = fn(&T) -> Result<Vec<u8>, rmp_serde::encode::Error>;
+
+/// Checks that a value deserialized from the input serializes, both as a tuple and with named
+/// fields, to bytes that deserialize and serialize to the same bytes again.
+fn roundtrip<T: Serialize + DeserializeOwned>(data: &[u8]) {
+    let val: T = match rmp_serde::from_slice(data) {
+        Ok(val) => val,
+        Err(..) => return,
+    };
+
+    // Compare encodings rather than values, since NaN never equals itself.
+    let encoders: [Encode<T>; 2] = [rmp_serde::to_vec, rmp_serde::to_vec_named];
+    for encode in encoders {
+        let buf = encode(&val).unwrap();
+        let again: T = rmp_serde::from_slice(&buf).unwrap();
+        assert_eq!(buf, encode(&again).unwrap());
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    roundtrip::<Struct>(data);
+    roundtrip::<Enum>(data);
+    roundtrip::<Vec<Tuple>>(data);
+    roundtrip::<BTreeMap<u64, Vec<Option<String>>>>(data);
+
+    // Borrowed data must point into the input.
+    if let Ok(val) = rmp_serde::from_slice::<Borrowed<'_>>(data) {
+        let range = data.as_ptr_range();
+        assert!(range.contains(&val.s.as_ptr()) || val.s.is_empty());
+        assert!(range.contains(&val.b.as_ptr()) || val.b.is_empty());
+    }
+});
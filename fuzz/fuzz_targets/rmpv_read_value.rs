@@ -0,0 +1,32 @@
+//! Decodes arbitrary input as `rmpv` values, checking that whatever decodes survives a roundtrip.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rmpv::decode::{read_value, read_value_ref, read_value_with_limits, DecodeLimits};
+use rmpv::encode::{write_value, write_value_ref};
+
+fuzz_target!(|data: &[u8]| {
+    let val = read_value(&mut &data[..]);
+    let val_ref = read_value_ref(&mut &data[..]);
+    assert_eq!(val.is_ok(), val_ref.is_ok());
+
+    let limits = DecodeLimits { max_total_bytes: data.len() as u64, ..DecodeLimits::default() };
+    assert_eq!(val.is_ok(), read_value_with_limits(&mut &data[..], &limits).is_ok());
+
+    let (val, val_ref) = match (val, val_ref) {
+        (Ok(val), Ok(val_ref)) => (val, val_ref),
+        _ => return,
+    };
+
+    // Compare encodings rather than values, since NaN never equals itself.
+    let mut buf = Vec::new();
+    write_value(&mut buf, &val).unwrap();
+    let mut buf_ref = Vec::new();
+    write_value_ref(&mut buf_ref, &val_ref).unwrap();
+    assert_eq!(buf, buf_ref);
+
+    let mut again = Vec::new();
+    write_value(&mut again, &read_value(&mut &buf[..]).unwrap()).unwrap();
+    assert_eq!(buf, again);
+});
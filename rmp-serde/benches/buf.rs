@@ -2,7 +2,8 @@
 
 extern crate test;
 
-
+#[macro_use]
+extern crate serde_derive;
 
 use serde::{Deserialize, Serialize};
 
@@ -35,3 +36,137 @@ fn bench_strings(bencher: &mut Bencher, size: usize) {
         <Vec<String>>::deserialize(&mut rmp_serde::Deserializer::new(&buf[..])).unwrap();
     })
 }
+
+#[derive(Serialize)]
+struct Point {
+    x: i32,
+    y: Option<i32>,
+    label: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Shape {
+    name: String,
+    origin: Point,
+    points: Vec<Point>,
+    parent: Option<Box<Shape>>,
+}
+
+fn shape() -> Shape {
+    let points = (0..100)
+        .map(|i| Point { x: i, y: if i % 2 == 0 { Some(i) } else { None }, label: None })
+        .collect();
+    Shape { name: "polygon".into(), origin: Point { x: 0, y: None, label: Some("o".into()) }, points, parent: None }
+}
+
+#[bench]
+fn bench_struct_map(bencher: &mut Bencher) {
+    let val = shape();
+    let mut buf = Vec::new();
+
+    bencher.iter(|| {
+        buf.clear();
+        val.serialize(&mut rmp_serde::Serializer::new(&mut buf).with_struct_map()).unwrap();
+    })
+}
+
+#[bench]
+fn bench_struct_map_skip_none(bencher: &mut Bencher) {
+    let val = shape();
+    let mut buf = Vec::new();
+
+    bencher.iter(|| {
+        buf.clear();
+        val.serialize(&mut rmp_serde::Serializer::new(&mut buf).with_struct_map().with_skip_none()).unwrap();
+    })
+}
+
+#[bench]
+fn bench_struct_map_skip_none_patched(bencher: &mut Bencher) {
+    let val = shape();
+    let mut buf = Vec::new();
+
+    bencher.iter(|| {
+        buf.clear();
+        let mut se = rmp_serde::Serializer::new(&mut buf).with_struct_map().with_skip_none().with_minimal_length_patching();
+        val.serialize(&mut se).unwrap();
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct Record(u32, String, bool);
+
+#[derive(Serialize, Deserialize)]
+struct NamedRecord {
+    id: u32,
+    name: String,
+    active: bool,
+}
+
+fn ints() -> Vec<u8> {
+    let vec: Vec<u64> = (0..1000).map(|i| i * i * i).collect();
+    rmp_serde::to_vec(&vec).unwrap()
+}
+
+fn records() -> Vec<u8> {
+    let vec: Vec<Record> = (0..1000).map(|i| Record(i, format!("record {i}"), i % 2 == 0)).collect();
+    rmp_serde::to_vec(&vec).unwrap()
+}
+
+fn named_records() -> Vec<u8> {
+    let vec: Vec<NamedRecord> = (0..1000)
+        .map(|i| NamedRecord { id: i, name: format!("record {i}"), active: i % 2 == 0 })
+        .collect();
+    rmp_serde::to_vec_named(&vec).unwrap()
+}
+
+#[bench]
+fn bench_from_slice_ints(bencher: &mut Bencher) {
+    let buf = ints();
+
+    bencher.iter(|| rmp_serde::from_slice::<Vec<u64>>(&buf).unwrap())
+}
+
+#[bench]
+fn bench_from_slice_tuple_struct(bencher: &mut Bencher) {
+    let buf = records();
+
+    bencher.iter(|| rmp_serde::from_slice::<Vec<Record>>(&buf).unwrap())
+}
+
+#[bench]
+fn bench_from_slice_named_struct(bencher: &mut Bencher) {
+    let buf = named_records();
+
+    bencher.iter(|| rmp_serde::from_slice::<Vec<NamedRecord>>(&buf).unwrap())
+}
+
+#[bench]
+fn bench_from_read_ints(bencher: &mut Bencher) {
+    let buf = ints();
+
+    bencher.iter(|| rmp_serde::from_read::<_, Vec<u64>>(&buf[..]).unwrap())
+}
+
+#[bench]
+fn bench_from_read_named_struct(bencher: &mut Bencher) {
+    let buf = named_records();
+
+    bencher.iter(|| rmp_serde::from_read::<_, Vec<NamedRecord>>(&buf[..]).unwrap())
+}
+
+#[bench]
+fn bench_to_vec_tuple_struct(bencher: &mut Bencher) {
+    let vec: Vec<Record> = (0..1000).map(|i| Record(i, format!("record {i}"), i % 2 == 0)).collect();
+
+    bencher.iter(|| rmp_serde::to_vec(&vec).unwrap())
+}
+
+#[bench]
+fn bench_to_vec_named_struct(bencher: &mut Bencher) {
+    let vec: Vec<NamedRecord> = (0..1000)
+        .map(|i| NamedRecord { id: i, name: format!("record {i}"), active: i % 2 == 0 })
+        .collect();
+
+    bencher.iter(|| rmp_serde::to_vec_named(&vec).unwrap())
+}
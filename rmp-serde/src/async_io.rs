@@ -0,0 +1,60 @@
+//! Asynchronous (de)serialization functions, generated for each supported async I/O library.
+
+macro_rules! async_serde_module {
+    ($(#[$attr:meta])* $name:ident, $($io:tt)+) => {
+        $(#[$attr])*
+        pub mod $name {
+            use serde::de::DeserializeOwned;
+            use serde::Serialize;
+
+            use rmp::decode::$name::read_value_bytes_async;
+            use rmp::encode::ValueWriteError;
+            use $($io)+::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+            use crate::{decode, encode};
+
+            /// Reads a single MessagePack value from the given reader and deserializes it into an
+            /// instance of type `T`.
+            ///
+            /// The value is framed by its own headers, so no length prefix is expected and nothing
+            /// past the end of the value is read, leaving the reader positioned at the next one.
+            /// The encoded value is buffered in memory before deserializing it.
+            ///
+            /// # Errors
+            ///
+            /// Besides the errors of [`from_slice`](crate::from_slice), this fails on any I/O
+            /// error. Reaching the end of the stream before the value starts is reported as
+            /// `Error::InvalidMarkerRead` with `ErrorKind::UnexpectedEof`.
+            pub async fn from_async_read<R, T>(rd: &mut R) -> Result<T, decode::Error>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+                T: DeserializeOwned,
+            {
+                let mut buf = Vec::with_capacity(128);
+                read_value_bytes_async(rd, &mut buf).await?;
+
+                decode::from_slice(&buf)
+            }
+
+            /// Serializes the given data structure as MessagePack into the given writer, using the
+            /// compact representation like [`to_vec`](crate::to_vec).
+            ///
+            /// The value is serialized into a buffer first and then written at once. The writer
+            /// is not flushed.
+            ///
+            /// # Errors
+            ///
+            /// Serialization can fail if `T`'s implementation of `Serialize` decides to fail, or on
+            /// any I/O error.
+            pub async fn to_async_write<W, T>(wr: &mut W, val: &T) -> Result<(), encode::Error>
+            where
+                W: AsyncWrite + Unpin + ?Sized,
+                T: Serialize + ?Sized,
+            {
+                let buf = encode::to_vec(val)?;
+                wr.write_all(&buf).await
+                    .map_err(|err| encode::Error::InvalidValueWrite(ValueWriteError::InvalidDataWrite(err)))
+            }
+        }
+    };
+}
@@ -0,0 +1,246 @@
+//! Framing of MessagePack values on byte streams with [`tokio_util::codec`].
+
+use std::error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io;
+use std::marker::PhantomData;
+
+use bytes::{BufMut, BytesMut};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio_util::codec::{Decoder, Encoder};
+
+use rmp::decode::{Event, PushDecodeError, PushDecoder, PushStatus};
+
+use crate::{decode, encode, Deserializer, Serializer};
+
+/// An error which can occur when decoding or encoding frames with a [`MsgPackCodec`].
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error of the underlying stream.
+    Io(io::Error),
+    /// A frame is longer than the maximum length allowed.
+    FrameTooLarge,
+    /// A frame is malformed or it could not be deserialized.
+    Decode(decode::Error),
+    /// An item could not be serialized.
+    Encode(encode::Error),
+}
+
+impl error::Error for Error {
+    #[cold]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::FrameTooLarge => None,
+            Error::Decode(ref err) => Some(err),
+            Error::Encode(ref err) => Some(err),
+        }
+    }
+}
+
+impl Display for Error {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            Error::Io(ref err) => Display::fmt(err, f),
+            Error::FrameTooLarge => f.write_str("frame exceeds the maximum length"),
+            Error::Decode(ref err) => Display::fmt(err, f),
+            Error::Encode(ref err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[cold]
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<decode::Error> for Error {
+    #[cold]
+    fn from(err: decode::Error) -> Error {
+        Error::Decode(err)
+    }
+}
+
+impl From<encode::Error> for Error {
+    #[cold]
+    fn from(err: encode::Error) -> Error {
+        Error::Encode(err)
+    }
+}
+
+impl From<PushDecodeError> for Error {
+    #[cold]
+    fn from(err: PushDecodeError) -> Error {
+        match err {
+            PushDecodeError::TypeMismatch(marker) => Error::Decode(decode::Error::TypeMismatch(marker)),
+            PushDecodeError::DepthLimitExceeded => Error::Decode(decode::Error::DepthLimitExceeded),
+        }
+    }
+}
+
+/// A codec splitting a byte stream into MessagePack values, decoding them as `T`.
+///
+/// MessagePack values are self-delimiting, so no length prefix is used: each frame is exactly one
+/// encoded value. The buffered input is scanned incrementally as it arrives, so every byte is
+/// looked at once regardless of how the stream is chunked, and a frame declaring more data than
+/// allowed is rejected as soon as its header is seen. Complete frames are deserialized straight
+/// from the read buffer without copying them.
+///
+/// Any `Serialize` item can be encoded, using the compact representation like
+/// [`to_vec`](crate::to_vec) by default.
+///
+/// Errors of the framing, including malformed input, leave the stream in an unknown state and it
+/// should be dropped. A frame that fails to deserialize as `T` is consumed though, so decoding may
+/// continue with the next one.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use rmp_serde::codec::MsgPackCodec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = MsgPackCodec::<(u8, String)>::new().max_frame_len(1024);
+///
+/// let mut buf = BytesMut::new();
+/// codec.encode((42, "le message"), &mut buf).unwrap();
+///
+/// // The frame is incomplete.
+/// let rest = buf.split_off(5);
+/// assert!(codec.decode(&mut buf).unwrap().is_none());
+///
+/// buf.unsplit(rest);
+/// assert_eq!(Some((42, "le message".to_owned())), codec.decode(&mut buf).unwrap());
+/// assert!(buf.is_empty());
+/// ```
+pub struct MsgPackCodec<T> {
+    decoder: PushDecoder,
+    /// Number of bytes of the current frame scanned so far.
+    scanned: usize,
+    max_frame_len: usize,
+    max_depth: usize,
+    struct_map: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Debug for MsgPackCodec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("MsgPackCodec")
+            .field("decoder", &self.decoder)
+            .field("scanned", &self.scanned)
+            .field("max_frame_len", &self.max_frame_len)
+            .field("max_depth", &self.max_depth)
+            .field("struct_map", &self.struct_map)
+            .finish()
+    }
+}
+
+impl<T> Default for MsgPackCodec<T> {
+    #[inline]
+    fn default() -> Self {
+        MsgPackCodec::new()
+    }
+}
+
+impl<T> MsgPackCodec<T> {
+    /// Constructs a new codec, allowing frames of up to 8 MiB with arrays and maps nested up to
+    /// 1024 levels deep.
+    #[inline]
+    pub fn new() -> Self {
+        MsgPackCodec {
+            decoder: PushDecoder::new(),
+            scanned: 0,
+            max_frame_len: 8 * 1024 * 1024,
+            max_depth: 1024,
+            struct_map: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length of a frame in bytes, both when decoding and encoding.
+    #[inline]
+    pub fn max_frame_len(mut self, len: usize) -> Self {
+        self.max_frame_len = len;
+        self
+    }
+
+    /// Sets the maximum nesting depth of arrays and maps of decoded frames.
+    #[inline]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self.decoder.set_max_depth(depth);
+        self
+    }
+
+    /// Encodes structs as maps with field names, like [`to_vec_named`](crate::to_vec_named).
+    #[inline]
+    pub fn with_struct_map(mut self) -> Self {
+        self.struct_map = true;
+        self
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), Error> {
+        if len > self.max_frame_len {
+            return Err(Error::FrameTooLarge);
+        }
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> Decoder for MsgPackCodec<T> {
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, Error> {
+        loop {
+            let (consumed, status) = self.decoder.feed(&src[self.scanned..])?;
+            self.scanned += consumed;
+
+            match status {
+                PushStatus::NeedMore(len) => {
+                    self.check_len(self.scanned)?;
+                    src.reserve(len.min(self.max_frame_len - self.scanned));
+                    return Ok(None);
+                }
+                PushStatus::Event(Event::Str(len) | Event::Bin(len) | Event::Ext { len, .. }) => {
+                    self.check_len(self.scanned.saturating_add(len as usize))?;
+                }
+                PushStatus::Event(..) | PushStatus::Data(..) => {
+                    self.check_len(self.scanned)?;
+                }
+                PushStatus::Value => {
+                    let frame = src.split_to(self.scanned);
+                    self.scanned = 0;
+
+                    let mut de = Deserializer::from_read_ref(&frame[..]);
+                    // The deserializer fails once its counter reaches zero, so it needs one more.
+                    de.set_max_depth(self.max_depth.saturating_add(1));
+                    return Ok(Some(T::deserialize(&mut de)?));
+                }
+            }
+        }
+    }
+}
+
+impl<T, I: Serialize> Encoder<I> for MsgPackCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<(), Error> {
+        let start = dst.len();
+        let res = if self.struct_map {
+            item.serialize(&mut Serializer::new(dst.writer()).with_struct_map())
+        } else {
+            item.serialize(&mut Serializer::new(dst.writer()))
+        };
+
+        let res = res.map_err(Error::from).and_then(|()| self.check_len(dst.len() - start));
+        if res.is_err() {
+            dst.truncate(start);
+        }
+        res
+    }
+}
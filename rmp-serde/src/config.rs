@@ -1,6 +1,6 @@
 //! Change MessagePack behavior with configuration wrappers.
 use rmp::encode;
-use serde::{Serialize, Serializer};
+use serde::Serializer;
 
 use crate::encode::{Error, UnderlyingWrite};
 
@@ -13,7 +13,7 @@ pub trait SerializerConfig: sealed::SerializerConfig {}
 impl<T: sealed::SerializerConfig> SerializerConfig for T {}
 
 mod sealed {
-    use serde::{Serialize, Serializer};
+    use serde::Serializer;
 
     use crate::encode::{Error, UnderlyingWrite};
 
@@ -27,11 +27,22 @@ mod sealed {
             S: UnderlyingWrite,
             for<'a> &'a mut S: Serializer<Ok = (), Error = Error>;
 
-        fn write_struct_field<S, T>(ser: &mut S, key: &'static str, value: &T) -> Result<(), Error>
+        /// Encodes the key of a struct field, given its index among all fields of the struct and
+        /// its name, which is followed by its value, returning the number of bytes written. Writes
+        /// nothing for structs serialized as arrays.
+        fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
         where
             S: UnderlyingWrite,
-            for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-            T: ?Sized + Serialize;
+            for<'a> &'a mut S: Serializer<Ok = (), Error = Error>;
+
+        /// Writes the header of an enum variant with data, which is followed by its identifier and
+        /// its data.
+        ///
+        /// Used in `Serializer::serialize_*_variant` methods, except for unit variants.
+        fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+        where
+            S: UnderlyingWrite,
+            for<'a> &'a mut S: Serializer<Ok = (), Error = Error>;
 
         /// Encodes an enum variant ident (id or name) according to underlying writer.
         ///
@@ -48,15 +59,56 @@ mod sealed {
         /// Determines the value of `Serializer::is_human_readable` and
         /// `Deserializer::is_human_readable`.
         fn is_human_readable() -> bool;
+
+        /// Determines whether the serializer produces the canonical encoding.
+        fn is_canonical() -> bool;
+
+        /// Determines whether `Some` is encoded as a single-element array, so that it can be told
+        /// apart from `None` even if it contains `None` or `()`.
+        fn wraps_options() -> bool;
+
+        /// Determines whether fields of structs serialized as maps are omitted if they are nil.
+        fn skips_none() -> bool;
+
+        /// Determines which encodings of structs the deserializer accepts.
+        fn struct_layout() -> StructLayout;
+
+        /// Determines which identifiers of enum variants the deserializer accepts.
+        fn variant_ident() -> VariantIdent;
+    }
+
+    /// Encodings of structs accepted by the deserializer.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum StructLayout {
+        /// Both maps with field names and arrays.
+        Any,
+        /// Only maps with field names.
+        Map,
+        /// Only arrays.
+        Tuple,
+    }
+
+    /// Identifiers of enum variants accepted by the deserializer.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum VariantIdent {
+        /// Both indices and names.
+        Any,
+        /// Only indices.
+        Index,
+        /// Only names.
+        Name,
     }
 }
 
+pub(crate) use self::sealed::{StructLayout, VariantIdent};
+
 /// The default serializer/deserializer configuration.
 ///
 /// This configuration:
 /// - Writes structs as a tuple, without field names
-/// - Writes enum variants as integers
+/// - Writes enum variants by name
 /// - Writes and reads types as binary, not human-readable
+/// - Reads structs and enum variants in either encoding
 //
 /// This is the most compact representation.
 #[derive(Copy, Clone, Debug)]
@@ -74,13 +126,23 @@ impl sealed::SerializerConfig for DefaultConfig {
     }
 
     #[inline]
-    fn write_struct_field<S, T>(ser: &mut S, _key: &'static str, value: &T) -> Result<(), Error>
+    fn write_struct_key<S>(_ser: &mut S, _idx: u32, _key: &'static str) -> Result<usize, Error>
     where
         S: UnderlyingWrite,
         for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-        T: ?Sized + Serialize,
     {
-        value.serialize(ser)
+        Ok(0)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        encode::write_map_len(ser.get_mut(), 1)?;
+
+        Ok(())
     }
 
     #[inline]
@@ -100,6 +162,31 @@ impl sealed::SerializerConfig for DefaultConfig {
     fn is_human_readable() -> bool {
         false
     }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        StructLayout::Any
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        VariantIdent::Any
+    }
 }
 
 /// Config wrapper, that overrides struct serialization by packing as a map with field names.
@@ -109,6 +196,8 @@ impl sealed::SerializerConfig for DefaultConfig {
 ///
 /// Default `Serializer` implementation writes structs as a tuple, i.e. only its length is encoded,
 /// because it is the most compact representation.
+///
+/// The deserializer then only accepts structs encoded as a map, rejecting those encoded as a tuple.
 #[derive(Copy, Clone, Debug)]
 pub struct StructMapConfig<C>(C);
 
@@ -134,14 +223,23 @@ where
         Ok(())
     }
 
-    fn write_struct_field<S, T>(ser: &mut S, key: &'static str, value: &T) -> Result<(), Error>
+    fn write_struct_key<S>(ser: &mut S, _idx: u32, key: &'static str) -> Result<usize, Error>
     where
         S: UnderlyingWrite,
         for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-        T: ?Sized + Serialize,
     {
         encode::write_str(ser.get_mut(), key)?;
-        value.serialize(ser)
+
+        Ok(encode::size_of_str_header(key.len() as u32) + key.len())
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
     }
 
     #[inline]
@@ -161,10 +259,37 @@ where
     fn is_human_readable() -> bool {
         C::is_human_readable()
     }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        StructLayout::Map
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
 }
 
 /// Config wrapper that overrides struct serlization by packing as a tuple without field
 /// names.
+///
+/// The deserializer then only accepts structs encoded as a tuple, rejecting those encoded as a map.
 #[derive(Copy, Clone, Debug)]
 pub struct StructTupleConfig<C>(C);
 
@@ -191,13 +316,21 @@ where
     }
 
     #[inline]
-    fn write_struct_field<S, T>(ser: &mut S, _key: &'static str, value: &T) -> Result<(), Error>
+    fn write_struct_key<S>(_ser: &mut S, _idx: u32, _key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        Ok(0)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
     where
         S: UnderlyingWrite,
         for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-        T: ?Sized + Serialize,
     {
-        value.serialize(ser)
+        C::write_variant_header(ser)
     }
 
     #[inline]
@@ -217,6 +350,31 @@ where
     fn is_human_readable() -> bool {
         C::is_human_readable()
     }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        StructLayout::Tuple
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
 }
 
 /// Config wrapper that overrides `Serializer::is_human_readable` and
@@ -246,13 +404,21 @@ where
     }
 
     #[inline]
-    fn write_struct_field<S, T>(ser: &mut S, key: &'static str, value: &T) -> Result<(), Error>
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
     where
         S: UnderlyingWrite,
         for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-        T: ?Sized + Serialize,
     {
-        C::write_struct_field(ser, key, value)
+        C::write_variant_header(ser)
     }
 
     #[inline]
@@ -272,6 +438,31 @@ where
     fn is_human_readable() -> bool {
         true
     }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
 }
 
 /// Config wrapper that overrides `Serializer::is_human_readable` and
@@ -301,13 +492,21 @@ where
     }
 
     #[inline]
-    fn write_struct_field<S, T>(ser: &mut S, key: &'static str, value: &T) -> Result<(), Error>
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
     where
         S: UnderlyingWrite,
         for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
-        T: ?Sized + Serialize,
     {
-        C::write_struct_field(ser, key, value)
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
     }
 
     #[inline]
@@ -327,4 +526,779 @@ where
     fn is_human_readable() -> bool {
         false
     }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that makes the serializer produce the canonical encoding, so that equal values
+/// are always serialized to the same bytes.
+///
+/// On top of the smallest integer and length headers written anyway, floats are written as `f32`
+/// whenever that is lossless with all NaNs normalized, and entries of maps, including structs
+/// serialized as maps, are sorted by the encoded bytes of their keys. See
+/// [`rmp::decode::is_canonical`] for the exact rules.
+///
+/// Maps are buffered in memory to sort their entries.
+#[derive(Copy, Clone, Debug)]
+pub struct CanonicalConfig<C>(C);
+
+impl<C> CanonicalConfig<C> {
+    /// Creates a `CanonicalConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for CanonicalConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that overrides enum serialization by identifying variants by their index.
+///
+/// The deserializer then only accepts variants identified by their index.
+#[derive(Copy, Clone, Debug)]
+pub struct VariantIntegerConfig<C>(C);
+
+impl<C> VariantIntegerConfig<C> {
+    /// Creates a `VariantIntegerConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for VariantIntegerConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        ser.serialize_u32(variant_index)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        VariantIdent::Index
+    }
+}
+
+/// Config wrapper that overrides enum serialization by identifying variants by their name.
+///
+/// The deserializer then only accepts variants identified by their name.
+#[derive(Copy, Clone, Debug)]
+pub struct VariantStringConfig<C>(C);
+
+impl<C> VariantStringConfig<C> {
+    /// Creates a `VariantStringConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for VariantStringConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        ser.serialize_str(variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        VariantIdent::Name
+    }
+}
+
+/// Config wrapper that overrides enum serialization by packing variants with data as a single-entry map `{ident: data}`.
+///
+/// This is the default, also known as externally tagged.
+#[derive(Copy, Clone, Debug)]
+pub struct EnumMapConfig<C>(C);
+
+impl<C> EnumMapConfig<C> {
+    /// Creates a `EnumMapConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for EnumMapConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        encode::write_map_len(ser.get_mut(), 1)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that overrides enum serialization by packing variants with data as a pair `[ident, data]`.
+///
+/// This is the layout expected by many RPC peers. Unit variants are still written as their
+/// identifier alone, and the deserializer accepts either layout.
+#[derive(Copy, Clone, Debug)]
+pub struct EnumArrayConfig<C>(C);
+
+impl<C> EnumArrayConfig<C> {
+    /// Creates a `EnumArrayConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for EnumArrayConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        encode::write_array_len(ser.get_mut(), 2)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that encodes `Some(value)` as a single-element array `[value]`, so that nested
+/// options such as `Some(None)` and options of unit such as `Some(())` round-trip exactly.
+///
+/// `None` is still encoded as nil. The deserializer then expects options to be encoded this way,
+/// which is incompatible with the default encoding.
+#[derive(Copy, Clone, Debug)]
+pub struct WrappedOptionConfig<C>(C);
+
+impl<C> WrappedOptionConfig<C> {
+    /// Creates a `WrappedOptionConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for WrappedOptionConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that omits fields of structs serialized as a map whose value is nil, such as
+/// `None`, instead of writing them as `key: nil`.
+///
+/// Deserializing such a struct treats the missing fields of `Option` type as `None`. Fields of
+/// other types encoded as nil, like `()`, are omitted as well, so they should be marked with
+/// `#[serde(default)]`. Structs serialized as a tuple are not affected.
+///
+/// The length of structs serialized as a map is only known once all fields are serialized.
+/// Serializers created with [`with_length_patching`] or [`with_minimal_length_patching`] write the
+/// header for all fields and patch it on `end()`. Other serializers, including those writing to
+/// writers not implementing [`RmpPatch`], buffer such structs in memory.
+///
+/// [`with_length_patching`]: crate::encode::Serializer::with_length_patching
+/// [`with_minimal_length_patching`]: crate::encode::Serializer::with_minimal_length_patching
+/// [`RmpPatch`]: rmp::encode::RmpPatch
+#[derive(Copy, Clone, Debug)]
+pub struct SkipNoneConfig<C>(C);
+
+impl<C> SkipNoneConfig<C> {
+    /// Creates a `SkipNoneConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline(always)]
+    pub fn new(inner: C) -> Self {
+        Self(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for SkipNoneConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    #[inline]
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_len(ser, len)
+    }
+
+    #[inline]
+    fn write_struct_key<S>(ser: &mut S, idx: u32, key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_struct_key(ser, idx, key)
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        C::struct_layout()
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
+}
+
+/// Config wrapper that overrides struct serialization by packing as a map with field indices as
+/// keys, instead of field names.
+///
+/// This is almost as compact as a tuple, while fields can still be added and skipped like with
+/// field names, as long as existing fields keep their position. Fields skipped with
+/// `#[serde(skip_serializing_if = "...")]` keep the indices of the following fields unchanged,
+/// unlike fields skipped with `#[serde(skip)]`.
+///
+/// The deserializer maps integer keys of structs encoded as maps back to field names.
+///
+/// Fields must be skipped either with `#[serde(skip)]` or with
+/// `#[serde(skip_serializing_if = "...")]`. A field with only `#[serde(skip_serializing)]` is
+/// left out of the indices when serializing but not when deserializing, so the following fields
+/// are decoded into the wrong fields.
+#[derive(Copy, Clone, Debug)]
+pub struct StructIndexConfig<C>(C);
+
+impl<C> StructIndexConfig<C> {
+    /// Creates a `StructIndexConfig` inheriting unchanged configuration options from the given configuration.
+    #[inline]
+    pub fn new(inner: C) -> Self {
+        StructIndexConfig(inner)
+    }
+}
+
+impl<C> sealed::SerializerConfig for StructIndexConfig<C>
+where
+    C: sealed::SerializerConfig,
+{
+    fn write_struct_len<S>(ser: &mut S, len: usize) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        encode::write_map_len(ser.get_mut(), len as u32)?;
+
+        Ok(())
+    }
+
+    fn write_struct_key<S>(ser: &mut S, idx: u32, _key: &'static str) -> Result<usize, Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        encode::write_uint(ser.get_mut(), idx.into())?;
+
+        Ok(encode::size_of_uint(idx.into()))
+    }
+
+    #[inline]
+    fn write_variant_header<S>(ser: &mut S) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_header(ser)
+    }
+
+    #[inline]
+    fn write_variant_ident<S>(
+        ser: &mut S,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>
+    where
+        S: UnderlyingWrite,
+        for<'a> &'a mut S: Serializer<Ok = (), Error = Error>,
+    {
+        C::write_variant_ident(ser, variant_index, variant)
+    }
+
+    #[inline(always)]
+    fn is_human_readable() -> bool {
+        C::is_human_readable()
+    }
+
+    #[inline(always)]
+    fn is_canonical() -> bool {
+        C::is_canonical()
+    }
+
+    #[inline(always)]
+    fn wraps_options() -> bool {
+        C::wraps_options()
+    }
+
+    #[inline(always)]
+    fn skips_none() -> bool {
+        C::skips_none()
+    }
+
+    #[inline(always)]
+    fn struct_layout() -> StructLayout {
+        StructLayout::Map
+    }
+
+    #[inline(always)]
+    fn variant_ident() -> VariantIdent {
+        C::variant_ident()
+    }
 }
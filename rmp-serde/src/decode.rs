@@ -4,19 +4,29 @@ use std::convert::TryInto;
 use std::error;
 use std::fmt::{self, Display, Formatter};
 use std::io::{self, Cursor, ErrorKind, Read};
+use std::mem;
 use std::num::TryFromIntError;
 use std::str::{self, Utf8Error};
 
 use byteorder::{self, ReadBytesExt};
 
 use serde;
+use serde::de::value::{BorrowedStrDeserializer, U64Deserializer};
 use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, Unexpected, Visitor};
 
 use rmp;
-use rmp::decode::{self, RmpRead, DecodeStringError, MarkerReadError, NumValueReadError, ValueReadError};
+use rmp::decode::{
+    self, RmpRead, DecodeStringError, MarkerReadError, NumValueReadError, ReaderError,
+    SkipValueError, ValueReadError,
+};
 use rmp::Marker;
 
-use crate::config::{BinaryConfig, DefaultConfig, HumanReadableConfig, SerializerConfig};
+pub use rmp::decode::{DecodeLimits, Limit, LocatedError, Path, PathSegment};
+
+use crate::config::{
+    BinaryConfig, DefaultConfig, HumanReadableConfig, SerializerConfig, StructLayout, StructMapConfig,
+    StructTupleConfig, VariantIdent, VariantIntegerConfig, VariantStringConfig, WrappedOptionConfig,
+};
 use crate::MSGPACK_EXT_STRUCT_NAME;
 
 /// Enum representing errors that can occur while decoding MessagePack data.
@@ -44,6 +54,8 @@ pub enum Error {
     Utf8Error(Utf8Error),
     /// The depth limit was exceeded.
     DepthLimitExceeded,
+    /// A limit set by [`Deserializer::set_limits`] was exceeded.
+    LimitExceeded(Limit),
 }
 
 macro_rules! depth_count(
@@ -73,6 +85,29 @@ impl error::Error for Error {
             Error::Syntax(..) => None,
             Error::Utf8Error(ref err) => Some(err),
             Error::DepthLimitExceeded => None,
+            Error::LimitExceeded(..) => None,
+        }
+    }
+}
+
+impl Error {
+    /// Wraps an I/O error while reading a marker, unless it was caused by the total bytes limit.
+    #[cold]
+    fn marker_read(err: io::Error) -> Error {
+        if TotalBytesExceeded::caused(&err) {
+            Error::LimitExceeded(Limit::TotalBytes)
+        } else {
+            Error::InvalidMarkerRead(err)
+        }
+    }
+
+    /// Wraps an I/O error while reading data, unless it was caused by the total bytes limit.
+    #[cold]
+    fn data_read(err: io::Error) -> Error {
+        if TotalBytesExceeded::caused(&err) {
+            Error::LimitExceeded(Limit::TotalBytes)
+        } else {
+            Error::InvalidDataRead(err)
         }
     }
 }
@@ -103,6 +138,7 @@ impl Display for Error {
             Error::Syntax(ref msg) => fmt.write_str(msg),
             Error::Utf8Error(ref err) => write!(fmt, "string found to be invalid utf8: {}", err),
             Error::DepthLimitExceeded => fmt.write_str("depth limit exceeded"),
+            Error::LimitExceeded(limit) => write!(fmt, "{} limit exceeded", limit),
         }
     }
 }
@@ -111,7 +147,7 @@ impl From<MarkerReadError> for Error {
     #[cold]
     fn from(err: MarkerReadError) -> Error {
         match err {
-            MarkerReadError(err) => Error::InvalidMarkerRead(err),
+            MarkerReadError(err) => Error::marker_read(err),
         }
     }
 }
@@ -128,8 +164,8 @@ impl From<ValueReadError> for Error {
     fn from(err: ValueReadError) -> Error {
         match err {
             ValueReadError::TypeMismatch(marker) => Error::TypeMismatch(marker),
-            ValueReadError::InvalidMarkerRead(err) => Error::InvalidMarkerRead(err),
-            ValueReadError::InvalidDataRead(err) => Error::InvalidDataRead(err),
+            ValueReadError::InvalidMarkerRead(err) => Error::marker_read(err),
+            ValueReadError::InvalidDataRead(err) => Error::data_read(err),
         }
     }
 }
@@ -139,8 +175,8 @@ impl From<NumValueReadError> for Error {
     fn from(err: NumValueReadError) -> Error {
         match err {
             NumValueReadError::TypeMismatch(marker) => Error::TypeMismatch(marker),
-            NumValueReadError::InvalidMarkerRead(err) => Error::InvalidMarkerRead(err),
-            NumValueReadError::InvalidDataRead(err) => Error::InvalidDataRead(err),
+            NumValueReadError::InvalidMarkerRead(err) => Error::marker_read(err),
+            NumValueReadError::InvalidDataRead(err) => Error::data_read(err),
             NumValueReadError::OutOfRange => Error::OutOfRange,
         }
     }
@@ -150,8 +186,8 @@ impl<'a> From<DecodeStringError<'a>> for Error {
     #[cold]
     fn from(err: DecodeStringError<'_>) -> Error {
         match err {
-            DecodeStringError::InvalidMarkerRead(err) => Error::InvalidMarkerRead(err),
-            DecodeStringError::InvalidDataRead(err) => Error::InvalidDataRead(err),
+            DecodeStringError::InvalidMarkerRead(err) => Error::marker_read(err),
+            DecodeStringError::InvalidDataRead(err) => Error::data_read(err),
             DecodeStringError::TypeMismatch(marker) => Error::TypeMismatch(marker),
             DecodeStringError::BufferSizeTooSmall(..) => Error::Uncategorized("BufferSizeTooSmall".to_string()),
             DecodeStringError::InvalidUtf8(..) => Error::Uncategorized("InvalidUtf8".to_string()),
@@ -159,6 +195,30 @@ impl<'a> From<DecodeStringError<'a>> for Error {
     }
 }
 
+impl From<SkipValueError<io::Error>> for Error {
+    #[cold]
+    fn from(err: SkipValueError<io::Error>) -> Error {
+        match err {
+            SkipValueError::InvalidMarkerRead(err) => Error::marker_read(err),
+            SkipValueError::InvalidDataRead(err) => Error::data_read(err),
+            SkipValueError::TypeMismatch(marker) => Error::TypeMismatch(marker),
+            SkipValueError::DepthLimitExceeded => Error::DepthLimitExceeded,
+        }
+    }
+}
+
+impl From<ReaderError<io::Error>> for Error {
+    #[cold]
+    fn from(err: ReaderError<io::Error>) -> Error {
+        match err {
+            ReaderError::InvalidMarkerRead(err) => Error::marker_read(err),
+            ReaderError::InvalidDataRead(err) => Error::data_read(err),
+            ReaderError::TypeMismatch(marker) => Error::TypeMismatch(marker),
+            ReaderError::DepthLimitExceeded => Error::DepthLimitExceeded,
+        }
+    }
+}
+
 impl From<TryFromIntError> for Error {
     #[cold]
     fn from(_: TryFromIntError) -> Self {
@@ -178,14 +238,57 @@ pub struct Deserializer<R, C = DefaultConfig> {
     config: C,
     marker: Option<Marker>,
     depth: usize,
+    limits: DecodeLimits,
+    tracker: Tracker,
+}
+
+/// Bookkeeping for locating errors, see [`Deserializer::locate`].
+#[derive(Debug, Default)]
+struct Tracker {
+    /// The position of the last marker read, which is the one of the value that failed on errors.
+    marker_offset: u64,
+    /// The path to the value being deserialized, if tracked at all.
+    path: Option<Path>,
+    /// The location recorded by the innermost array or map an error went through.
+    error_at: Option<(u64, Path)>,
+    /// Whether the next value is a map key, to be recorded in `key`.
+    capture_key: bool,
+    key: Option<PathSegment>,
+}
+
+impl Tracker {
+    /// Records an unsigned map key, if it is being captured for the path.
+    #[inline]
+    fn index_key<T: Copy + Into<u64>>(&mut self, capture_key: bool, key: T) -> T {
+        if capture_key {
+            self.capture_key(PathSegment::Index(key.into()));
+        }
+        key
+    }
+
+    /// Records a string map key, if it is being captured for the path.
+    #[inline]
+    fn str_key(&mut self, capture_key: bool, key: &str) {
+        if capture_key {
+            self.capture_key(PathSegment::Key(key.into()));
+        }
+    }
+
+    /// Records a map key out of line, keeping its construction off the default decode path.
+    #[cold]
+    #[inline(never)]
+    fn capture_key(&mut self, key: PathSegment) {
+        self.key = Some(key);
+    }
 }
 
-impl<R: Read, C> Deserializer<R, C> {
+impl<'de, R: ReadSlice<'de>, C> Deserializer<R, C> {
     #[inline]
     fn take_or_read_marker(&mut self) -> Result<Marker, MarkerReadError> {
-        self.marker
-            .take()
-            .map_or_else(|| rmp::decode::read_marker(&mut self.rd), Ok)
+        match self.marker.take() {
+            Some(m) => Ok(m),
+            None => self.read_marker(),
+        }
     }
 
     #[inline]
@@ -193,10 +296,16 @@ impl<R: Read, C> Deserializer<R, C> {
         if let Some(m) = self.marker {
             Ok(m)
         } else {
-            let m = rmp::decode::read_marker(&mut self.rd)?;
+            let m = self.read_marker()?;
             Ok(self.marker.insert(m).to_owned())
         }
     }
+
+    #[inline]
+    fn read_marker(&mut self) -> Result<Marker, MarkerReadError> {
+        self.tracker.marker_offset = self.rd.position();
+        rmp::decode::read_marker(&mut self.rd)
+    }
 }
 
 impl<R: Read> Deserializer<ReadReader<R>, DefaultConfig> {
@@ -209,6 +318,8 @@ impl<R: Read> Deserializer<ReadReader<R>, DefaultConfig> {
             // Cached marker in case of deserializing optional values.
             marker: None,
             depth: 1024,
+            limits: DecodeLimits::new(),
+            tracker: Tracker::default(),
         }
     }
 }
@@ -241,12 +352,14 @@ impl<R: Read, C: SerializerConfig> Deserializer<R, C> {
     /// versions of `rmp-serde`.
     #[inline]
     pub fn with_human_readable(self) -> Deserializer<R, HumanReadableConfig<C>> {
-        let Deserializer { rd, config, marker, depth } = self;
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
         Deserializer {
             rd,
             config: HumanReadableConfig::new(config),
             marker,
             depth,
+            limits,
+            tracker,
         }
     }
 
@@ -257,12 +370,93 @@ impl<R: Read, C: SerializerConfig> Deserializer<R, C> {
     /// representation.
     #[inline]
     pub fn with_binary(self) -> Deserializer<R, BinaryConfig<C>> {
-        let Deserializer { rd, config, marker, depth } = self;
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
         Deserializer {
             rd,
             config: BinaryConfig::new(config),
             marker,
             depth,
+            limits,
+            tracker,
+        }
+    }
+
+    /// Consumes this deserializer and returns a new one, which will only accept structs encoded
+    /// as a map with field names, failing with `Error::TypeMismatch` on those encoded as a tuple.
+    ///
+    /// By default both encodings are accepted.
+    #[inline]
+    pub fn with_struct_map(self) -> Deserializer<R, StructMapConfig<C>> {
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
+        Deserializer {
+            rd,
+            config: StructMapConfig::new(config),
+            marker,
+            depth,
+            limits,
+            tracker,
+        }
+    }
+
+    /// Consumes this deserializer and returns a new one, which will only accept structs encoded
+    /// as a tuple, failing with `Error::TypeMismatch` on those encoded as a map.
+    #[inline]
+    pub fn with_struct_tuple(self) -> Deserializer<R, StructTupleConfig<C>> {
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
+        Deserializer {
+            rd,
+            config: StructTupleConfig::new(config),
+            marker,
+            depth,
+            limits,
+            tracker,
+        }
+    }
+
+    /// Consumes this deserializer and returns a new one, which will only accept enum variants
+    /// identified by their index, failing with `Error::TypeMismatch` on those identified by name.
+    ///
+    /// By default both identifiers are accepted.
+    #[inline]
+    pub fn with_integer_variants(self) -> Deserializer<R, VariantIntegerConfig<C>> {
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
+        Deserializer {
+            rd,
+            config: VariantIntegerConfig::new(config),
+            marker,
+            depth,
+            limits,
+            tracker,
+        }
+    }
+
+    /// Consumes this deserializer and returns a new one, which will only accept enum variants
+    /// identified by their name, failing with `Error::TypeMismatch` on those identified by index.
+    #[inline]
+    pub fn with_string_variants(self) -> Deserializer<R, VariantStringConfig<C>> {
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
+        Deserializer {
+            rd,
+            config: VariantStringConfig::new(config),
+            marker,
+            depth,
+            limits,
+            tracker,
+        }
+    }
+
+    /// Consumes this deserializer and returns a new one, which will expect `Some(value)` to be
+    /// encoded as a single-element array `[value]`, as done by `Serializer::with_wrapped_options`.
+    #[inline]
+    pub fn with_wrapped_options(self) -> Deserializer<R, WrappedOptionConfig<C>> {
+        let Deserializer { rd, config, marker, depth, limits, tracker } = self;
+        Deserializer {
+            rd,
+            config: WrappedOptionConfig::new(config),
+            marker,
+            depth,
+            limits,
+            tracker,
         }
     }
 }
@@ -287,6 +481,8 @@ where
             config: DefaultConfig,
             marker: None,
             depth: 1024,
+            limits: DecodeLimits::new(),
+            tracker: Tracker::default(),
         }
     }
 
@@ -304,13 +500,282 @@ impl<'de, R: ReadSlice<'de>, C: SerializerConfig> Deserializer<R, C> {
         self.depth = depth;
     }
 
-    fn read_str_data<V>(&mut self, len: u32, visitor: V) -> Result<V::Value, Error>
+    /// Rejects values exceeding the given limits with [`Error::LimitExceeded`], replacing the
+    /// maximum nesting depth of arrays and maps with `limits.max_depth`.
+    ///
+    /// Declared lengths are checked before reading or allocating anything for the value, so this
+    /// is suitable for deserializing untrusted input. The total number of bytes is counted from
+    /// this call on, across all values read by this deserializer. Values skipped as
+    /// [`IgnoredAny`](de::IgnoredAny) are never allocated, so only the depth and the total number
+    /// of bytes are limited for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp_serde::decode::{DecodeLimits, Error, Limit};
+    /// use rmp_serde::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// // A string declaring 4 GiB of data.
+    /// let buf = [0xdb, 0xff, 0xff, 0xff, 0xff];
+    ///
+    /// let mut de = Deserializer::new(&buf[..]);
+    /// de.set_limits(DecodeLimits { max_str_len: 1024, ..DecodeLimits::default() });
+    ///
+    /// match String::deserialize(&mut de) {
+    ///     Err(Error::LimitExceeded(Limit::StrLen)) => {}
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn set_limits(&mut self, limits: DecodeLimits) {
+        // The depth counter fails once it reaches zero, so it allows one level less than its value.
+        self.depth = limits.max_depth.saturating_add(1);
+        self.rd.limit_total_bytes(limits.max_total_bytes);
+        self.limits = limits;
+    }
+
+    /// Keeps track of the path to the value being deserialized, so that [`Deserializer::locate`]
+    /// can report it, which is disabled by default because it slows deserialization down.
+    ///
+    /// Struct fields are named in the path even if the struct is encoded as an array.
+    pub fn set_track_path(&mut self, track: bool) {
+        self.tracker.path = if track { Some(Path::new()) } else { None };
+    }
+
+    /// Attaches to an error returned by this deserializer where it occurred, that is the offset
+    /// of the marker of the value that failed from where this deserializer started reading, and
+    /// the path to that value if [tracked](Deserializer::set_track_path).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use rmp_serde::decode::Error;
+    /// use rmp_serde::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// // {"a": [1, "two"]}
+    /// let buf = [0x81, 0xa1, 0x61, 0x92, 0x01, 0xa3, 0x74, 0x77, 0x6f];
+    ///
+    /// let mut de = Deserializer::new(&buf[..]);
+    /// de.set_track_path(true);
+    ///
+    /// let err = BTreeMap::<String, Vec<u8>>::deserialize(&mut de).unwrap_err();
+    /// let err = de.locate(err);
+    /// assert!(matches!(err.error, Error::Syntax(..)));
+    /// assert_eq!(5, err.offset);
+    /// assert_eq!(".a[1]", err.path.to_string());
+    /// ```
+    pub fn locate(&mut self, error: Error) -> LocatedError<Error> {
+        let tracker = &mut self.tracker;
+        let (offset, path) = tracker.error_at.take()
+            .unwrap_or_else(|| (tracker.marker_offset, tracker.path.clone().unwrap_or_default()));
+
+        LocatedError { error, offset, path }
+    }
+
+    /// Deserializes a nested value with `f`, stepping into it along the tracked path.
+    #[inline]
+    fn nested<T>(&mut self, segment: PathSegment, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        match self.tracker.path {
+            Some(ref mut path) => path.push(segment),
+            None => return f(self),
+        }
+        let res = f(self);
+
+        let tracker = &mut self.tracker;
+        if let Some(ref mut path) = tracker.path {
+            if res.is_err() {
+                if tracker.error_at.is_none() {
+                    tracker.error_at = Some((tracker.marker_offset, path.clone()));
+                }
+            } else {
+                // An error from deeper down may have been recovered from.
+                tracker.error_at = None;
+            }
+            path.pop();
+        }
+        res
+    }
+
+    /// Deserializes any value, naming the elements of arrays with `fields` if it is a struct.
+    #[inline(never)]
+    fn any_value<V>(&mut self, visitor: V, fields: Option<&'static [&'static str]>) -> Result<V::Value, Error>
+        where V: Visitor<'de>
+    {
+        // Only this value may be a map key, not those nested in it.
+        let capture_key = self.tracker.capture_key;
+        if capture_key {
+            self.tracker.capture_key = false;
+        }
+        let marker = self.take_or_read_marker()?;
+
+        match marker {
+            Marker::Null => visitor.visit_unit(),
+            Marker::True |
+            Marker::False => visitor.visit_bool(marker == Marker::True),
+            Marker::FixPos(val) => visitor.visit_u8(self.tracker.index_key(capture_key, val)),
+            Marker::FixNeg(val) => visitor.visit_i8(val),
+            Marker::U8 => visitor.visit_u8(self.tracker.index_key(capture_key, self.rd.read_data_u8()?)),
+            Marker::U16 => visitor.visit_u16(self.tracker.index_key(capture_key, self.rd.read_data_u16()?)),
+            Marker::U32 => visitor.visit_u32(self.tracker.index_key(capture_key, self.rd.read_data_u32()?)),
+            Marker::U64 => visitor.visit_u64(self.tracker.index_key(capture_key, self.rd.read_data_u64()?)),
+            Marker::I8 => visitor.visit_i8(self.rd.read_data_i8()?),
+            Marker::I16 => visitor.visit_i16(self.rd.read_data_i16()?),
+            Marker::I32 => visitor.visit_i32(self.rd.read_data_i32()?),
+            Marker::I64 => visitor.visit_i64(self.rd.read_data_i64()?),
+            Marker::F32 => visitor.visit_f32(self.rd.read_data_f32()?),
+            Marker::F64 => visitor.visit_f64(self.rd.read_data_f64()?),
+            Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
+                let len = match marker {
+                    Marker::FixStr(len) => Ok(len.into()),
+                    Marker::Str8 => read_u8(&mut self.rd).map(u32::from),
+                    Marker::Str16 => read_u16(&mut self.rd).map(u32::from),
+                    Marker::Str32 => read_u32(&mut self.rd).map(u32::from),
+                    _ => unreachable!()
+                }?;
+                self.check_len(Limit::StrLen, len)?;
+                self.read_str_data(len, capture_key, visitor)
+            }
+            Marker::FixArray(_) |
+            Marker::Array16 |
+            Marker::Array32 => {
+                let len = match marker {
+                    Marker::FixArray(len) => len.into(),
+                    Marker::Array16 => read_u16(&mut self.rd)?.into(),
+                    Marker::Array32 => read_u32(&mut self.rd)?,
+                    _ => unreachable!(),
+                };
+                self.check_len(Limit::ArrayLen, len)?;
+
+                depth_count!(self.depth, {
+                    let mut seq = SeqAccess::new(self, len, fields);
+                    let res = visitor.visit_seq(&mut seq)?;
+                    match seq.left {
+                        0 => Ok(res),
+                        excess => Err(Error::LengthMismatch(len - excess)),
+                    }
+                })
+            }
+            Marker::FixMap(_) |
+            Marker::Map16 |
+            Marker::Map32 => {
+                let len = match marker {
+                    Marker::FixMap(len) => len.into(),
+                    Marker::Map16 => read_u16(&mut self.rd)?.into(),
+                    Marker::Map32 => read_u32(&mut self.rd)?,
+                    _ => unreachable!()
+                };
+                self.check_len(Limit::MapLen, len)?;
+
+                depth_count!(self.depth, {
+                    let mut seq = MapAccess::new(self, len, fields);
+                    let res = visitor.visit_map(&mut seq)?;
+                    match seq.left {
+                        0 => Ok(res),
+                        excess => Err(Error::LengthMismatch(len - excess)),
+                    }
+                })
+            }
+            Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => {
+                let len = match marker {
+                    Marker::Bin8 => read_u8(&mut self.rd).map(u32::from),
+                    Marker::Bin16 => read_u16(&mut self.rd).map(u32::from),
+                    Marker::Bin32 => read_u32(&mut self.rd).map(u32::from),
+                    _ => unreachable!()
+                }?;
+                self.check_len(Limit::BinLen, len)?;
+                match read_bin_data(&mut self.rd, len)? {
+                    Reference::Borrowed(buf) => visitor.visit_borrowed_bytes(buf),
+                    Reference::Copied(buf) => visitor.visit_bytes(buf),
+                }
+            }
+            Marker::FixExt1 |
+            Marker::FixExt2 |
+            Marker::FixExt4 |
+            Marker::FixExt8 |
+            Marker::FixExt16 |
+            Marker::Ext8 |
+            Marker::Ext16 |
+            Marker::Ext32 => {
+                let len = ext_len(&mut self.rd, marker)?;
+                self.check_len(Limit::ExtLen, len)?;
+                visitor.visit_newtype_struct(ExtDeserializer::new(self, len))
+            }
+            Marker::Reserved => Err(Error::TypeMismatch(Marker::Reserved)),
+        }
+    }
+
+    /// Deserializes a map key, which is mapped to the field name if it is the index of a field of
+    /// the struct being deserialized.
+    fn map_key<K>(&mut self, fields: Option<&'static [&'static str]>, seed: K) -> Result<K::Value, Error>
+        where K: DeserializeSeed<'de>
+    {
+        let fields = match fields {
+            Some(fields) => fields,
+            None => return seed.deserialize(self),
+        };
+        let idx = match self.peek_or_read_marker()? {
+            Marker::FixPos(idx) => u64::from(idx),
+            Marker::U8 => u64::from(self.rd.read_data_u8()?),
+            Marker::U16 => u64::from(self.rd.read_data_u16()?),
+            Marker::U32 => u64::from(self.rd.read_data_u32()?),
+            Marker::U64 => self.rd.read_data_u64()?,
+            _ => return seed.deserialize(self),
+        };
+        self.marker = None;
+
+        let capture_key = mem::take(&mut self.tracker.capture_key);
+        match usize::try_from(idx).ok().and_then(|idx| fields.get(idx)) {
+            Some(&name) => {
+                if capture_key {
+                    self.tracker.key = Some(PathSegment::Field(name));
+                }
+                seed.deserialize(BorrowedStrDeserializer::new(name))
+            }
+            None => {
+                self.tracker.index_key(capture_key, idx);
+                seed.deserialize(U64Deserializer::new(idx))
+            }
+        }
+    }
+
+    /// Rejects the identifier of the enum variant about to be deserialized if the config does.
+    #[inline]
+    fn check_variant_ident(&mut self) -> Result<(), Error> {
+        let ident = C::variant_ident();
+        if ident == VariantIdent::Any {
+            return Ok(());
+        }
+        let marker = self.peek_or_read_marker()?;
+        let rejected = match marker {
+            Marker::FixPos(..) | Marker::U8 | Marker::U16 | Marker::U32 | Marker::U64 => ident == VariantIdent::Name,
+            Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32 => ident == VariantIdent::Index,
+            _ => false,
+        };
+        if rejected {
+            self.marker = None;
+            return Err(Error::TypeMismatch(marker));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn check_len(&self, limit: Limit, len: u32) -> Result<(), Error> {
+        self.limits.check(limit, len.into()).map_err(Error::LimitExceeded)
+    }
+
+    fn read_str_data<V>(&mut self, len: u32, capture_key: bool, visitor: V) -> Result<V::Value, Error>
         where V: Visitor<'de>
     {
         match read_bin_data(&mut self.rd, len)? {
             Reference::Borrowed(buf) => {
                 match str::from_utf8(buf) {
-                    Ok(s) => visitor.visit_borrowed_str(s),
+                    Ok(s) => {
+                        self.tracker.str_key(capture_key, s);
+                        visitor.visit_borrowed_str(s)
+                    }
                     Err(err) => {
                         // Allow to unpack invalid UTF-8 bytes into a byte array.
                         match visitor.visit_borrowed_bytes::<Error>(buf) {
@@ -322,7 +787,10 @@ impl<'de, R: ReadSlice<'de>, C: SerializerConfig> Deserializer<R, C> {
             }
             Reference::Copied(buf) => {
                 match str::from_utf8(buf) {
-                    Ok(s) => visitor.visit_str(s),
+                    Ok(s) => {
+                        self.tracker.str_key(capture_key, s);
+                        visitor.visit_str(s)
+                    }
                     Err(err) => {
                         // Allow to unpack invalid UTF-8 bytes into a byte array.
                         match visitor.visit_bytes::<Error>(buf) {
@@ -358,21 +826,21 @@ impl<'de, R: ReadSlice<'de>, C: SerializerConfig> Deserializer<R, C> {
 }
 
 fn read_bin_data<'a, 'de, R: ReadSlice<'de>>(rd: &'a mut R, len: u32) -> Result<Reference<'de,'a, [u8]>, Error> {
-    rd.read_slice(len as usize).map_err(Error::InvalidDataRead)
+    rd.read_slice(len as usize).map_err(Error::data_read)
 }
 
 fn read_u8<R: Read>(rd: &mut R) -> Result<u8, Error> {
-    byteorder::ReadBytesExt::read_u8(rd).map_err(Error::InvalidDataRead)
+    byteorder::ReadBytesExt::read_u8(rd).map_err(Error::data_read)
 }
 
 fn read_u16<R: Read>(rd: &mut R) -> Result<u16, Error> {
     rd.read_u16::<byteorder::BigEndian>()
-        .map_err(Error::InvalidDataRead)
+        .map_err(Error::data_read)
 }
 
 fn read_u32<R: Read>(rd: &mut R) -> Result<u32, Error> {
     rd.read_u32::<byteorder::BigEndian>()
-        .map_err(Error::InvalidDataRead)
+        .map_err(Error::data_read)
 }
 
 fn ext_len<R: Read>(rd: &mut R, marker: Marker) -> Result<u32, Error> {
@@ -462,7 +930,7 @@ impl<'de, 'a, R: ReadSlice<'de> + 'a, C: SerializerConfig> de::Deserializer<'de>
                 visitor.visit_i8(tag)
             }
             ExtDeserializerState::ReadTag => {
-                let data = self.rd.read_slice(self.len as usize).map_err(Error::InvalidDataRead)?;
+                let data = self.rd.read_slice(self.len as usize).map_err(Error::data_read)?;
                 self.state = ExtDeserializerState::ReadBinary;
                 match data {
                     Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
@@ -488,101 +956,11 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> serde::Deserializer<'de> f
         C::is_human_readable()
     }
 
-    #[inline(never)]
+    #[inline(always)]
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where V: Visitor<'de>
     {
-        let marker = self.take_or_read_marker()?;
-
-        match marker {
-            Marker::Null => visitor.visit_unit(),
-            Marker::True |
-            Marker::False => visitor.visit_bool(marker == Marker::True),
-            Marker::FixPos(val) => visitor.visit_u8(val),
-            Marker::FixNeg(val) => visitor.visit_i8(val),
-            Marker::U8 => visitor.visit_u8(self.rd.read_data_u8()?),
-            Marker::U16 => visitor.visit_u16(self.rd.read_data_u16()?),
-            Marker::U32 => visitor.visit_u32(self.rd.read_data_u32()?),
-            Marker::U64 => visitor.visit_u64(self.rd.read_data_u64()?),
-            Marker::I8 => visitor.visit_i8(self.rd.read_data_i8()?),
-            Marker::I16 => visitor.visit_i16(self.rd.read_data_i16()?),
-            Marker::I32 => visitor.visit_i32(self.rd.read_data_i32()?),
-            Marker::I64 => visitor.visit_i64(self.rd.read_data_i64()?),
-            Marker::F32 => visitor.visit_f32(self.rd.read_data_f32()?),
-            Marker::F64 => visitor.visit_f64(self.rd.read_data_f64()?),
-            Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
-                let len = match marker {
-                    Marker::FixStr(len) => Ok(len.into()),
-                    Marker::Str8 => read_u8(&mut self.rd).map(u32::from),
-                    Marker::Str16 => read_u16(&mut self.rd).map(u32::from),
-                    Marker::Str32 => read_u32(&mut self.rd).map(u32::from),
-                    _ => unreachable!()
-                }?;
-                self.read_str_data(len, visitor)
-            }
-            Marker::FixArray(_) |
-            Marker::Array16 |
-            Marker::Array32 => {
-                let len = match marker {
-                    Marker::FixArray(len) => len.into(),
-                    Marker::Array16 => read_u16(&mut self.rd)?.into(),
-                    Marker::Array32 => read_u32(&mut self.rd)?,
-                    _ => unreachable!(),
-                };
-
-                depth_count!(self.depth, {
-                    let mut seq = SeqAccess::new(self, len);
-                    let res = visitor.visit_seq(&mut seq)?;
-                    match seq.left {
-                        0 => Ok(res),
-                        excess => Err(Error::LengthMismatch(len - excess)),
-                    }
-                })
-            }
-            Marker::FixMap(_) |
-            Marker::Map16 |
-            Marker::Map32 => {
-                let len = match marker {
-                    Marker::FixMap(len) => len.into(),
-                    Marker::Map16 => read_u16(&mut self.rd)?.into(),
-                    Marker::Map32 => read_u32(&mut self.rd)?,
-                    _ => unreachable!()
-                };
-
-                depth_count!(self.depth, {
-                    let mut seq = MapAccess::new(self, len);
-                    let res = visitor.visit_map(&mut seq)?;
-                    match seq.left {
-                        0 => Ok(res),
-                        excess => Err(Error::LengthMismatch(len - excess)),
-                    }
-                })
-            }
-            Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => {
-                let len = match marker {
-                    Marker::Bin8 => read_u8(&mut self.rd).map(u32::from),
-                    Marker::Bin16 => read_u16(&mut self.rd).map(u32::from),
-                    Marker::Bin32 => read_u32(&mut self.rd).map(u32::from),
-                    _ => unreachable!()
-                }?;
-                match read_bin_data(&mut self.rd, len)? {
-                    Reference::Borrowed(buf) => visitor.visit_borrowed_bytes(buf),
-                    Reference::Copied(buf) => visitor.visit_bytes(buf),
-                }
-            }
-            Marker::FixExt1 |
-            Marker::FixExt2 |
-            Marker::FixExt4 |
-            Marker::FixExt8 |
-            Marker::FixExt16 |
-            Marker::Ext8 |
-            Marker::Ext16 |
-            Marker::Ext32 => {
-                let len = ext_len(&mut self.rd, marker)?;
-                depth_count!(self.depth, visitor.visit_newtype_struct(ExtDeserializer::new(self, len)))
-            }
-            Marker::Reserved => Err(Error::TypeMismatch(Marker::Reserved)),
-        }
+        self.any_value(visitor, None)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -599,10 +977,17 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> serde::Deserializer<'de> f
         // # Note
         //
         // Round trips of Options where `Option<t> = None` such as `Some(None)` will fail because
-        // they are just seriialized as `nil`. The serialization format has probably to be changed
-        // to solve this. But as serde_json behaves the same, I think it's not worth doing this.
+        // they are just seriialized as `nil`, unless `Some` is wrapped in an array by the config.
         let marker = self.take_or_read_marker()?;
 
+        if C::wraps_options() {
+            return match marker {
+                Marker::Null => visitor.visit_none(),
+                Marker::FixArray(1) => depth_count!(self.depth, visitor.visit_some(&mut *self)),
+                marker => Err(Error::TypeMismatch(marker)),
+            };
+        }
+
         if marker == Marker::Null {
             visitor.visit_none()
         } else {
@@ -615,21 +1000,23 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> serde::Deserializer<'de> f
     fn deserialize_enum<V>(self, _name: &str, _variants: &[&str], visitor: V) -> Result<V::Value, Error>
         where V: Visitor<'de>
     {
-        let marker = self.peek_or_read_marker()?;
-        match rmp::decode::marker_to_len(&mut self.rd, marker) {
-            Ok(len) => match len {
-                // Enums are either encoded as maps with a single K/V pair
-                // where the K = the variant & V = associated data
-                // or as just the variant
-                1 => {
-                    self.marker = None;
-                    visitor.visit_enum(VariantAccess::new(self))
-                }
-                n => Err(Error::LengthMismatch(n)),
-            },
-            // TODO: Check this is a string
-            Err(_) => visitor.visit_enum(UnitVariantAccess::new(self)),
+        // Enums are either encoded as maps with a single K/V pair where the K = the variant & V =
+        // associated data, as arrays [variant, data], or as just the variant.
+        let (len, expected) = match self.peek_or_read_marker()? {
+            Marker::FixMap(len) => (len.into(), 1),
+            Marker::Map16 => (read_u16(&mut self.rd)?.into(), 1),
+            Marker::Map32 => (read_u32(&mut self.rd)?, 1),
+            Marker::FixArray(len) => (len.into(), 2),
+            Marker::Array16 => (read_u16(&mut self.rd)?.into(), 2),
+            Marker::Array32 => (read_u32(&mut self.rd)?, 2),
+            // The variant is checked by the visitor.
+            _ => return visitor.visit_enum(UnitVariantAccess::new(self)),
+        };
+        self.marker = None;
+        if len != expected {
+            return Err(Error::LengthMismatch(len));
         }
+        visitor.visit_enum(VariantAccess::new(self))
     }
 
     fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
@@ -639,6 +1026,7 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> serde::Deserializer<'de> f
             let marker = self.take_or_read_marker()?;
 
             let len = ext_len(&mut self.rd, marker)?;
+            self.check_len(Limit::ExtLen, len)?;
             let ext_de = ExtDeserializer::new(self, len);
             return visitor.visit_newtype_struct(ext_de);
         }
@@ -679,25 +1067,63 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> serde::Deserializer<'de> f
         visitor.visit_u128(u128::from_be_bytes(buf))
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        // Skip the value without decoding it, which avoids allocating strings and byte buffers.
+        // The depth counter fails once it reaches zero, so it allows one level less than its value.
+        let marker = self.take_or_read_marker()?;
+        rmp::decode::skip_value_from_marker(&mut self.rd, marker, self.depth.saturating_sub(1))?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_struct<V>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let layout = C::struct_layout();
+        if layout != StructLayout::Any {
+            let marker = self.peek_or_read_marker()?;
+            let rejected = match marker {
+                Marker::FixArray(..) | Marker::Array16 | Marker::Array32 => layout == StructLayout::Map,
+                Marker::FixMap(..) | Marker::Map16 | Marker::Map32 => layout == StructLayout::Tuple,
+                _ => false,
+            };
+            if rejected {
+                self.marker = None;
+                return Err(Error::TypeMismatch(marker));
+            }
+        }
+
+        // Name the fields in the path if the struct is encoded as an array, or its keys if they
+        // are field indices.
+        self.any_value(visitor, Some(fields))
+    }
+
     forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32
         f64 char str string bytes byte_buf unit
-        seq map struct identifier tuple
-        tuple_struct ignored_any
+        seq map identifier tuple
+        tuple_struct
     }
 }
 
 struct SeqAccess<'a, R, C> {
     de: &'a mut Deserializer<R, C>,
     left: u32,
+    idx: u32,
+    fields: Option<&'static [&'static str]>,
 }
 
 impl<'a, R: 'a, C> SeqAccess<'a, R, C> {
     #[inline]
-    fn new(de: &'a mut Deserializer<R, C>, len: u32) -> Self {
+    fn new(de: &'a mut Deserializer<R, C>, len: u32, fields: Option<&'static [&'static str]>) -> Self {
         SeqAccess {
             de,
             left: len,
+            idx: 0,
+            fields,
         }
     }
 }
@@ -711,7 +1137,15 @@ impl<'de, 'a, R: ReadSlice<'de> + 'a, C: SerializerConfig> de::SeqAccess<'de> fo
     {
         if self.left > 0 {
             self.left -= 1;
-            Ok(Some(seed.deserialize(&mut *self.de)?))
+            if self.de.tracker.path.is_none() {
+                return seed.deserialize(&mut *self.de).map(Some);
+            }
+            let segment = match self.fields.and_then(|fields| fields.get(self.idx as usize)) {
+                Some(name) => PathSegment::Field(name),
+                None => PathSegment::Index(self.idx.into()),
+            };
+            self.idx += 1;
+            Ok(Some(self.de.nested(segment, |de| seed.deserialize(de))?))
         } else {
             Ok(None)
         }
@@ -726,13 +1160,15 @@ impl<'de, 'a, R: ReadSlice<'de> + 'a, C: SerializerConfig> de::SeqAccess<'de> fo
 struct MapAccess<'a, R, C> {
     de: &'a mut Deserializer<R, C>,
     left: u32,
+    fields: Option<&'static [&'static str]>,
 }
 
 impl<'a, R: 'a, C> MapAccess<'a, R, C> {
-    fn new(de: &'a mut Deserializer<R, C>, len: u32) -> Self {
+    fn new(de: &'a mut Deserializer<R, C>, len: u32, fields: Option<&'static [&'static str]>) -> Self {
         MapAccess {
             de,
             left: len,
+            fields,
         }
     }
 }
@@ -746,7 +1182,15 @@ impl<'de, 'a, R: ReadSlice<'de> + 'a, C: SerializerConfig> de::MapAccess<'de> fo
     {
         if self.left > 0 {
             self.left -= 1;
-            seed.deserialize(&mut *self.de).map(Some)
+            let fields = self.fields;
+            if self.de.tracker.path.is_none() {
+                return self.de.map_key(fields, seed).map(Some);
+            }
+            self.de.tracker.capture_key = true;
+            self.de.tracker.key = None;
+            let res = self.de.nested(PathSegment::Other, |de| de.map_key(fields, seed));
+            self.de.tracker.capture_key = false;
+            res.map(Some)
         } else {
             Ok(None)
         }
@@ -756,7 +1200,11 @@ impl<'de, 'a, R: ReadSlice<'de> + 'a, C: SerializerConfig> de::MapAccess<'de> fo
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
         where V: DeserializeSeed<'de>
     {
-        seed.deserialize(&mut *self.de)
+        if self.de.tracker.path.is_none() {
+            return seed.deserialize(&mut *self.de);
+        }
+        let segment = self.de.tracker.key.take().unwrap_or(PathSegment::Other);
+        self.de.nested(segment, |de| seed.deserialize(de))
     }
 
     #[inline(always)]
@@ -786,6 +1234,7 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> de::EnumAccess<'de>
     where
         V: de::DeserializeSeed<'de>,
     {
+        self.de.check_variant_ident()?;
         let variant = seed.deserialize(&mut *self.de)?;
         Ok((variant, self))
     }
@@ -853,6 +1302,7 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> de::EnumAccess<'de> for Va
     fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
         where V: de::DeserializeSeed<'de>,
     {
+        self.de.check_variant_ident()?;
         Ok((seed.deserialize(&mut *self.de)?, self))
     }
 }
@@ -884,7 +1334,7 @@ impl<'de, 'a, R: ReadSlice<'de>, C: SerializerConfig> de::VariantAccess<'de> for
     fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error>
         where V: Visitor<'de>
     {
-        de::Deserializer::deserialize_tuple(self.de, fields.len(), visitor)
+        de::Deserializer::deserialize_struct(self.de, "", fields, visitor)
     }
 }
 
@@ -903,6 +1353,91 @@ pub enum Reference<'b, 'c, T: ?Sized + 'static> {
 pub trait ReadSlice<'de>: Read {
     /// Reads the exact number of bytes from the underlying byte-array.
     fn read_slice<'a>(&'a mut self, len: usize) -> Result<Reference<'de, 'a, [u8]>, io::Error>;
+
+    /// Fails all reads once the given number of bytes has been read from now on, reporting it as
+    /// [`Limit::TotalBytes`] being exceeded.
+    ///
+    /// Does nothing by default.
+    #[inline]
+    fn limit_total_bytes(&mut self, max: u64) {
+        let _ = max;
+    }
+
+    /// Returns the number of bytes read so far, used to locate errors.
+    ///
+    /// Returns zero by default.
+    #[inline]
+    fn position(&self) -> u64 {
+        0
+    }
+}
+
+/// The position up to which a reader may read, failing reads past it. Readers without a limit
+/// check nothing.
+#[derive(Clone, Copy, Debug)]
+struct ByteLimit {
+    max: u64,
+}
+
+impl ByteLimit {
+    #[inline]
+    fn new() -> Self {
+        ByteLimit { max: u64::MAX }
+    }
+
+    /// Allows reading at most `len` more bytes from `pos` on.
+    #[inline]
+    fn limit(&mut self, pos: u64, len: u64) {
+        self.max = pos.saturating_add(len);
+    }
+
+    /// Checks whether `len` more bytes may be read from `pos`.
+    #[inline(always)]
+    fn check(&self, pos: u64, len: usize) -> io::Result<()> {
+        if self.max != u64::MAX && len as u64 > self.max - pos {
+            return Err(TotalBytesExceeded.into());
+        }
+        Ok(())
+    }
+
+    /// Returns how many of the `len` bytes wanted by a partial read from `pos` may be read.
+    #[inline(always)]
+    fn clamp(&self, pos: u64, len: usize) -> io::Result<usize> {
+        if self.max == u64::MAX {
+            return Ok(len);
+        }
+        let left = self.max - pos;
+        if left == 0 && len > 0 {
+            return Err(TotalBytesExceeded.into());
+        }
+        Ok(len.min(left.try_into().unwrap_or(usize::MAX)))
+    }
+}
+
+/// The I/O error returned by readers once they would read past their limit.
+#[derive(Debug)]
+struct TotalBytesExceeded;
+
+impl TotalBytesExceeded {
+    fn caused(err: &io::Error) -> bool {
+        err.get_ref().is_some_and(|err| err.is::<TotalBytesExceeded>())
+    }
+}
+
+impl Display for TotalBytesExceeded {
+    #[cold]
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt.write_str("total bytes limit exceeded")
+    }
+}
+
+impl error::Error for TotalBytesExceeded {}
+
+impl From<TotalBytesExceeded> for io::Error {
+    #[cold]
+    fn from(err: TotalBytesExceeded) -> io::Error {
+        io::Error::other(err)
+    }
 }
 
 /// Owned reader wrapper.
@@ -910,6 +1445,8 @@ pub trait ReadSlice<'de>: Read {
 pub struct ReadReader<R: Read> {
     rd: R,
     buf: Vec<u8>,
+    pos: u64,
+    limit: ByteLimit,
 }
 
 impl<R: Read> ReadReader<R> {
@@ -918,6 +1455,8 @@ impl<R: Read> ReadReader<R> {
         ReadReader {
             rd,
             buf: Vec::with_capacity(128),
+            pos: 0,
+            limit: ByteLimit::new(),
         }
     }
 }
@@ -925,25 +1464,43 @@ impl<R: Read> ReadReader<R> {
 impl<'de, R: Read> ReadSlice<'de> for ReadReader<R> {
     #[inline]
     fn read_slice<'a>(&'a mut self, len: usize) -> Result<Reference<'de, 'a, [u8]>, io::Error> {
+        self.limit.check(self.pos, len)?;
         self.buf.clear();
         let read = self.rd.by_ref().take(len as u64).read_to_end(&mut self.buf)?;
+        self.pos += read as u64;
         if read != len {
             return Err(io::ErrorKind::UnexpectedEof.into());
         }
 
         Ok(Reference::Copied(&self.buf[..]))
     }
+
+    #[inline]
+    fn limit_total_bytes(&mut self, max: u64) {
+        self.limit.limit(self.pos, max);
+    }
+
+    #[inline]
+    fn position(&self) -> u64 {
+        self.pos
+    }
 }
 
 impl<R: Read> Read for ReadReader<R> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.rd.read(buf)
+        let len = self.limit.clamp(self.pos, buf.len())?;
+        let read = self.rd.read(&mut buf[..len])?;
+        self.pos += read as u64;
+        Ok(read)
     }
 
     #[inline]
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
-        self.rd.read_exact(buf)
+        self.limit.check(self.pos, buf.len())?;
+        self.rd.read_exact(buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
     }
 }
 
@@ -952,13 +1509,9 @@ impl<R: Read> Read for ReadReader<R> {
 pub struct ReadRefReader<'a, R: ?Sized> {
     whole_slice: &'a R,
     buf: &'a [u8],
-}
-
-impl<'a, T> ReadRefReader<'a, T> {
-    /// Returns the part that hasn't been consumed yet
-    pub fn remaining_slice(&self) -> &'a [u8] {
-        self.buf
-    }
+    /// The number of bytes following `buf` cut off by the limit of total bytes, so that reads
+    /// are only checked against it once they run out of bytes.
+    cut: usize,
 }
 
 impl<'a, T: AsRef<[u8]> + ?Sized> ReadRefReader<'a, T> {
@@ -967,6 +1520,27 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ReadRefReader<'a, T> {
         Self {
             whole_slice: rd,
             buf: rd.as_ref(),
+            cut: 0,
+        }
+    }
+
+    /// Returns the part that hasn't been consumed yet
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        &self.whole_slice.as_ref()[self.pos() as usize..]
+    }
+
+    #[inline]
+    fn pos(&self) -> u64 {
+        (self.whole_slice.as_ref().len() - self.buf.len() - self.cut) as u64
+    }
+
+    /// Reports running out of bytes as exceeding the limit if some were cut off by it.
+    #[cold]
+    fn eof(&self) -> io::Error {
+        if self.cut > 0 {
+            TotalBytesExceeded.into()
+        } else {
+            ErrorKind::UnexpectedEof.into()
         }
     }
 }
@@ -974,11 +1548,17 @@ impl<'a, T: AsRef<[u8]> + ?Sized> ReadRefReader<'a, T> {
 impl<'a, T: AsRef<[u8]> + ?Sized> Read for ReadRefReader<'a, T> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.buf.is_empty() && self.cut > 0 && !buf.is_empty() {
+            return Err(TotalBytesExceeded.into());
+        }
         self.buf.read(buf)
     }
 
     #[inline]
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        if buf.len() > self.buf.len() {
+            return Err(self.eof());
+        }
         self.buf.read_exact(buf)
     }
 }
@@ -987,12 +1567,25 @@ impl<'de, T: AsRef<[u8]> + ?Sized> ReadSlice<'de> for ReadRefReader<'de, T> {
     #[inline]
     fn read_slice<'a>(&'a mut self, len: usize) -> Result<Reference<'de, 'a, [u8]>, io::Error> {
         if len > self.buf.len() {
-            return Err(ErrorKind::UnexpectedEof.into());
+            return Err(self.eof());
         }
         let (a, b) = self.buf.split_at(len);
         self.buf = b;
         Ok(Reference::Borrowed(a))
     }
+
+    #[inline]
+    fn limit_total_bytes(&mut self, max: u64) {
+        let rest = self.remaining_slice();
+        let len = rest.len().min(max.try_into().unwrap_or(usize::MAX));
+        self.buf = &rest[..len];
+        self.cut = rest.len() - len;
+    }
+
+    #[inline]
+    fn position(&self) -> u64 {
+        self.pos()
+    }
 }
 
 #[test]
@@ -1006,6 +1599,27 @@ fn test_as_ref_reader() {
     assert_eq!(rd.read_slice(4).unwrap(), Reference::Borrowed(&[7, 8, 9, 10][..]));
 }
 
+#[test]
+fn test_as_ref_reader_limit() {
+    let buf = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    let mut rd = ReadRefReader::new(&buf);
+
+    assert_eq!(rd.read_slice(2).unwrap(), Reference::Borrowed(&[0, 1][..]));
+    rd.limit_total_bytes(4);
+    assert_eq!(2, rd.position());
+    assert_eq!(&buf[2..], rd.remaining_slice());
+    assert_eq!(rd.read_slice(3).unwrap(), Reference::Borrowed(&[2, 3, 4][..]));
+    assert!(TotalBytesExceeded::caused(&rd.read_exact(&mut [0; 2]).unwrap_err()));
+    assert_eq!(rd.read_slice(1).unwrap(), Reference::Borrowed(&[5][..]));
+    assert!(TotalBytesExceeded::caused(&rd.read(&mut [0; 1]).unwrap_err()));
+    assert_eq!(6, rd.position());
+
+    // A new limit counts from the current position.
+    rd.limit_total_bytes(10);
+    assert_eq!(rd.read_slice(5).unwrap(), Reference::Borrowed(&[6, 7, 8, 9, 10][..]));
+    assert_eq!(ErrorKind::UnexpectedEof, rd.read_exact(&mut [0; 1]).unwrap_err().kind());
+}
+
 /// Deserialize an instance of type `T` from an I/O stream of MessagePack.
 ///
 /// # Errors
@@ -1068,3 +1682,48 @@ where
     let mut de = Deserializer::from_read_ref(rd);
     Deserialize::deserialize(&mut de)
 }
+
+/// Deserialize an instance of type `T` from a slice like [`from_slice`], reporting where in the
+/// input an error occurred.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+///
+/// // Encoded `{"users": [{"zip": "1"}]}`.
+/// let buf = [0x81, 0xa5, 0x75, 0x73, 0x65, 0x72, 0x73, 0x91, 0x81, 0xa3, 0x7a, 0x69, 0x70, 0xa1, 0x31];
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Address {
+///     zip: u32,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Users {
+///     users: Vec<Address>,
+/// }
+///
+/// let err = rmp_serde::decode::from_slice_located::<Users>(&buf).unwrap_err();
+/// assert_eq!(13, err.offset);
+/// assert_eq!(".users[0].zip", err.path.to_string());
+/// ```
+pub fn from_slice_located<'a, T>(input: &'a [u8]) -> Result<T, LocatedError<Error>>
+where
+    T: Deserialize<'a>
+{
+    let mut de = Deserializer::from_read_ref(input);
+    de.set_track_path(true);
+    Deserialize::deserialize(&mut de).map_err(|err| de.locate(err))
+}
+
+/// Deserialize an instance of type `T` from an I/O stream like [`from_read`], reporting where in
+/// the stream an error occurred.
+pub fn from_read_located<R, T>(rd: R) -> Result<T, LocatedError<Error>>
+where R: Read,
+      T: DeserializeOwned
+{
+    let mut de = Deserializer::new(rd);
+    de.set_track_path(true);
+    Deserialize::deserialize(&mut de).map_err(|err| de.locate(err))
+}
@@ -2,7 +2,7 @@
 
 use std::error;
 use std::fmt::{self, Display};
-use std::io::Write;
+use std::io::{self, Write};
 
 use serde;
 use serde::ser::{
@@ -11,12 +11,13 @@ use serde::ser::{
 };
 use serde::Serialize;
 
-use rmp::encode::ValueWriteError;
+use rmp::encode::{ReservedLen, RmpPatch, ValueWriteError};
 use rmp::{encode, Marker};
 
 use crate::config::{
-    BinaryConfig, DefaultConfig, HumanReadableConfig, SerializerConfig, StructMapConfig,
-    StructTupleConfig
+    BinaryConfig, CanonicalConfig, DefaultConfig, EnumArrayConfig, EnumMapConfig, HumanReadableConfig,
+    SerializerConfig, SkipNoneConfig, StructIndexConfig, StructMapConfig, StructTupleConfig,
+    VariantIntegerConfig, VariantStringConfig, WrappedOptionConfig,
 };
 use crate::MSGPACK_EXT_STRUCT_NAME;
 
@@ -116,6 +117,69 @@ pub struct Serializer<W, C = DefaultConfig> {
     wr: W,
     config: C,
     depth: usize,
+    patcher: Option<Patcher<W>>,
+    /// The reserved headers of the structs being serialized as maps with nil fields skipped, the
+    /// innermost last, kept here rather than in `Compound` to keep it small.
+    reserved: Vec<ReservedStructLen<W>>,
+}
+
+/// Whether headers of sequences and maps of unknown length are shrunk on `end()`, and the
+/// functions patching them, available for writers implementing [`RmpPatch`] only.
+///
+/// The functions are looked up through a single pointer to keep serializers small.
+#[derive(Debug)]
+struct Patcher<W> {
+    minimal: bool,
+    ops: fn() -> PatchOps<W>,
+}
+
+impl<W: RmpPatch<Error = io::Error>> Patcher<W> {
+    fn new(minimal: bool) -> Self {
+        Patcher {
+            minimal,
+            ops: if minimal { PatchOps::minimal } else { PatchOps::fixed },
+        }
+    }
+}
+
+impl<W> Clone for Patcher<W> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<W> Copy for Patcher<W> {}
+
+/// Functions reserving headers of sequences and maps of unknown length in the writer and patching
+/// them on `end()`.
+#[derive(Debug)]
+struct PatchOps<W> {
+    begin_array: fn(&mut W) -> Result<ReservedLen, ValueWriteError>,
+    begin_map: fn(&mut W) -> Result<ReservedLen, ValueWriteError>,
+    finish_array: fn(&mut W, ReservedLen, u32) -> Marker,
+    finish_map: fn(&mut W, ReservedLen, u32) -> Marker,
+    written: fn(&W) -> &[u8],
+    patch: fn(&mut W, usize, &[u8]),
+    remove: fn(&mut W, usize, usize),
+}
+
+impl<W: RmpPatch<Error = io::Error>> PatchOps<W> {
+    fn fixed() -> Self {
+        PatchOps { finish_array: encode::finish_array, finish_map: encode::finish_map, ..Self::minimal() }
+    }
+
+    fn minimal() -> Self {
+        PatchOps {
+            begin_array: encode::begin_array_unknown,
+            begin_map: encode::begin_map_unknown,
+            finish_array: encode::finish_array_minimal,
+            finish_map: encode::finish_map_minimal,
+            written: W::written_bytes,
+            patch: W::patch,
+            remove: W::remove,
+        }
+    }
 }
 
 impl<W: Write, C> Serializer<W, C> {
@@ -163,33 +227,83 @@ impl<W: Write> Serializer<W, DefaultConfig> {
             wr,
             depth: 1024,
             config: DefaultConfig,
+            patcher: None,
+            reserved: Vec::new(),
         }
     }
 }
 
+impl<W: Write + RmpPatch<Error = io::Error>, C> Serializer<W, C> {
+    /// Consumes this serializer returning the new one, which will write sequences and maps of
+    /// unknown length directly into the writer, instead of buffering their elements to count them.
+    ///
+    /// Their headers are reserved with a 32-bit length and patched with the actual length on
+    /// `end()`, so the output may be a few bytes longer than with other serializers, but the
+    /// elements are never moved. Canonical maps are still buffered to sort their entries.
+    #[inline]
+    pub fn with_length_patching(mut self) -> Self {
+        self.patcher = Some(Patcher::new(false));
+        self
+    }
+
+    /// Consumes this serializer returning the new one, which will write sequences and maps of
+    /// unknown length directly into the writer like [`with_length_patching`], but shrinks their
+    /// headers to the smallest ones on `end()`.
+    ///
+    /// This moves the elements written after a shrunk header back, but produces exactly the same
+    /// output as other serializers.
+    ///
+    /// [`with_length_patching`]: Serializer::with_length_patching
+    #[inline]
+    pub fn with_minimal_length_patching(mut self) -> Self {
+        self.patcher = Some(Patcher::new(true));
+        self
+    }
+}
+
+impl<W, C> Serializer<W, C> {
+    /// Returns the header of the innermost struct being serialized with nil fields skipped.
+    #[inline]
+    fn last_reserved(&mut self) -> &mut ReservedStructLen<W> {
+        self.reserved.last_mut().expect("the header of the struct is reserved")
+    }
+}
+
 impl<'a, W: Write + 'a, C> Serializer<W, C> {
     #[inline]
     fn compound(&'a mut self) -> Result<Compound<'a, W, C>, Error> {
-        let c = Compound { se: self };
+        let c = Compound { se: self, buffered: None, reserved: false, idx: 0 };
         Ok(c)
     }
 }
 
 impl<'a, W: Write + 'a, C: SerializerConfig> Serializer<W, C> {
     #[inline]
-    fn maybe_unknown_len_compound<F>(&'a mut self, len: Option<usize>, f: F) -> Result<MaybeUnknownLengthCompound<'a, W, C>, Error>
-    where F: Fn(&mut W, u32) -> Result<Marker, ValueWriteError>
-    {
-        Ok(MaybeUnknownLengthCompound {
-            compound: match len {
-                Some(len) => {
-                    f(&mut self.wr, len as u32)?;
-                    None
-                }
-                None => Some(UnknownLengthCompound::from(&*self)),
-            },
-            se: self,
-        })
+    fn maybe_unknown_len_compound(&'a mut self, len: Option<usize>, is_map: bool) -> Result<MaybeUnknownLengthCompound<'a, W, C>, Error> {
+        let mut compound = None;
+        let mut reserved = None;
+        match (len, self.patcher) {
+            (Some(len), _) if is_map => {
+                encode::write_map_len(&mut self.wr, len as u32)?;
+            }
+            (Some(len), _) => {
+                encode::write_array_len(&mut self.wr, len as u32)?;
+            }
+            // Canonical maps are buffered to sort their entries.
+            (None, Some(patcher)) if !(is_map && C::is_canonical()) => {
+                let ops = (patcher.ops)();
+                reserved = Some(if is_map {
+                    PatchedLen { header: (ops.begin_map)(&mut self.wr)?, count: 0, finish: ops.finish_map }
+                } else {
+                    PatchedLen { header: (ops.begin_array)(&mut self.wr)?, count: 0, finish: ops.finish_array }
+                });
+            }
+            (None, _) => {
+                compound = Some(UnknownLengthCompound::from(&*self));
+            }
+        }
+
+        Ok(MaybeUnknownLengthCompound { se: self, compound, reserved })
     }
 }
 
@@ -200,11 +314,13 @@ impl<W: Write, C> Serializer<W, C> {
     /// requirements.
     #[inline]
     pub fn with_struct_map(self) -> Serializer<W, StructMapConfig<C>> {
-        let Serializer { wr, depth, config } = self;
+        let Serializer { wr, depth, config, patcher, reserved } = self;
         Serializer {
             wr,
             depth,
             config: StructMapConfig::new(config),
+            patcher,
+            reserved,
         }
     }
 
@@ -215,11 +331,124 @@ impl<W: Write, C> Serializer<W, C> {
     /// representation.
     #[inline]
     pub fn with_struct_tuple(self) -> Serializer<W, StructTupleConfig<C>> {
-        let Serializer { wr, depth, config } = self;
+        let Serializer { wr, depth, config, patcher, reserved } = self;
         Serializer {
             wr,
             depth,
             config: StructTupleConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will serialize structs as a map with
+    /// field indices as keys.
+    ///
+    /// This is almost as compact as a tuple, while allowing fields to be added and skipped like a
+    /// map with field names. See [`StructIndexConfig`] for details.
+    #[inline]
+    pub fn with_struct_index(self) -> Serializer<W, StructIndexConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: StructIndexConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will identify enum variants by their
+    /// index rather than by their name.
+    ///
+    /// This is more compact, but renumbers variants whenever they are reordered.
+    #[inline]
+    pub fn with_integer_variants(self) -> Serializer<W, VariantIntegerConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: VariantIntegerConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will identify enum variants by their
+    /// name.
+    ///
+    /// This is the default, kept stable when variants are reordered.
+    #[inline]
+    pub fn with_string_variants(self) -> Serializer<W, VariantStringConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: VariantStringConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will serialize enum variants with
+    /// data as a pair `[ident, data]` rather than as a single-entry map.
+    #[inline]
+    pub fn with_enum_array(self) -> Serializer<W, EnumArrayConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: EnumArrayConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will serialize enum variants with
+    /// data as a single-entry map `{ident: data}`.
+    ///
+    /// This is the default MessagePack serialization mechanism.
+    #[inline]
+    pub fn with_enum_map(self) -> Serializer<W, EnumMapConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: EnumMapConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will serialize `Some(value)` as a
+    /// single-element array `[value]`, so that nested options round-trip exactly.
+    ///
+    /// Such data must be deserialized with `Deserializer::with_wrapped_options`.
+    #[inline]
+    pub fn with_wrapped_options(self) -> Serializer<W, WrappedOptionConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: WrappedOptionConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will omit fields of structs
+    /// serialized as a map whose value is nil, such as `None`.
+    ///
+    /// See [`SkipNoneConfig`] for details.
+    #[inline]
+    pub fn with_skip_none(self) -> Serializer<W, SkipNoneConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: SkipNoneConfig::new(config),
+            patcher,
+            reserved,
         }
     }
 
@@ -232,11 +461,13 @@ impl<W: Write, C> Serializer<W, C> {
     /// versions of `rmp-serde`.
     #[inline]
     pub fn with_human_readable(self) -> Serializer<W, HumanReadableConfig<C>> {
-        let Serializer { wr, depth, config } = self;
+        let Serializer { wr, depth, config, patcher, reserved } = self;
         Serializer {
             wr,
             depth,
             config: HumanReadableConfig::new(config),
+            patcher,
+            reserved,
         }
     }
 
@@ -247,11 +478,31 @@ impl<W: Write, C> Serializer<W, C> {
     /// representation.
     #[inline]
     pub fn with_binary(self) -> Serializer<W, BinaryConfig<C>> {
-        let Serializer { wr, depth, config } = self;
+        let Serializer { wr, depth, config, patcher, reserved } = self;
         Serializer {
             wr,
             depth,
             config: BinaryConfig::new(config),
+            patcher,
+            reserved,
+        }
+    }
+
+    /// Consumes this serializer returning the new one, which will produce the canonical encoding,
+    /// so that equal values are always serialized to the same bytes.
+    ///
+    /// Floats are written as `f32` whenever that is lossless and entries of maps, including
+    /// structs serialized as maps, are sorted by their encoded keys. See [`CanonicalConfig`] for
+    /// details.
+    #[inline]
+    pub fn with_canonical(self) -> Serializer<W, CanonicalConfig<C>> {
+        let Serializer { wr, depth, config, patcher, reserved } = self;
+        Serializer {
+            wr,
+            depth,
+            config: CanonicalConfig::new(config),
+            patcher,
+            reserved,
         }
     }
 }
@@ -279,8 +530,39 @@ impl<W: Write, C> UnderlyingWrite for Serializer<W, C> {
 #[derive(Debug)]
 pub struct Compound<'a, W: 'a, C: 'a> {
     se: &'a mut Serializer<W, C>,
+    /// Buffered fields of a struct serialized as a map in canonical mode, to be sorted on `end()`,
+    /// or when nil fields are skipped and the writer can't patch the header, to be counted.
+    buffered: Option<Box<UnknownLengthCompound<C>>>,
+    /// Whether the header of a struct serialized as a map was written when nil fields are skipped
+    /// by a patching serializer, to be patched with the number of fields written on `end()`. It
+    /// is the last one reserved in the serializer.
+    reserved: bool,
+    /// The index of the next struct field, including skipped fields.
+    idx: u32,
+}
+
+/// The header of a struct serialized as a map, written for all of its fields, and the fields
+/// written after it so far.
+#[derive(Debug)]
+struct ReservedStructLen<W> {
+    written: fn(&W) -> &[u8],
+    remove: fn(&mut W, usize, usize),
+    pos: usize,
+    len: u32,
+    count: u32,
+    /// The position following the last field written.
+    end: usize,
+}
+
+impl<W> Clone for ReservedStructLen<W> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
+impl<W> Copy for ReservedStructLen<W> {}
+
 #[derive(Debug)]
 #[allow(missing_docs)]
 pub struct ExtFieldSerializer<'a, W> {
@@ -349,13 +631,87 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeStruct for Compound<'a, W,
     fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) ->
         Result<(), Self::Error>
     {
-        C::write_struct_field(&mut *self.se, key, value)
+        let idx = self.idx;
+        self.idx += 1;
+        // Only structs serialized in canonical mode or with nil fields skipped may be buffered or
+        // have their header reserved, which keeps the plain path small enough to be inlined.
+        if !(C::is_canonical() || C::skips_none()) {
+            C::write_struct_key(&mut *self.se, idx, key)?;
+            return value.serialize(&mut *self.se);
+        }
+        if self.reserved {
+            let ReservedStructLen { written, remove, end: start, .. } = *self.se.last_reserved();
+            let key_end = start + C::write_struct_key(&mut *self.se, idx, key)?;
+            value.serialize(&mut *self.se)?;
+            let wr = written(&self.se.wr);
+            let (end, nil) = (wr.len(), is_nil(&wr[key_end..]));
+            if nil {
+                remove(&mut self.se.wr, start, end - start);
+            } else {
+                let reserved = self.se.last_reserved();
+                reserved.count += 1;
+                reserved.end = end;
+            }
+            return Ok(());
+        }
+        match self.buffered.as_mut() {
+            None => {
+                C::write_struct_key(&mut *self.se, idx, key)?;
+                value.serialize(&mut *self.se)
+            }
+            Some(buf) => {
+                let start = buf.se.wr.len();
+                let key_end = start + C::write_struct_key(&mut buf.se, idx, key)?;
+                value.serialize(&mut buf.se)?;
+                if C::skips_none() && is_nil(&buf.se.wr[key_end..]) {
+                    buf.se.wr.truncate(start);
+                } else {
+                    buf.entries.push(start);
+                }
+                Ok(())
+            }
+        }
     }
 
-    #[inline(always)]
-    fn end(self) -> Result<Self::Ok, Self::Error> {
+    #[inline]
+    fn skip_field(&mut self, _key: &'static str) -> Result<(), Self::Error> {
+        self.idx += 1;
         Ok(())
     }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        if !(C::is_canonical() || C::skips_none()) {
+            return Ok(());
+        }
+        let reserved = if self.reserved { self.se.reserved.pop() } else { None };
+        match reserved {
+            Some(reserved) if reserved.count != reserved.len => {
+                let patcher = self.se.patcher.expect("headers are only reserved by patching serializers");
+                // Fewer fields never need a larger header, but a smaller one moves them back.
+                let width = encode::size_of_map_header(reserved.len);
+                let mut header = [0; 5];
+                let len = write_map_header(&mut header, reserved.count, if patcher.minimal { 0 } else { width });
+                ((patcher.ops)().patch)(&mut self.se.wr, reserved.pos, &header[..len]);
+                if len < width {
+                    (reserved.remove)(&mut self.se.wr, reserved.pos + len, width - len);
+                }
+            }
+            _ => {}
+        }
+        match self.buffered {
+            None => Ok(()),
+            Some(buf) => {
+                encode::write_map_len(&mut self.se.wr, buf.entries.len() as u32)?;
+                if C::is_canonical() {
+                    return buf.write_sorted(&mut self.se.wr);
+                }
+                self.se.wr.write_all(&buf.se.into_inner())
+                    .map_err(ValueWriteError::InvalidDataWrite)?;
+                Ok(())
+            }
+        }
+    }
 }
 
 impl<'a, W: Write + 'a, C: SerializerConfig> SerializeTupleVariant for Compound<'a, W, C> {
@@ -380,12 +736,47 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeStructVariant for Compound
     fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) ->
         Result<(), Self::Error>
     {
-        C::write_struct_field(&mut *self.se, key, value)
+        <Self as SerializeStruct>::serialize_field(self, key, value)
     }
 
-    #[inline(always)]
+    #[inline]
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        <Self as SerializeStruct>::skip_field(self, key)
+    }
+
+    #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        <Self as SerializeStruct>::end(self)
+    }
+}
+
+/// Checks whether an encoded value is nil.
+#[inline]
+fn is_nil(value: &[u8]) -> bool {
+    value == [Marker::Null.to_u8()]
+}
+
+/// Checks whether an encoded value starts with the header of a map.
+#[inline]
+fn is_map_header(buf: &[u8]) -> bool {
+    matches!(buf.first().map(|&b| Marker::from_u8(b)), Some(Marker::FixMap(..) | Marker::Map16 | Marker::Map32))
+}
+
+/// Encodes the smallest header of a map with `len` entries that is at least `width` bytes long,
+/// returning its length.
+fn write_map_header(buf: &mut [u8; 5], len: u32, width: usize) -> usize {
+    let be = len.to_be_bytes();
+    if len < 16 && width <= 1 {
+        buf[0] = Marker::FixMap(len as u8).to_u8();
+        1
+    } else if len <= u16::MAX as u32 && width <= 3 {
+        buf[0] = Marker::Map16.to_u8();
+        buf[1..3].copy_from_slice(&be[2..]);
+        3
+    } else {
+        buf[0] = Marker::Map32.to_u8();
+        buf[1..].copy_from_slice(&be);
+        5
     }
 }
 
@@ -395,13 +786,46 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeStructVariant for Compound
 struct UnknownLengthCompound<C> {
     se: Serializer<Vec<u8>, C>,
     elem_count: u32,
+    /// Offsets where map entries start, recorded in canonical mode only.
+    entries: Vec<usize>,
 }
 impl<W, C: SerializerConfig> From<&Serializer<W, C>> for UnknownLengthCompound<C> {
     fn from(se: &Serializer<W, C>) -> Self {
         Self {
-            se: Serializer { wr: Vec::with_capacity(128), config: se.config, depth: se.depth },
-            elem_count: 0
+            se: Serializer {
+                wr: Vec::with_capacity(128),
+                config: se.config,
+                depth: se.depth,
+                // Buffers can always be patched, so nested values of unknown length are never
+                // buffered again. Minimal headers keep the output the same as without patching.
+                patcher: Some(Patcher::new(!matches!(se.patcher, Some(Patcher { minimal: false, .. })))),
+                reserved: Vec::new(),
+            },
+            elem_count: 0,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<C> UnknownLengthCompound<C> {
+    /// Writes the buffered data with map entries sorted by their encoded bytes.
+    ///
+    /// Anything before the first entry is written as is. Since encoded values are self-delimiting,
+    /// sorting whole entries sorts them by their keys.
+    fn write_sorted<W: Write>(self, wr: &mut W) -> Result<(), Error> {
+        let buf = self.se.into_inner();
+        let head = self.entries.first().copied().unwrap_or(buf.len());
+
+        let mut entries: Vec<&[u8]> = self.entries.iter().enumerate()
+            .map(|(idx, &start)| &buf[start..self.entries.get(idx + 1).copied().unwrap_or(buf.len())])
+            .collect();
+        entries.sort_unstable();
+
+        wr.write_all(&buf[..head]).map_err(ValueWriteError::InvalidDataWrite)?;
+        for entry in entries {
+            wr.write_all(entry).map_err(ValueWriteError::InvalidDataWrite)?;
         }
+        Ok(())
     }
 }
 
@@ -419,11 +843,23 @@ impl<W, C: SerializerConfig> From<&Serializer<W, C>> for UnknownLengthCompound<C
 /// that structs that contain flattened fields arem always written as a map, even when compact
 /// representaion is desired.
 ///
+/// If the writer supports patching and patching was enabled, the header is reserved in the writer
+/// instead, the elements are encoded directly and the header is patched on `end()`.
+///
 /// Otherwise, if the length is known, the elements will be encoded directly by the `Serializer`.
 #[derive(Debug)]
 pub struct MaybeUnknownLengthCompound<'a, W: 'a, C: 'a> {
     se: &'a mut Serializer<W, C>,
     compound: Option<UnknownLengthCompound<C>>,
+    reserved: Option<PatchedLen<W>>,
+}
+
+/// A header reserved in the writer and the number of elements written after it so far.
+#[derive(Debug)]
+struct PatchedLen<W> {
+    header: ReservedLen,
+    count: u32,
+    finish: fn(&mut W, ReservedLen, u32) -> Marker,
 }
 
 impl<'a, W: Write + 'a, C: SerializerConfig> SerializeSeq for MaybeUnknownLengthCompound<'a, W, C> {
@@ -432,7 +868,13 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeSeq for MaybeUnknownLength
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
         match self.compound.as_mut() {
-            None => value.serialize(&mut *self.se),
+            None => {
+                value.serialize(&mut *self.se)?;
+                if let Some(reserved) = self.reserved.as_mut() {
+                    reserved.count += 1;
+                }
+                Ok(())
+            }
             Some(buf) => {
                 value.serialize(&mut buf.se)?;
                 buf.elem_count += 1;
@@ -442,6 +884,9 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeSeq for MaybeUnknownLength
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        if let Some(reserved) = self.reserved {
+            (reserved.finish)(&mut self.se.wr, reserved.header, reserved.count);
+        }
         if let Some(compound) = self.compound {
             encode::write_array_len(&mut self.se.wr, compound.elem_count)?;
             self.se.wr.write_all(&compound.se.into_inner())
@@ -456,6 +901,11 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeMap for MaybeUnknownLength
     type Error = Error;
 
     fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        if C::is_canonical() {
+            if let Some(buf) = self.compound.as_mut() {
+                buf.entries.push(buf.se.wr.len());
+            }
+        }
         <Self as SerializeSeq>::serialize_element(self, key)
     }
 
@@ -464,10 +914,17 @@ impl<'a, W: Write + 'a, C: SerializerConfig> SerializeMap for MaybeUnknownLength
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        if let Some(reserved) = self.reserved {
+            (reserved.finish)(&mut self.se.wr, reserved.header, reserved.count / 2);
+        }
         if let Some(compound) = self.compound {
             encode::write_map_len(&mut self.se.wr, compound.elem_count / 2)?;
-            self.se.wr.write_all(&compound.se.into_inner())
-                .map_err(ValueWriteError::InvalidDataWrite)?;
+            if C::is_canonical() {
+                compound.write_sorted(&mut self.se.wr)?;
+            } else {
+                self.se.wr.write_all(&compound.se.into_inner())
+                    .map_err(ValueWriteError::InvalidDataWrite)?;
+            }
         }
         Ok(())
     }
@@ -541,12 +998,20 @@ where
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        encode::write_f32(&mut self.wr, v)?;
+        if C::is_canonical() {
+            encode::write_f32_canonical(&mut self.wr, v)?;
+        } else {
+            encode::write_f32(&mut self.wr, v)?;
+        }
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        encode::write_f64(&mut self.wr, v)?;
+        if C::is_canonical() {
+            encode::write_f64_canonical(&mut self.wr, v)?;
+        } else {
+            encode::write_f64(&mut self.wr, v)?;
+        }
         Ok(())
     }
 
@@ -573,6 +1038,9 @@ where
     }
 
     fn serialize_some<T: ?Sized + serde::Serialize>(self, v: &T) -> Result<(), Self::Error> {
+        if C::wraps_options() {
+            encode::write_array_len(&mut self.wr, 1)?;
+        }
         v.serialize(self)
     }
 
@@ -606,13 +1074,13 @@ where
 
     fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(self, _name: &'static str, idx: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
         // encode as a map from variant idx to its attributed data, like: {idx => value}
-        encode::write_map_len(&mut self.wr, 1)?;
+        C::write_variant_header(self)?;
         C::write_variant_ident(self, idx, variant)?;
         value.serialize(self)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
-        self.maybe_unknown_len_compound(len, |wr, len| encode::write_array_len(wr, len))
+        self.maybe_unknown_len_compound(len, false)
     }
 
     //TODO: normal compund
@@ -634,18 +1102,56 @@ where
         Result<Self::SerializeTupleVariant, Error>
     {
         // encode as a map from variant idx to a sequence of its attributed data, like: {idx => [v1,...,vN]}
-        encode::write_map_len(&mut self.wr, 1)?;
+        C::write_variant_header(self)?;
         C::write_variant_ident(self, idx, variant)?;
         self.serialize_tuple(len)
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
-        self.maybe_unknown_len_compound(len, |wr, len| encode::write_map_len(wr, len))
+        // Canonical maps are always buffered to sort their entries.
+        let len = if C::is_canonical() { None } else { len };
+        self.maybe_unknown_len_compound(len, true)
     }
 
     fn serialize_struct(self, _name: &'static str, len: usize) ->
         Result<Self::SerializeStruct, Self::Error>
     {
+        // Only structs serialized as maps have keys to sort or fields to skip.
+        if let (false, true, Some(patcher)) = (C::is_canonical(), C::skips_none(), self.patcher) {
+            // The header written for all fields is patched on `end()` with the number of fields
+            // that aren't skipped. Headers of arrays and maps of the same length are equally long.
+            C::write_struct_len(self, len)?;
+            let ops = (patcher.ops)();
+            let wr = (ops.written)(&self.wr);
+            let pos = wr.len() - encode::size_of_map_header(len as u32);
+            let reserved = is_map_header(&wr[pos..]);
+            if reserved {
+                let end = wr.len();
+                self.reserved.push(ReservedStructLen {
+                    written: ops.written,
+                    remove: ops.remove,
+                    pos,
+                    len: len as u32,
+                    count: 0,
+                    end,
+                });
+            }
+            return Ok(Compound { se: self, buffered: None, reserved, idx: 0 });
+        }
+        if C::is_canonical() || C::skips_none() {
+            let mut buf = UnknownLengthCompound::from(&*self);
+            C::write_struct_len(&mut buf.se, len)?;
+
+            // The header of maps is written on `end()`, once the number of fields is known.
+            if is_map_header(&buf.se.wr) {
+                buf.se.wr.clear();
+                return Ok(Compound { se: self, buffered: Some(Box::new(buf)), reserved: false, idx: 0 });
+            }
+            self.wr.write_all(&buf.se.into_inner())
+                .map_err(ValueWriteError::InvalidDataWrite)?;
+            return self.compound();
+        }
+
         C::write_struct_len(self, len)?;
         self.compound()
     }
@@ -654,7 +1160,7 @@ where
         Result<Self::SerializeStructVariant, Error>
     {
         // encode as a map from variant idx to a sequence of its attributed data, like: {idx => [v1,...,vN]}
-        encode::write_map_len(&mut self.wr, 1)?;
+        C::write_variant_header(self)?;
         C::write_variant_ident(self, id, variant)?;
         self.serialize_struct(name, len)
     }
@@ -1097,3 +1603,61 @@ where
     write_named(&mut wr, val)?;
     Ok(wr)
 }
+
+/// A writer which discards the bytes written into it, only counting them.
+struct SizeCounter(usize);
+
+impl Write for SizeCounter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Computes the exact number of bytes [`to_vec`] produces for the given data structure, without
+/// keeping the encoded bytes.
+///
+/// This is useful to preallocate exact buffers or to split batches under a size limit.
+///
+/// # Errors
+///
+/// Serialization can fail if `T`'s implementation of `Serialize` decides to fail.
+///
+/// # Examples
+///
+/// ```
+/// let val = (42, "le message");
+///
+/// assert_eq!(rmp_serde::to_vec(&val).unwrap().len(), rmp_serde::encoded_size(&val).unwrap());
+/// ```
+#[inline]
+pub fn encoded_size<T>(val: &T) -> Result<usize, Error>
+where
+    T: Serialize + ?Sized
+{
+    let mut wr = SizeCounter(0);
+    write(&mut wr, val)?;
+    Ok(wr.0)
+}
+
+/// Computes the exact number of bytes [`to_vec_named`] produces for the given data structure,
+/// without keeping the encoded bytes.
+///
+/// # Errors
+///
+/// Serialization can fail if `T`'s implementation of `Serialize` decides to fail.
+#[inline]
+pub fn encoded_size_named<T>(val: &T) -> Result<usize, Error>
+where
+    T: Serialize + ?Sized
+{
+    let mut wr = SizeCounter(0);
+    write_named(&mut wr, val)?;
+    Ok(wr.0)
+}
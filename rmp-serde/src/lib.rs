@@ -70,13 +70,65 @@ use serde::{Deserialize, Serialize};
 pub use crate::decode::{from_read, Deserializer};
 #[allow(deprecated)]
 pub use crate::decode::from_read_ref;
-pub use crate::encode::{to_vec, to_vec_named, Serializer};
+pub use crate::encode::{encoded_size, encoded_size_named, to_vec, to_vec_named, Serializer};
 
 pub use crate::decode::from_slice;
 
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[macro_use]
+mod async_io;
+#[cfg(feature = "codec")]
+pub mod codec;
 pub mod config;
 pub mod decode;
 pub mod encode;
+pub mod timestamp;
+
+#[cfg(feature = "tokio")]
+async_serde_module! {
+    /// Asynchronous (de)serialization with [`tokio::io`](::tokio::io) readers and writers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp_serde::tokio::{from_async_read, to_async_write};
+    ///
+    /// # futures_executor::block_on(async {
+    /// let mut buf = Vec::new();
+    /// to_async_write(&mut buf, &(42, "le message")).await.unwrap();
+    /// to_async_write(&mut buf, &[1, 2]).await.unwrap();
+    ///
+    /// let mut rd = &buf[..];
+    /// let first: (u8, String) = from_async_read(&mut rd).await.unwrap();
+    /// let second: Vec<u8> = from_async_read(&mut rd).await.unwrap();
+    ///
+    /// assert_eq!((42, "le message".to_owned()), first);
+    /// assert_eq!(vec![1, 2], second);
+    /// # });
+    /// ```
+    tokio, ::tokio::io
+}
+
+#[cfg(feature = "futures")]
+async_serde_module! {
+    /// Asynchronous (de)serialization with [`futures::io`](::futures_util::io) readers and
+    /// writers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp_serde::futures::{from_async_read, to_async_write};
+    ///
+    /// # futures_executor::block_on(async {
+    /// let mut buf = Vec::new();
+    /// to_async_write(&mut buf, &(42, "le message")).await.unwrap();
+    ///
+    /// let val: (u8, String) = from_async_read(&mut &buf[..]).await.unwrap();
+    /// assert_eq!((42, "le message".to_owned()), val);
+    /// # });
+    /// ```
+    futures, ::futures_util::io
+}
 
 /// Name of Serde newtype struct to Represent Msgpack's Ext
 /// Msgpack Ext: Ext(tag, binary)
@@ -0,0 +1,202 @@
+//! Serde adapters for the MessagePack timestamp extension type.
+//!
+//! This module is meant to be used with the `#[serde(with = "rmp_serde::timestamp")]` field
+//! attribute. Supported field types are [`SystemTime`], [`Duration`] (interpreted as time elapsed
+//! since the Unix epoch) and [`rmp::Timestamp`] itself. Values are encoded as the extension type
+//! `-1` using the most compact of the timestamp 32, 64 or 96 layouts, while all three layouts are
+//! accepted when decoding.
+//!
+//! # Examples
+//!
+//! ```
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "rmp_serde::timestamp")]
+//!     at: SystemTime,
+//! }
+//!
+//! fn main() {
+//!     let event = Event { at: UNIX_EPOCH + Duration::from_secs(42) };
+//!     let buf = rmp_serde::to_vec(&event).unwrap();
+//!
+//!     assert_eq!(vec![0x91, 0xd6, 0xff, 0x00, 0x00, 0x00, 0x2a], buf);
+//!     assert_eq!(event, rmp_serde::from_slice(&buf).unwrap());
+//! }
+//! ```
+
+use std::fmt::{self, Formatter};
+use std::time::{Duration, SystemTime};
+
+use rmp::Timestamp;
+use serde::de::{self, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::MSGPACK_EXT_STRUCT_NAME;
+
+/// Types that can be represented as a MessagePack timestamp.
+///
+/// Implemented as an empty trait depending on a hidden trait in order to disallow external
+/// implementations.
+pub trait TimestampLike: sealed::TimestampLike {}
+
+impl<T: sealed::TimestampLike> TimestampLike for T {}
+
+mod sealed {
+    use rmp::Timestamp;
+
+    pub trait TimestampLike: Sized {
+        fn to_timestamp(&self) -> Option<Timestamp>;
+        fn from_timestamp(ts: Timestamp) -> Option<Self>;
+    }
+}
+
+impl sealed::TimestampLike for Timestamp {
+    #[inline]
+    fn to_timestamp(&self) -> Option<Timestamp> {
+        Some(*self)
+    }
+
+    #[inline]
+    fn from_timestamp(ts: Timestamp) -> Option<Self> {
+        Some(ts)
+    }
+}
+
+impl sealed::TimestampLike for SystemTime {
+    #[inline]
+    fn to_timestamp(&self) -> Option<Timestamp> {
+        Timestamp::from_system_time(*self)
+    }
+
+    #[inline]
+    fn from_timestamp(ts: Timestamp) -> Option<Self> {
+        ts.to_system_time()
+    }
+}
+
+impl sealed::TimestampLike for Duration {
+    #[inline]
+    fn to_timestamp(&self) -> Option<Timestamp> {
+        Timestamp::from_duration(*self)
+    }
+
+    #[inline]
+    fn from_timestamp(ts: Timestamp) -> Option<Self> {
+        ts.to_duration()
+    }
+}
+
+/// Serializes the given value as a MessagePack timestamp.
+///
+/// # Errors
+///
+/// Returns an error if the value does not fit in a timestamp, i.e. its seconds overflow `i64`.
+pub fn serialize<T, S>(val: &T, se: S) -> Result<S::Ok, S::Error>
+where
+    T: TimestampLike,
+    S: Serializer,
+{
+    use serde::ser::Error;
+
+    let ts = val.to_timestamp()
+        .ok_or_else(|| S::Error::custom("timestamp is out of range"))?;
+
+    let mut buf = [0; 12];
+    let data = ts.to_ext_data(&mut buf);
+
+    se.serialize_newtype_struct(MSGPACK_EXT_STRUCT_NAME, &(Timestamp::EXT_TYPE, ExtData(data)))
+}
+
+/// Deserializes a MessagePack timestamp into the requested type.
+///
+/// # Errors
+///
+/// Returns an error if the value is not a timestamp extension, or if the timestamp cannot be
+/// represented by the requested type, e.g. a point in time before the epoch for `Duration`.
+pub fn deserialize<'de, T, D>(de: D) -> Result<T, D::Error>
+where
+    T: TimestampLike,
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let ts = de.deserialize_newtype_struct(MSGPACK_EXT_STRUCT_NAME, TimestampVisitor)?;
+
+    T::from_timestamp(ts).ok_or_else(|| D::Error::custom("timestamp is out of range"))
+}
+
+struct ExtData<'a>(&'a [u8]);
+
+impl<'a> Serialize for ExtData<'a> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, se: S) -> Result<S::Ok, S::Error> {
+        se.serialize_bytes(self.0)
+    }
+}
+
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = Timestamp;
+
+    #[cold]
+    fn expecting(&self, fmt: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt.write_str("a MessagePack timestamp extension")
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, de: D) -> Result<Self::Value, D::Error>
+        where D: Deserializer<'de>
+    {
+        de.deserialize_tuple(2, self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let ty: i8 = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if ty != Timestamp::EXT_TYPE {
+            return Err(de::Error::invalid_value(Unexpected::Signed(ty as i64), &"extension type -1"));
+        }
+
+        seq.next_element::<ExtTimestamp>()?
+            .map(|ExtTimestamp(ts)| ts)
+            .ok_or_else(|| de::Error::invalid_length(1, &self))
+    }
+}
+
+/// Timestamp decoded directly from the extension data, without an intermediate buffer.
+struct ExtTimestamp(Timestamp);
+
+impl<'de> de::Deserialize<'de> for ExtTimestamp {
+    #[inline]
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        de.deserialize_bytes(ExtTimestampVisitor).map(ExtTimestamp)
+    }
+}
+
+struct ExtTimestampVisitor;
+
+impl<'de> Visitor<'de> for ExtTimestampVisitor {
+    type Value = Timestamp;
+
+    #[cold]
+    fn expecting(&self, fmt: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt.write_str("timestamp extension data of 4, 8 or 12 bytes")
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where E: de::Error
+    {
+        Timestamp::from_ext_data(v).ok_or_else(|| de::Error::invalid_value(Unexpected::Bytes(v), &self))
+    }
+}
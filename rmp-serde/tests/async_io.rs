@@ -0,0 +1,81 @@
+#![cfg(any(feature = "tokio", feature = "futures"))]
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::io::ErrorKind;
+
+use futures_executor::block_on;
+
+use rmp_serde::decode::Error;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    body: String,
+    tags: Vec<String>,
+}
+
+macro_rules! async_tests {
+    ($name:ident) => {
+        mod $name {
+            use super::*;
+            use rmp_serde::$name::{from_async_read, to_async_write};
+
+            #[test]
+            fn pass_roundtrip_stream() {
+                block_on(async {
+                    let messages = vec![
+                        Message { id: 1, body: "hello".into(), tags: vec![] },
+                        Message { id: 2, body: "x".repeat(100_000), tags: vec!["a".into(), "b".into()] },
+                    ];
+
+                    let mut buf = Vec::new();
+                    for msg in &messages {
+                        to_async_write(&mut buf, msg).await.unwrap();
+                    }
+
+                    let mut rd = &buf[..];
+                    for msg in &messages {
+                        assert_eq!(*msg, from_async_read::<_, Message>(&mut rd).await.unwrap());
+                    }
+
+                    match from_async_read::<_, Message>(&mut rd).await {
+                        Err(Error::InvalidMarkerRead(err)) => assert_eq!(ErrorKind::UnexpectedEof, err.kind()),
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                });
+            }
+
+            #[test]
+            fn fail_truncated_value() {
+                block_on(async {
+                    let buf = rmp_serde::to_vec(&Message { id: 1, body: "hello".into(), tags: vec![] }).unwrap();
+
+                    match from_async_read::<_, Message>(&mut &buf[..buf.len() - 1]).await {
+                        Err(Error::InvalidDataRead(err)) => assert_eq!(ErrorKind::UnexpectedEof, err.kind()),
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                });
+            }
+
+            #[test]
+            fn fail_type_mismatch_consumes_value() {
+                block_on(async {
+                    let mut buf = rmp_serde::to_vec(&"not a message").unwrap();
+                    buf.push(0x2a);
+                    let mut rd = &buf[..];
+
+                    assert!(from_async_read::<_, Message>(&mut rd).await.is_err());
+                    assert_eq!(42u8, from_async_read::<_, u8>(&mut rd).await.unwrap());
+                });
+            }
+        }
+    };
+}
+
+#[cfg(feature = "tokio")]
+async_tests!(tokio);
+
+#[cfg(feature = "futures")]
+async_tests!(futures);
@@ -0,0 +1,156 @@
+#![cfg(feature = "codec")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use rmp::Marker;
+use rmp_serde::codec::{Error, MsgPackCodec};
+use rmp_serde::decode;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    body: String,
+}
+
+#[test]
+fn pass_decode_byte_by_byte() {
+    let mut codec = MsgPackCodec::<Message>::new();
+
+    let mut stream = BytesMut::new();
+    codec.encode(Message { id: 1, body: "hello".into() }, &mut stream).unwrap();
+    codec.encode(&Message { id: 2, body: "x".repeat(1000) }, &mut stream).unwrap();
+
+    let mut buf = BytesMut::new();
+    let mut messages = Vec::new();
+    for byte in stream {
+        buf.extend_from_slice(&[byte]);
+        if let Some(msg) = codec.decode(&mut buf).unwrap() {
+            messages.push(msg);
+        }
+    }
+
+    assert_eq!(vec![
+        Message { id: 1, body: "hello".into() },
+        Message { id: 2, body: "x".repeat(1000) },
+    ], messages);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn pass_decode_several_frames_at_once() {
+    let mut codec = MsgPackCodec::<Vec<u8>>::new();
+    // [1, 2], [], [3] and a part of the next frame.
+    let mut buf = BytesMut::from(&[0x92, 0x01, 0x02, 0x90, 0x91, 0x03, 0x92, 0x04][..]);
+
+    assert_eq!(Some(vec![1, 2]), codec.decode(&mut buf).unwrap());
+    assert_eq!(Some(vec![]), codec.decode(&mut buf).unwrap());
+    assert_eq!(Some(vec![3]), codec.decode(&mut buf).unwrap());
+    assert_eq!(None, codec.decode(&mut buf).unwrap());
+    assert_eq!(&[0x92, 0x04][..], &buf[..]);
+}
+
+#[test]
+fn pass_encode_struct_map() {
+    let mut codec = MsgPackCodec::<Message>::new().with_struct_map();
+
+    let mut buf = BytesMut::new();
+    codec.encode(Message { id: 1, body: "a".into() }, &mut buf).unwrap();
+
+    assert_eq!(&[0x82, 0xa2, 0x69, 0x64, 0x01, 0xa4, 0x62, 0x6f, 0x64, 0x79, 0xa1, 0x61][..], &buf[..]);
+    assert_eq!(Some(Message { id: 1, body: "a".into() }), codec.decode(&mut buf).unwrap());
+}
+
+#[test]
+fn fail_decode_frame_too_large_from_header() {
+    let mut codec = MsgPackCodec::<String>::new().max_frame_len(16);
+    // A string declaring 1000 bytes, none of which have arrived yet.
+    let mut buf = BytesMut::from(&[0xda, 0x03, 0xe8][..]);
+
+    match codec.decode(&mut buf) {
+        Err(Error::FrameTooLarge) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_decode_frame_too_large_from_elements() {
+    let mut codec = MsgPackCodec::<Vec<u8>>::new().max_frame_len(16);
+    // An array declaring 100 elements, each of which is small.
+    let mut buf = BytesMut::from(&[0xdc, 0x00, 0x64][..]);
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+
+    buf.extend_from_slice(&[0x01; 20]);
+    match codec.decode(&mut buf) {
+        Err(Error::FrameTooLarge) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_decode_depth_limit_exceeded() {
+    let mut codec = MsgPackCodec::<Vec<Vec<Vec<u8>>>>::new().max_depth(2);
+
+    let mut buf = BytesMut::from(&[0x91, 0x91, 0x90][..]);
+    match codec.decode(&mut buf) {
+        Err(Error::Decode(decode::Error::DepthLimitExceeded)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut codec = MsgPackCodec::<Vec<Vec<u8>>>::new().max_depth(2);
+    let mut buf = BytesMut::from(&[0x91, 0x90][..]);
+    assert_eq!(Some(vec![vec![]]), codec.decode(&mut buf).unwrap());
+}
+
+#[test]
+fn fail_decode_reserved_marker() {
+    let mut codec = MsgPackCodec::<u8>::new();
+    let mut buf = BytesMut::from(&[0xc1][..]);
+
+    match codec.decode(&mut buf) {
+        Err(Error::Decode(decode::Error::TypeMismatch(Marker::Reserved))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_decode_type_mismatch_consumes_frame() {
+    let mut codec = MsgPackCodec::<u8>::new();
+    // "a" followed by 1.
+    let mut buf = BytesMut::from(&[0xa1, 0x61, 0x01][..]);
+
+    match codec.decode(&mut buf) {
+        Err(Error::Decode(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(Some(1), codec.decode(&mut buf).unwrap());
+}
+
+#[test]
+fn fail_decode_eof_in_frame() {
+    let mut codec = MsgPackCodec::<Vec<u8>>::new();
+    let mut buf = BytesMut::from(&[0x92, 0x01][..]);
+
+    match codec.decode_eof(&mut buf) {
+        Err(Error::Io(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_encode_frame_too_large() {
+    let mut codec = MsgPackCodec::<String>::new().max_frame_len(4);
+
+    let mut buf = BytesMut::from(&[0x01][..]);
+    match codec.encode("hello", &mut buf) {
+        Err(Error::FrameTooLarge) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(&[0x01][..], &buf[..]);
+
+    codec.encode("abc", &mut buf).unwrap();
+    assert_eq!(&[0x01, 0xa3, 0x61, 0x62, 0x63][..], &buf[..]);
+}
@@ -6,7 +6,7 @@ use std::io::Cursor;
 use serde::de;
 use serde::Deserialize;
 
-use crate::rmps::decode::{self, Error};
+use crate::rmps::decode::{self, DecodeLimits, Error, Limit};
 use crate::rmps::{Deserializer, Raw, RawRef};
 use rmp::Marker;
 
@@ -569,3 +569,129 @@ fn fail_depth_limit() {
         other => panic!("unexpected result: {:?}", other),
     }
 }
+
+#[test]
+fn pass_ignored_any() {
+    // [{"a": [1, "x", {}], "b": <bin>, "c": <ext>}, 42]
+    let buf = [
+        0x92,
+        0x83,
+        0xa1, 0x61, 0x93, 0x01, 0xa1, 0x78, 0x80,
+        0xa1, 0x62, 0xc4, 0x02, 0x00, 0x00,
+        0xa1, 0x63, 0xd4, 0x01, 0x00,
+        0x2a,
+    ];
+
+    let (_, val): (de::IgnoredAny, u8) = rmps::from_slice(&buf[..]).unwrap();
+    assert_eq!(42, val);
+
+    let mut de = Deserializer::new(Cursor::new(&buf[..]));
+    let (_, val): (de::IgnoredAny, u8) = Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(42, val);
+    assert_eq!(buf.len() as u64, de.get_ref().position());
+}
+
+#[test]
+fn fail_ignored_any_depth_limit() {
+    let buf = [0x91; 100];
+
+    let mut de = Deserializer::new(&buf[..]);
+    de.set_max_depth(50);
+    match de::IgnoredAny::deserialize(&mut de).unwrap_err() {
+        decode::Error::DepthLimitExceeded => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_ignored_any_reserved() {
+    let buf = [0x91, 0xc1];
+
+    match rmps::from_slice::<de::IgnoredAny>(&buf[..]).unwrap_err() {
+        decode::Error::TypeMismatch(Marker::Reserved) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_within_limits() {
+    // ["ab", [1, 2], {"c": <bin 1>}]
+    let buf = [0x93, 0xa2, 0x61, 0x62, 0x92, 0x01, 0x02, 0x81, 0xa1, 0x63, 0xc4, 0x01, 0x00];
+    let limits = DecodeLimits {
+        max_str_len: 2,
+        max_bin_len: 1,
+        max_array_len: 3,
+        max_map_len: 1,
+        max_total_bytes: buf.len() as u64,
+        ..DecodeLimits::default()
+    };
+
+    let mut de = Deserializer::from_read_ref(&buf[..]);
+    de.set_limits(limits);
+    let (s, arr, map): (&str, Vec<u8>, std::collections::BTreeMap<String, serde_bytes::ByteBuf>) =
+        Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!("ab", s);
+    assert_eq!(vec![1, 2], arr);
+    assert_eq!(&[0][..], &map["c"][..]);
+}
+
+#[test]
+fn fail_limits_exceeded() {
+    let cases: &[(&[u8], DecodeLimits, Limit)] = &[
+        (&[0xdb, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_str_len: 1024, ..DecodeLimits::default() }, Limit::StrLen),
+        (&[0xc6, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_bin_len: 1024, ..DecodeLimits::default() }, Limit::BinLen),
+        (&[0xdd, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_array_len: 1024, ..DecodeLimits::default() }, Limit::ArrayLen),
+        (&[0xdf, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_map_len: 1024, ..DecodeLimits::default() }, Limit::MapLen),
+        (&[0xc9, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_ext_len: 1024, ..DecodeLimits::default() }, Limit::ExtLen),
+        (&[0x93, 0x01, 0x02, 0x03], DecodeLimits { max_total_bytes: 3, ..DecodeLimits::default() }, Limit::TotalBytes),
+    ];
+
+    for (buf, limits, expected) in cases {
+        let mut de = Deserializer::new(&buf[..]);
+        de.set_limits(*limits);
+        match serde::Deserializer::deserialize_any(&mut de, de::IgnoredAny) {
+            Err(Error::LimitExceeded(limit)) if limit == *expected => {}
+            other => panic!("unexpected result for {:?}: {:?}", expected, other),
+        }
+
+        let mut de = Deserializer::from_read_ref(buf);
+        de.set_limits(*limits);
+        match serde::Deserializer::deserialize_any(&mut de, de::IgnoredAny) {
+            Err(Error::LimitExceeded(limit)) if limit == *expected => {}
+            other => panic!("unexpected result for {:?}: {:?}", expected, other),
+        }
+    }
+}
+
+#[test]
+fn fail_limits_total_bytes_across_values() {
+    let buf = [0xa3, 0x61, 0x62, 0x63, 0xa3, 0x64, 0x65, 0x66];
+
+    let mut de = Deserializer::new(&buf[..]);
+    de.set_limits(DecodeLimits { max_total_bytes: 6, ..DecodeLimits::default() });
+    assert_eq!("abc", String::deserialize(&mut de).unwrap());
+    match String::deserialize(&mut de) {
+        Err(Error::LimitExceeded(Limit::TotalBytes)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut de = Deserializer::from_read_ref(&buf);
+    de.set_limits(DecodeLimits { max_total_bytes: 6, ..DecodeLimits::default() });
+    assert_eq!("abc", <&str>::deserialize(&mut de).unwrap());
+    match <&str>::deserialize(&mut de) {
+        Err(Error::LimitExceeded(Limit::TotalBytes)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_limits_depth() {
+    let buf = [0x91; 8];
+
+    let mut de = Deserializer::new(&buf[..]);
+    de.set_limits(DecodeLimits { max_depth: 4, ..DecodeLimits::default() });
+    match Vec::<Vec<Vec<Vec<Vec<()>>>>>::deserialize(&mut de) {
+        Err(Error::DepthLimitExceeded) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
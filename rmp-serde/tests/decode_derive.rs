@@ -3,6 +3,7 @@ extern crate serde_derive;
 
 extern crate rmp_serde as rmps;
 
+use std::collections::BTreeMap;
 use std::io::Cursor;
 
 use serde::Deserialize;
@@ -535,3 +536,280 @@ fn pass_from_ref() {
 
     assert_eq!(Dog { name: "Bobby", age: 8 }, rmps::from_read_ref(&buf).unwrap());
 }
+
+#[test]
+fn pass_struct_map_with_unknown_fields() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Struct {
+        b: u32,
+    }
+
+    // {"a": [[nil]], "b": 42, "c": "unknown"}
+    let buf = [
+        0x83,
+        0xa1, 0x61, 0x91, 0x91, 0xc0,
+        0xa1, 0x62, 0x2a,
+        0xa1, 0x63, 0xa7, 0x75, 0x6e, 0x6b, 0x6e, 0x6f, 0x77, 0x6e,
+    ];
+
+    assert_eq!(Struct { b: 42 }, rmps::from_slice(&buf[..]).unwrap());
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Address<Zip> {
+    city: String,
+    zip: Zip,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct User<Zip> {
+    name: String,
+    address: Address<Zip>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Users<Zip> {
+    users: Vec<User<Zip>>,
+}
+
+/// A zip code that serializes to either type, to encode input with an invalid zip.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+enum AnyZip {
+    Num(u32),
+    Str(&'static str),
+}
+
+fn users_with_zip<Zip: Clone>(zip: Zip, last: Zip) -> Users<Zip> {
+    let mut users: Vec<_> = (0..43)
+        .map(|i| User {
+            name: format!("user{}", i),
+            address: Address { city: "Paris".into(), zip: zip.clone() },
+        })
+        .collect();
+    users[42].address.zip = last;
+    Users { users }
+}
+
+#[test]
+fn fail_located_struct_map() {
+    let buf = rmps::to_vec_named(&users_with_zip(AnyZip::Num(75001), AnyZip::Str("oops"))).unwrap();
+
+    let err = rmps::decode::from_slice_located::<Users<u32>>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::Syntax(..)), "{:?}", err);
+    // The invalid zip is the very last value.
+    assert_eq!(buf.len() as u64 - 5, err.offset);
+    assert_eq!(".users[42].address.zip", err.path.to_string());
+}
+
+#[test]
+fn fail_located_struct_array() {
+    let buf = rmps::to_vec(&users_with_zip(AnyZip::Num(75001), AnyZip::Str("oops"))).unwrap();
+
+    let err = rmps::decode::from_read_located::<_, Users<u32>>(&buf[..]).unwrap_err();
+    assert!(matches!(err.error, Error::Syntax(..)), "{:?}", err);
+    assert_eq!(buf.len() as u64 - 5, err.offset);
+    assert_eq!(".users[42].address.zip", err.path.to_string());
+}
+
+#[test]
+fn fail_located_truncated() {
+    let mut buf = rmps::to_vec_named(&users_with_zip(75001, 75002)).unwrap();
+    // Cut off the data of the last zip, a `u32` marker followed by 4 bytes.
+    buf.truncate(buf.len() - 2);
+
+    let err = rmps::decode::from_slice_located::<Users<u32>>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::InvalidDataRead(..)), "{:?}", err);
+    assert_eq!(buf.len() as u64 - 3, err.offset);
+    assert_eq!(".users[42].address.zip", err.path.to_string());
+}
+
+#[test]
+fn fail_located_map_keys() {
+    let mut map = BTreeMap::new();
+    map.insert("zip code", vec![1, 2, 3]);
+    map.insert("ok", vec![4, 300]);
+    let buf = rmps::to_vec(&map).unwrap();
+
+    let err = rmps::decode::from_slice_located::<BTreeMap<String, Vec<u8>>>(&buf).unwrap_err();
+    assert_eq!(".ok[1]", err.path.to_string());
+
+    let mut map = BTreeMap::new();
+    map.insert(7u64, vec!["a", "b"]);
+    let buf = rmps::to_vec(&map).unwrap();
+
+    let err = rmps::decode::from_slice_located::<BTreeMap<u64, (String, u8)>>(&buf).unwrap_err();
+    assert_eq!("[7][1]", err.path.to_string());
+
+    // The error is in a key, so there is no key to name.
+    let buf = [0x81, 0xa1, 0x61, 0xc0];
+    let err = rmps::decode::from_slice_located::<BTreeMap<u64, ()>>(&buf).unwrap_err();
+    assert_eq!(1, err.offset);
+    assert_eq!("[?]", err.path.to_string());
+}
+
+#[test]
+fn fail_located_top_level() {
+    let buf = [0xc1];
+
+    let err = rmps::decode::from_slice_located::<Users<u32>>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::TypeMismatch(rmp::Marker::Reserved)), "{:?}", err);
+    assert_eq!(0, err.offset);
+    assert_eq!(".", err.path.to_string());
+    assert_eq!("wrong msgpack marker Reserved at offset 0, path .", err.to_string());
+}
+
+#[test]
+fn pass_located_offset_without_path() {
+    let buf = rmps::to_vec(&users_with_zip(AnyZip::Num(75001), AnyZip::Str("oops"))).unwrap();
+
+    let mut de = Deserializer::new(&buf[..]);
+    let err = Users::<u32>::deserialize(&mut de).unwrap_err();
+    let err = de.locate(err);
+    assert_eq!(buf.len() as u64 - 5, err.offset);
+    assert!(err.path.segments().is_empty());
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: u8,
+    y: u8,
+}
+
+#[test]
+fn pass_struct_map_strict() {
+    // {"x": 1, "y": 2}
+    let buf = [0x82, 0xa1, 0x78, 0x01, 0xa1, 0x79, 0x02];
+    let mut de = Deserializer::new(&buf[..]).with_struct_map();
+    assert_eq!(Point { x: 1, y: 2 }, Point::deserialize(&mut de).unwrap());
+
+    // [1, 2]
+    let buf = [0x92, 0x01, 0x02];
+    let mut de = Deserializer::new(&buf[..]).with_struct_map();
+    match Point::deserialize(&mut de) {
+        Err(Error::TypeMismatch(rmp::Marker::FixArray(2))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_struct_tuple_strict() {
+    // [1, 2]
+    let buf = [0x92, 0x01, 0x02];
+    let mut de = Deserializer::new(&buf[..]).with_struct_tuple();
+    assert_eq!(Point { x: 1, y: 2 }, Point::deserialize(&mut de).unwrap());
+
+    // {"x": 1, "y": 2}
+    let buf = [0x82, 0xa1, 0x78, 0x01, 0xa1, 0x79, 0x02];
+    let mut de = Deserializer::new(&buf[..]).with_struct_tuple();
+    match Point::deserialize(&mut de) {
+        Err(Error::TypeMismatch(rmp::Marker::FixMap(2))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_struct_variant_strict() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Enum {
+        V { x: u8, y: u8 },
+    }
+
+    // {"V": [1, 2]}
+    let buf = [0x81, 0xa1, 0x56, 0x92, 0x01, 0x02];
+    let mut de = Deserializer::new(&buf[..]).with_struct_tuple();
+    assert_eq!(Enum::V { x: 1, y: 2 }, Enum::deserialize(&mut de).unwrap());
+
+    let mut de = Deserializer::new(&buf[..]).with_struct_map();
+    match Enum::deserialize(&mut de) {
+        Err(Error::TypeMismatch(rmp::Marker::FixArray(2))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_variants_strict() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Enum {
+        V1,
+        V2(u8),
+    }
+
+    // "V1", {"V2": 42}
+    let names = [0xa2, 0x56, 0x31, 0x81, 0xa2, 0x56, 0x32, 0x2a];
+    // 0, {1: 42}
+    let indices = [0x00, 0x81, 0x01, 0x2a];
+
+    let mut de = Deserializer::new(&names[..]).with_string_variants();
+    assert_eq!(Enum::V1, Enum::deserialize(&mut de).unwrap());
+    assert_eq!(Enum::V2(42), Enum::deserialize(&mut de).unwrap());
+
+    let mut de = Deserializer::new(&indices[..]).with_integer_variants();
+    assert_eq!(Enum::V1, Enum::deserialize(&mut de).unwrap());
+    assert_eq!(Enum::V2(42), Enum::deserialize(&mut de).unwrap());
+
+    // Both are accepted by default.
+    for buf in [&names[..], &indices[..]] {
+        let mut de = Deserializer::new(buf);
+        assert_eq!(Enum::V1, Enum::deserialize(&mut de).unwrap());
+        assert_eq!(Enum::V2(42), Enum::deserialize(&mut de).unwrap());
+    }
+
+    let mut de = Deserializer::new(&names[..]).with_integer_variants();
+    match Enum::deserialize(&mut de) {
+        Err(Error::TypeMismatch(rmp::Marker::FixStr(2))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut de = Deserializer::new(&indices[1..]).with_string_variants();
+    match Enum::deserialize(&mut de) {
+        Err(Error::TypeMismatch(rmp::Marker::FixPos(1))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_enum_layouts() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Enum {
+        V1,
+        V2(u8),
+        V3 { a: u8 },
+    }
+
+    let cases: &[(&[u8], Enum)] = &[
+        // 0, "V1", {0: nil}, [0, nil]
+        (&[0x00], Enum::V1),
+        (&[0xa2, 0x56, 0x31], Enum::V1),
+        (&[0x81, 0x00, 0xc0], Enum::V1),
+        (&[0x92, 0x00, 0xc0], Enum::V1),
+        // {1: 42}, {"V2": 42}, [1, 42], ["V2", 42]
+        (&[0x81, 0x01, 0x2a], Enum::V2(42)),
+        (&[0x81, 0xa2, 0x56, 0x32, 0x2a], Enum::V2(42)),
+        (&[0x92, 0x01, 0x2a], Enum::V2(42)),
+        (&[0x92, 0xa2, 0x56, 0x32, 0x2a], Enum::V2(42)),
+        // [2, [7]], [2, {"a": 7}]
+        (&[0x92, 0x02, 0x91, 0x07], Enum::V3 { a: 7 }),
+        (&[0x92, 0x02, 0x81, 0xa1, 0x61, 0x07], Enum::V3 { a: 7 }),
+    ];
+
+    for (buf, expected) in cases {
+        assert_eq!(*expected, rmps::from_slice::<Enum>(buf).unwrap(), "{:?}", buf);
+    }
+}
+
+#[test]
+fn fail_enum_array_length() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    enum Enum {
+        V(u8),
+    }
+
+    // [0, 1, 2]
+    let buf = [0x93, 0x00, 0x01, 0x02];
+    match rmps::from_slice::<Enum>(&buf) {
+        Err(Error::LengthMismatch(3)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
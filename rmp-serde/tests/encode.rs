@@ -368,3 +368,43 @@ fn pass_raw_ref_invalid_utf8() {
 fn serializer_one_type_arg() {
     let _s: rmp_serde::Serializer<&mut dyn std::io::Write>;
 }
+
+#[test]
+fn pass_canonical_floats() {
+    let mut buf = Vec::new();
+    let mut se = Serializer::new(&mut buf).with_canonical();
+
+    1.5f64.serialize(&mut se).unwrap();
+    0.1f64.serialize(&mut se).unwrap();
+    f32::NAN.serialize(&mut se).unwrap();
+
+    assert_eq!(vec![
+        0xca, 0x3f, 0xc0, 0x00, 0x00,
+        0xcb, 0x3f, 0xb9, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a,
+        0xca, 0x7f, 0xc0, 0x00, 0x00,
+    ], buf);
+}
+
+#[test]
+fn pass_canonical_map_sorted() {
+    use std::collections::HashMap;
+
+    let mut map = HashMap::new();
+    for key in ["ccc", "bb", "a", "b", "c", "aa"] {
+        map.insert(key, vec![key.len()]);
+    }
+
+    let mut buf = Vec::new();
+    map.serialize(&mut Serializer::new(&mut buf).with_canonical()).unwrap();
+
+    assert_eq!(vec![
+        0x86,
+        0xa1, 0x61, 0x91, 0x01,
+        0xa1, 0x62, 0x91, 0x01,
+        0xa1, 0x63, 0x91, 0x01,
+        0xa2, 0x61, 0x61, 0x91, 0x02,
+        0xa2, 0x62, 0x62, 0x91, 0x02,
+        0xa3, 0x63, 0x63, 0x63, 0x91, 0x03,
+    ], buf);
+    assert!(rmp::decode::is_canonical(&buf));
+}
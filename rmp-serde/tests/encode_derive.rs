@@ -34,6 +34,46 @@ fn pass_unit_variant() {
     assert_eq!(vec![0xa2, 0x56, 0x31, 0xa2, 0x56, 0x32], buf);
 }
 
+#[test]
+fn pass_integer_variants() {
+    #[derive(Serialize)]
+    enum Enum {
+        V1,
+        V2(u64),
+    }
+
+    let mut se = Serializer::new(Vec::new()).with_integer_variants();
+    Enum::V1.serialize(&mut se).unwrap();
+    Enum::V2(42).serialize(&mut se).unwrap();
+
+    // Expect: 0, {1 => 42}
+    assert_eq!(vec![0x00, 0x81, 0x01, 0x2a], se.into_inner());
+}
+
+#[test]
+fn pass_enum_array() {
+    #[derive(Serialize)]
+    enum Enum {
+        V1,
+        V2(u64),
+        V3(u8, u8),
+    }
+
+    let mut se = Serializer::new(Vec::new()).with_enum_array().with_integer_variants();
+    Enum::V1.serialize(&mut se).unwrap();
+    Enum::V2(42).serialize(&mut se).unwrap();
+    Enum::V3(1, 2).serialize(&mut se).unwrap();
+
+    // Expect: 0, [1, 42], [2, [1, 2]]
+    assert_eq!(vec![0x00, 0x92, 0x01, 0x2a, 0x92, 0x02, 0x92, 0x01, 0x02], se.into_inner());
+
+    let mut se = Serializer::new(Vec::new()).with_enum_array().with_enum_map();
+    Enum::V2(42).serialize(&mut se).unwrap();
+
+    // Expect: {"V2" => 42}
+    assert_eq!(vec![0x81, 0xa2, 0x56, 0x32, 0x2a], se.into_inner());
+}
+
 #[test]
 fn pass_newtype_struct() {
     #[derive(Serialize)]
@@ -360,3 +400,304 @@ fn pass_struct_as_map_using_triple_ext_many_times() {
     assert_eq!(vec![0x82, 0xa4, 0x6e, 0x61, 0x6d, 0x65, 0xa5, 0x42, 0x6f, 0x62, 0x62, 0x79, 0xa3, 0x61, 0x67, 0x65, 0x08],
                se.into_inner());
 }
+
+#[test]
+fn pass_canonical_struct_map_sorted() {
+    #[derive(Serialize)]
+    struct Inner {
+        z: u8,
+        y: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        name: &'static str,
+        id: u32,
+        inner: Inner,
+    }
+
+    let val = Outer { name: "x", id: 7, inner: Inner { z: 1, y: 0.5 } };
+
+    let mut buf = Vec::new();
+    val.serialize(&mut Serializer::new(&mut buf).with_struct_map().with_canonical()).unwrap();
+
+    // Expect: {"id": 7, "name": "x", "inner": {"y": 0.5, "z": 1}}, shorter keys come first.
+    assert_eq!(vec![
+        0x83,
+        0xa2, 0x69, 0x64, 0x07,
+        0xa4, 0x6e, 0x61, 0x6d, 0x65, 0xa1, 0x78,
+        0xa5, 0x69, 0x6e, 0x6e, 0x65, 0x72,
+        0x82, 0xa1, 0x79, 0xca, 0x3f, 0x00, 0x00, 0x00, 0xa1, 0x7a, 0x01,
+    ], buf);
+    assert!(rmp::decode::is_canonical(&buf));
+}
+
+#[test]
+fn pass_canonical_struct_tuple_keeps_field_order() {
+    #[derive(Serialize)]
+    struct Struct {
+        b: u8,
+        a: u8,
+    }
+
+    let mut buf = Vec::new();
+    Struct { b: 2, a: 1 }.serialize(&mut Serializer::new(&mut buf).with_canonical()).unwrap();
+
+    assert_eq!(vec![0x92, 0x02, 0x01], buf);
+}
+
+#[test]
+fn pass_canonical_struct_variant_sorted() {
+    #[derive(Serialize)]
+    enum Enum {
+        V { b: u8, a: u8 },
+    }
+
+    let mut buf = Vec::new();
+    Enum::V { b: 2, a: 1 }.serialize(&mut Serializer::new(&mut buf).with_struct_map().with_canonical()).unwrap();
+
+    // Expect: {"V": {"a": 1, "b": 2}}.
+    assert_eq!(vec![0x81, 0xa1, 0x56, 0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0x02], buf);
+}
+
+#[test]
+fn pass_encoded_size_matches_to_vec() {
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    enum Kind {
+        Unit,
+        Pair(u32, i64),
+    }
+
+    #[derive(Serialize)]
+    struct Batch {
+        id: u64,
+        name: String,
+        kinds: Vec<Kind>,
+        tags: BTreeMap<String, Option<f64>>,
+        raw: serde_bytes::ByteBuf,
+    }
+
+    let mut tags = BTreeMap::new();
+    tags.insert("a".to_owned(), Some(0.5));
+    tags.insert("b".to_owned(), None);
+
+    let val = Batch {
+        id: 70000,
+        name: "x".repeat(300),
+        kinds: vec![Kind::Unit, Kind::Pair(1, -1000)],
+        tags,
+        raw: serde_bytes::ByteBuf::from(vec![0; 20]),
+    };
+
+    assert_eq!(rmps::to_vec(&val).unwrap().len(), rmps::encoded_size(&val).unwrap());
+    assert_eq!(rmps::to_vec_named(&val).unwrap().len(), rmps::encoded_size_named(&val).unwrap());
+}
+
+#[test]
+fn pass_encoded_size_of_unknown_length_seq() {
+    struct Evens(u32);
+
+    impl Serialize for Evens {
+        fn serialize<S: serde::Serializer>(&self, se: S) -> Result<S::Ok, S::Error> {
+            se.collect_seq((0..self.0).filter(|n| n % 2 == 0))
+        }
+    }
+
+    let val = Evens(100);
+    assert_eq!(rmps::to_vec(&val).unwrap().len(), rmps::encoded_size(&val).unwrap());
+}
+
+#[test]
+fn pass_length_patching_matches_buffering() {
+    use std::collections::BTreeMap;
+
+    struct Evens(u32);
+
+    impl Serialize for Evens {
+        fn serialize<S: serde::Serializer>(&self, se: S) -> Result<S::Ok, S::Error> {
+            se.collect_seq((0..self.0).filter(|n| n % 2 == 0))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        small: Evens,
+        large: Evens,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        id: u32,
+        #[serde(flatten)]
+        inner: Inner,
+        #[serde(flatten)]
+        extra: BTreeMap<String, Vec<Evens>>,
+    }
+
+    let mut extra = BTreeMap::new();
+    extra.insert("b".to_owned(), vec![Evens(3), Evens(40)]);
+    extra.insert("a".to_owned(), vec![]);
+    let val = Outer { id: 1, inner: Inner { small: Evens(5), large: Evens(200_000) }, extra };
+
+    let mut buffered = Serializer::new(Vec::new());
+    val.serialize(&mut buffered).unwrap();
+    let mut patched = Serializer::new(Vec::new()).with_minimal_length_patching();
+    val.serialize(&mut patched).unwrap();
+    assert_eq!(buffered.into_inner(), patched.into_inner());
+
+    let mut buffered = Serializer::new(Vec::new()).with_struct_map();
+    val.serialize(&mut buffered).unwrap();
+    assert_eq!(buffered.into_inner(), rmps::to_vec_named(&val).unwrap());
+
+    let mut buffered = Serializer::new(Vec::new()).with_canonical();
+    val.serialize(&mut buffered).unwrap();
+    let mut patched = Serializer::new(Vec::new()).with_canonical().with_minimal_length_patching();
+    val.serialize(&mut patched).unwrap();
+    assert_eq!(buffered.into_inner(), patched.into_inner());
+}
+
+#[test]
+fn pass_length_patching_fixed_headers() {
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Struct {
+        a: u8,
+        #[serde(flatten)]
+        rest: BTreeMap<String, Vec<u8>>,
+    }
+
+    let mut rest = BTreeMap::new();
+    rest.insert("b".to_owned(), vec![1, 2]);
+
+    let mut se = Serializer::new(Vec::new()).with_length_patching();
+    Struct { a: 0, rest }.serialize(&mut se).unwrap();
+
+    // Expect: { "a": 0, "b": [1, 2] }, with a 32-bit length for the flattened struct only.
+    assert_eq!(
+        vec![0xdf, 0x00, 0x00, 0x00, 0x02, 0xa1, 0x61, 0x00, 0xa1, 0x62, 0x92, 0x01, 0x02],
+        se.into_inner()
+    );
+}
+
+#[test]
+fn pass_skip_none_struct_map() {
+    #[derive(Serialize)]
+    struct Struct {
+        a: Option<u8>,
+        b: u8,
+        c: Option<Option<u8>>,
+    }
+
+    let val = Struct { a: None, b: 1, c: Some(None) };
+
+    let mut se = Serializer::new(Vec::new()).with_struct_map().with_skip_none();
+    val.serialize(&mut se).unwrap();
+    // Expect: {"b": 1}
+    assert_eq!(vec![0x81, 0xa1, 0x62, 0x01], se.into_inner());
+
+    // A wrapped `Some(None)` is not nil.
+    let mut se = Serializer::new(Vec::new()).with_struct_map().with_wrapped_options().with_skip_none();
+    val.serialize(&mut se).unwrap();
+    // Expect: {"b": 1, "c": [nil]}
+    assert_eq!(vec![0x82, 0xa1, 0x62, 0x01, 0xa1, 0x63, 0x91, 0xc0], se.into_inner());
+
+    // Structs serialized as a tuple keep all their fields.
+    let mut se = Serializer::new(Vec::new()).with_skip_none();
+    val.serialize(&mut se).unwrap();
+    // Expect: [nil, 1, nil]
+    assert_eq!(vec![0x93, 0xc0, 0x01, 0xc0], se.into_inner());
+}
+
+#[test]
+fn pass_skip_none_length_patching() {
+    #[derive(Serialize)]
+    struct Inner {
+        a: Option<u8>,
+        b: Option<u8>,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        x: u8,
+        inner: Inner,
+        y: Option<Inner>,
+        z: Option<u8>,
+    }
+
+    // Values ending with the nil marker aren't nil themselves.
+    let val = Outer { x: 0xc0, inner: Inner { a: Some(1), b: None }, y: None, z: Some(0xc0) };
+
+    let mut buffered = Serializer::new(Vec::new()).with_struct_map().with_skip_none();
+    val.serialize(&mut buffered).unwrap();
+    // Expect: {"x": 192, "inner": {"a": 1}, "z": 192}
+    let expected = vec![
+        0x83, 0xa1, 0x78, 0xcc, 0xc0, 0xa5, 0x69, 0x6e, 0x6e, 0x65, 0x72, 0x81, 0xa1, 0x61, 0x01,
+        0xa1, 0x7a, 0xcc, 0xc0,
+    ];
+    assert_eq!(expected, buffered.into_inner());
+
+    let mut patched = Serializer::new(Vec::new()).with_struct_map().with_skip_none().with_minimal_length_patching();
+    val.serialize(&mut patched).unwrap();
+    assert_eq!(expected, patched.into_inner());
+
+    // Struct headers are sized for all fields, so they shrink in place.
+    let mut patched = Serializer::new(Vec::new()).with_struct_map().with_skip_none().with_length_patching();
+    val.serialize(&mut patched).unwrap();
+    assert_eq!(expected, patched.into_inner());
+
+    let mut buffered = Serializer::new(Vec::new()).with_struct_index().with_skip_none();
+    val.serialize(&mut buffered).unwrap();
+    // Expect: {0: 192, 1: {0: 1}, 3: 192}
+    let expected = vec![0x83, 0x00, 0xcc, 0xc0, 0x01, 0x81, 0x00, 0x01, 0x03, 0xcc, 0xc0];
+    assert_eq!(expected, buffered.into_inner());
+
+    let mut patched = Serializer::new(Vec::new()).with_struct_index().with_skip_none().with_minimal_length_patching();
+    val.serialize(&mut patched).unwrap();
+    assert_eq!(expected, patched.into_inner());
+}
+
+#[test]
+fn pass_skip_none_canonical() {
+    #[derive(Serialize)]
+    struct Struct {
+        b: Option<u8>,
+        a: u8,
+        c: Option<u8>,
+    }
+
+    let val = Struct { b: Some(2), a: 1, c: None };
+
+    let mut se = Serializer::new(Vec::new()).with_struct_map().with_skip_none().with_canonical();
+    val.serialize(&mut se).unwrap();
+    // Expect: {"a": 1, "b": 2}
+    assert_eq!(vec![0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0x02], se.into_inner());
+}
+
+#[test]
+fn pass_struct_index() {
+    #[derive(Serialize)]
+    struct Struct {
+        a: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        b: Option<u8>,
+        c: u8,
+    }
+
+    let mut se = Serializer::new(Vec::new()).with_struct_index();
+    Struct { a: 1, b: Some(2), c: 3 }.serialize(&mut se).unwrap();
+    // Expect: {0: 1, 1: 2, 2: 3}
+    assert_eq!(vec![0x83, 0x00, 0x01, 0x01, 0x02, 0x02, 0x03], se.into_inner());
+
+    // Skipped fields keep the indices of the following ones.
+    let mut se = Serializer::new(Vec::new()).with_struct_index();
+    Struct { a: 1, b: None, c: 3 }.serialize(&mut se).unwrap();
+    // Expect: {0: 1, 2: 3}
+    assert_eq!(vec![0x82, 0x00, 0x01, 0x02, 0x03], se.into_inner());
+
+    let mut se = Serializer::new(Vec::new()).with_struct_index().with_skip_none();
+    Struct { a: 1, b: None, c: 3 }.serialize(&mut se).unwrap();
+    assert_eq!(vec![0x82, 0x00, 0x01, 0x02, 0x03], se.into_inner());
+}
@@ -81,6 +81,185 @@ fn round_trip_nested_option() {
     assert_eq!(expected, Deserialize::deserialize(&mut de).unwrap());
 }
 
+#[test]
+fn round_trip_wrapped_option() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Patch {
+        name: Option<Option<String>>,
+        flag: Option<()>,
+        deep: Option<Option<Option<u8>>>,
+    }
+
+    let cases = [
+        Patch { name: None, flag: None, deep: None },
+        Patch { name: Some(None), flag: Some(()), deep: Some(None) },
+        Patch { name: Some(Some("a".into())), flag: None, deep: Some(Some(None)) },
+        Patch { name: None, flag: Some(()), deep: Some(Some(Some(7))) },
+    ];
+
+    for expected in &cases {
+        let mut buf = Vec::new();
+        expected.serialize(&mut Serializer::new(&mut buf).with_wrapped_options()).unwrap();
+
+        let mut de = Deserializer::new(&buf[..]).with_wrapped_options();
+        assert_eq!(*expected, Deserialize::deserialize(&mut de).unwrap());
+    }
+
+    // Expect: [[["a"]], nil, [[nil]]]
+    let mut buf = Vec::new();
+    cases[2].serialize(&mut Serializer::new(&mut buf).with_wrapped_options()).unwrap();
+    assert_eq!(vec![0x93, 0x91, 0x91, 0xa1, 0x61, 0xc0, 0x91, 0x91, 0xc0], buf);
+
+    // An unwrapped value is rejected.
+    let buf = [0x07];
+    let mut de = Deserializer::new(&buf[..]).with_wrapped_options();
+    match Option::<u8>::deserialize(&mut de) {
+        Err(rmps::decode::Error::TypeMismatch(rmp::Marker::FixPos(7))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn round_trip_skip_none() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Enum {
+        V { x: Option<u32>, y: u8 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: u32,
+        name: Option<String>,
+        tags: Option<Vec<String>>,
+        nested: Option<Box<Record>>,
+        variant: Enum,
+    }
+
+    let expected = Record {
+        id: 1,
+        name: None,
+        tags: Some(vec!["a".into()]),
+        nested: Some(Box::new(Record {
+            id: 2,
+            name: Some("b".into()),
+            tags: None,
+            nested: None,
+            variant: Enum::V { x: Some(3), y: 4 },
+        })),
+        variant: Enum::V { x: None, y: 5 },
+    };
+
+    let mut buf = Vec::new();
+    expected.serialize(&mut Serializer::new(&mut buf).with_struct_map().with_skip_none()).unwrap();
+
+    let mut named = Vec::new();
+    expected.serialize(&mut Serializer::new(&mut named).with_struct_map()).unwrap();
+    assert!(buf.len() < named.len());
+
+    let mut de = Deserializer::new(&buf[..]).with_struct_map();
+    assert_eq!(expected, Deserialize::deserialize(&mut de).unwrap());
+}
+
+#[test]
+fn round_trip_struct_index() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Enum {
+        V { x: u8, y: Option<u8> },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct V1 {
+        id: u32,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct V2 {
+        id: u32,
+        name: String,
+        email: Option<String>,
+        variant: Option<Enum>,
+    }
+
+    let expected = V2 {
+        id: 1,
+        name: "a".into(),
+        email: Some("a@b".into()),
+        variant: Some(Enum::V { x: 2, y: None }),
+    };
+    let mut buf = Vec::new();
+    expected.serialize(&mut Serializer::new(&mut buf).with_struct_index().with_skip_none()).unwrap();
+
+    let mut de = Deserializer::new(&buf[..]).with_struct_map();
+    assert_eq!(expected, Deserialize::deserialize(&mut de).unwrap());
+
+    // Newer fields are ignored by older readers.
+    let mut de = Deserializer::new(&buf[..]);
+    assert_eq!(V1 { id: 1, name: "a".into() }, Deserialize::deserialize(&mut de).unwrap());
+
+    // Missing fields are `None` for newer readers.
+    let mut buf = Vec::new();
+    V1 { id: 3, name: "c".into() }.serialize(&mut Serializer::new(&mut buf).with_struct_index()).unwrap();
+    let mut de = Deserializer::new(&buf[..]);
+    let expected = V2 { id: 3, name: "c".into(), email: None, variant: None };
+    assert_eq!(expected, Deserialize::deserialize(&mut de).unwrap());
+}
+
+#[test]
+fn round_struct_index_skip() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Skip {
+        #[serde(skip)]
+        a: u32,
+        b: u32,
+        c: u32,
+    }
+
+    let mut buf = Vec::new();
+    Skip { a: 5, b: 7, c: 9 }.serialize(&mut Serializer::new(&mut buf).with_struct_index()).unwrap();
+    assert_eq!(vec![0x82, 0x00, 0x07, 0x01, 0x09], buf);
+    assert_eq!(Skip { a: 0, b: 7, c: 9 }, rmps::from_slice(&buf).unwrap());
+
+    // Unsupported: the field still takes an index when deserializing.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct SkipSerializing {
+        #[serde(skip_serializing)]
+        #[allow(dead_code)]
+        a: u32,
+        b: u32,
+        c: u32,
+    }
+
+    let mut buf = Vec::new();
+    SkipSerializing { a: 5, b: 7, c: 9 }.serialize(&mut Serializer::new(&mut buf).with_struct_index()).unwrap();
+    assert_eq!(vec![0x82, 0x00, 0x07, 0x01, 0x09], buf);
+    let err = rmps::from_slice::<SkipSerializing>(&buf).unwrap_err();
+    assert_eq!("missing field `c`", err.to_string());
+}
+
+#[test]
+fn round_struct_index_located() {
+    #[derive(Debug, Serialize)]
+    struct Wrong {
+        id: u32,
+        name: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Right {
+        #[allow(dead_code)]
+        id: u32,
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    let mut buf = Vec::new();
+    Wrong { id: 1, name: 2 }.serialize(&mut Serializer::new(&mut buf).with_struct_index()).unwrap();
+
+    let err = rmps::decode::from_slice_located::<Vec<Right>>(&[&[0x91][..], &buf].concat()).unwrap_err();
+    assert_eq!("[0].name", err.path.to_string());
+}
+
 #[test]
 fn round_trip_optional_enum() {
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -609,6 +788,69 @@ fn checked_seq_access_len()
         .expect_err("Input round tripped into Output; this shouldn't happen");
 }
 
+#[test]
+fn roundtrip_timestamp() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Event {
+        #[serde(with = "rmp_serde::timestamp")]
+        at: SystemTime,
+        #[serde(with = "rmp_serde::timestamp")]
+        uptime: Duration,
+        #[serde(with = "rmp_serde::timestamp")]
+        ts: rmp::Timestamp,
+    }
+
+    for at in [
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::from_secs(1514862245),
+        UNIX_EPOCH + Duration::new(1514862245, 678901234),
+        UNIX_EPOCH + Duration::from_secs(1 << 34),
+        UNIX_EPOCH - Duration::new(1, 1),
+    ] {
+        assert_roundtrips(Event {
+            at,
+            uptime: Duration::new(42, 1),
+            ts: rmp::Timestamp::new(-42, 999_999_999).unwrap(),
+        });
+    }
+}
+
+#[test]
+fn timestamp_is_ext() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[derive(Serialize)]
+    struct Event {
+        #[serde(with = "rmp_serde::timestamp")]
+        at: std::time::SystemTime,
+    }
+
+    let buf = rmp_serde::to_vec(&Event { at: UNIX_EPOCH + Duration::new(1514862245, 678901234) }).unwrap();
+    let val = rmpv::decode::read_value(&mut &buf[..]).unwrap();
+
+    assert_eq!(Some(rmp::Timestamp::new(1514862245, 678901234).unwrap()), val[0].as_timestamp());
+}
+
+#[test]
+fn timestamp_rejects_other_values() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Wrapper(#[serde(with = "rmp_serde::timestamp")] std::time::Duration);
+
+    // Ext of another type.
+    rmp_serde::from_slice::<Wrapper>(&[0xd6, 0x01, 0x00, 0x00, 0x00, 0x2a]).unwrap_err();
+    // Timestamp data of an invalid length.
+    rmp_serde::from_slice::<Wrapper>(&[0xd5, 0xff, 0x00, 0x2a]).unwrap_err();
+    // Duration before the epoch.
+    rmp_serde::from_slice::<Wrapper>(&[
+        0xc7, 0x0c, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ]).unwrap_err();
+    // Not an ext at all.
+    rmp_serde::from_slice::<Wrapper>(&[0x2a]).unwrap_err();
+}
+
 #[ignore]
 #[test]
 fn roundtrip_some_failures() {
@@ -654,6 +896,24 @@ fn assert_roundtrips<T: PartialEq + std::fmt::Debug + Serialize + for<'a> Deseri
         },
         |d| d.with_human_readable(),
     );
+    assert_roundtrips_config(
+        &val,
+        ".with_struct_index()",
+        |s| s.with_struct_index(),
+        |d| d.with_struct_map(),
+    );
+    assert_roundtrips_config(
+        &val,
+        ".with_enum_array().with_integer_variants()",
+        |s| s.with_enum_array().with_integer_variants(),
+        |d| d.with_integer_variants(),
+    );
+    assert_roundtrips_config(
+        &val,
+        ".with_enum_array().with_struct_map()",
+        |s| s.with_enum_array().with_struct_map(),
+        |d| d.with_struct_map().with_string_variants(),
+    );
 }
 
 #[cfg(test)]
@@ -42,3 +42,35 @@ fn from_string_read_str(b: &mut Bencher) {
         test::black_box(res);
     });
 }
+
+#[bench]
+fn from_string_skip_value(b: &mut Bencher) {
+    // Lorem ipsum dolor sit amet.
+    let buf = [
+        0xbb, 0x4c, 0x6f, 0x72, 0x65, 0x6d, 0x20, 0x69, 0x70, 0x73,
+        0x75, 0x6d, 0x20, 0x64, 0x6f, 0x6c, 0x6f, 0x72, 0x20, 0x73,
+        0x69, 0x74, 0x20, 0x61, 0x6d, 0x65, 0x74, 0x2e
+    ];
+
+    b.iter(|| {
+        let mut rd = &buf[..];
+        skip_value(&mut rd).unwrap();
+        test::black_box(rd);
+    });
+}
+
+#[bench]
+fn from_nested_map_skip_value(b: &mut Bencher) {
+    // {"a": [1, [], {}], "b": {"c": [[2]]}}
+    let buf = [
+        0x82,
+        0xa1, 0x61, 0x93, 0x01, 0x90, 0x80,
+        0xa1, 0x62, 0x81, 0xa1, 0x63, 0x91, 0x91, 0x02,
+    ];
+
+    b.iter(|| {
+        let mut rd = &buf[..];
+        skip_value(&mut rd).unwrap();
+        test::black_box(rd);
+    });
+}
@@ -0,0 +1,284 @@
+//! Asynchronous decoding functions, generated for each supported async I/O library.
+
+use std::io;
+
+use super::push::header_data_len;
+use super::reader::read_event;
+use super::{Bytes, Event, PushDecodeError, ReaderError, ValueReadError};
+use crate::Marker;
+
+/// Maximum number of bytes of string, binary or extension data requested from the reader at once
+/// by `read_value_bytes_async`, so that a forged length can't make it allocate a huge buffer
+/// upfront.
+pub(super) const READ_CHUNK_LEN: usize = 8 * 1024;
+
+/// Decodes the header data following the given marker, which must have been read completely.
+pub(super) fn decode_header(marker: Marker, data: &[u8]) -> Event {
+    read_event(&mut Bytes::new(data), marker).expect("complete header must be decodable")
+}
+
+/// Returns the length of the header data following the given marker.
+pub(super) fn header_len(marker: Marker) -> Result<usize, ValueReadError<io::Error>> {
+    header_data_len(marker).map_err(|_| ValueReadError::TypeMismatch(marker))
+}
+
+impl From<PushDecodeError> for ReaderError<io::Error> {
+    #[cold]
+    fn from(err: PushDecodeError) -> Self {
+        match err {
+            PushDecodeError::TypeMismatch(marker) => ReaderError::TypeMismatch(marker),
+            PushDecodeError::DepthLimitExceeded => ReaderError::DepthLimitExceeded,
+        }
+    }
+}
+
+macro_rules! async_decode_module {
+    ($(#[$attr:meta])* $name:ident, $($io:tt)+) => {
+        $(#[$attr])*
+        pub mod $name {
+            use std::io;
+
+            use num_traits::cast::FromPrimitive;
+            use $($io)+::{AsyncRead, AsyncReadExt};
+
+            use super::async_io::{decode_header, header_len, READ_CHUNK_LEN};
+            use super::{
+                Event, ExtMeta, MarkerReadError, NumValueReadError, PushDecoder, PushStatus,
+                ReaderError, ValueReadError,
+            };
+            use crate::Marker;
+
+            /// Reads the header data following the given marker.
+            async fn read_header<R>(rd: &mut R, marker: Marker) -> Result<Event, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                let mut buf = [0; 8];
+                let len = header_len(marker)?;
+                rd.read_exact(&mut buf[..len]).await.map_err(ValueReadError::InvalidDataRead)?;
+
+                Ok(decode_header(marker, &buf[..len]))
+            }
+
+            /// Attempts to read a single byte from the given reader and to decode it as a
+            /// MessagePack marker.
+            pub async fn read_marker_async<R>(rd: &mut R) -> Result<Marker, MarkerReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                let mut buf = [0];
+                rd.read_exact(&mut buf).await.map_err(MarkerReadError)?;
+
+                Ok(Marker::from_u8(buf[0]))
+            }
+
+            /// Attempts to read a nil value, see [`read_nil`](crate::decode::read_nil).
+            pub async fn read_nil_async<R>(rd: &mut R) -> Result<(), ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    Marker::Null => Ok(()),
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read a boolean value, see [`read_bool`](crate::decode::read_bool).
+            pub async fn read_bool_async<R>(rd: &mut R) -> Result<bool, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    Marker::True => Ok(true),
+                    Marker::False => Ok(false),
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read an integer of any size and to convert it to the requested type,
+            /// see [`read_int`](crate::decode::read_int).
+            pub async fn read_int_async<T, R>(rd: &mut R) -> Result<T, NumValueReadError<io::Error>>
+            where
+                T: FromPrimitive,
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                let marker = read_marker_async(rd).await?;
+                match marker {
+                    Marker::FixPos(..) | Marker::FixNeg(..) |
+                    Marker::U8 | Marker::U16 | Marker::U32 | Marker::U64 |
+                    Marker::I8 | Marker::I16 | Marker::I32 | Marker::I64 => {}
+                    marker => return Err(NumValueReadError::TypeMismatch(marker)),
+                }
+
+                let val = match read_header(rd, marker).await? {
+                    Event::UInt(val) => T::from_u64(val),
+                    Event::Int(val) => T::from_i64(val),
+                    _ => unreachable!(),
+                };
+                val.ok_or(NumValueReadError::OutOfRange)
+            }
+
+            /// Attempts to read an `f32` value, see [`read_f32`](crate::decode::read_f32).
+            pub async fn read_f32_async<R>(rd: &mut R) -> Result<f32, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    Marker::F32 => match read_header(rd, Marker::F32).await? {
+                        Event::F32(val) => Ok(val),
+                        _ => unreachable!(),
+                    },
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read an `f64` value, see [`read_f64`](crate::decode::read_f64).
+            pub async fn read_f64_async<R>(rd: &mut R) -> Result<f64, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    Marker::F64 => match read_header(rd, Marker::F64).await? {
+                        Event::F64(val) => Ok(val),
+                        _ => unreachable!(),
+                    },
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read a string length, see [`read_str_len`](crate::decode::read_str_len).
+            pub async fn read_str_len_async<R>(rd: &mut R) -> Result<u32, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    marker @ (Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32) => {
+                        match read_header(rd, marker).await? {
+                            Event::Str(len) => Ok(len),
+                            _ => unreachable!(),
+                        }
+                    }
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read a binary length, see [`read_bin_len`](crate::decode::read_bin_len).
+            pub async fn read_bin_len_async<R>(rd: &mut R) -> Result<u32, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    marker @ (Marker::Bin8 | Marker::Bin16 | Marker::Bin32) => {
+                        match read_header(rd, marker).await? {
+                            Event::Bin(len) => Ok(len),
+                            _ => unreachable!(),
+                        }
+                    }
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read an array length, see
+            /// [`read_array_len`](crate::decode::read_array_len).
+            pub async fn read_array_len_async<R>(rd: &mut R) -> Result<u32, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    marker @ (Marker::FixArray(..) | Marker::Array16 | Marker::Array32) => {
+                        match read_header(rd, marker).await? {
+                            Event::ArrayStart(len) => Ok(len),
+                            _ => unreachable!(),
+                        }
+                    }
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read a map length, see [`read_map_len`](crate::decode::read_map_len).
+            pub async fn read_map_len_async<R>(rd: &mut R) -> Result<u32, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    marker @ (Marker::FixMap(..) | Marker::Map16 | Marker::Map32) => {
+                        match read_header(rd, marker).await? {
+                            Event::MapStart(len) => Ok(len),
+                            _ => unreachable!(),
+                        }
+                    }
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read the type and the length of an extension, see
+            /// [`read_ext_meta`](crate::decode::read_ext_meta).
+            pub async fn read_ext_meta_async<R>(rd: &mut R) -> Result<ExtMeta, ValueReadError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                match read_marker_async(rd).await? {
+                    marker @ (Marker::FixExt1 | Marker::FixExt2 | Marker::FixExt4 |
+                              Marker::FixExt8 | Marker::FixExt16 |
+                              Marker::Ext8 | Marker::Ext16 | Marker::Ext32) => {
+                        match read_header(rd, marker).await? {
+                            Event::Ext { ty, len } => Ok(ExtMeta { typeid: ty, size: len }),
+                            _ => unreachable!(),
+                        }
+                    }
+                    marker => Err(ValueReadError::TypeMismatch(marker)),
+                }
+            }
+
+            /// Attempts to read one complete MessagePack value, appending its encoded bytes to
+            /// the given buffer and returning their number.
+            ///
+            /// This frames a single value in a stream, so that it can be decoded by the
+            /// synchronous functions afterwards. Nothing past the end of the value is read from
+            /// the reader, and the buffer only grows as the data actually arrives, regardless of
+            /// the lengths claimed by the headers. Arrays and maps may be nested up to 1024 levels
+            /// deep.
+            ///
+            /// Headers are read in small pieces, so it is advisable to wrap unbuffered readers,
+            /// like sockets, into a buffered reader.
+            ///
+            /// # Errors
+            ///
+            /// This function will return `ReaderError` on any I/O error, when the reserved marker
+            /// is encountered or when the depth limit is exceeded. Reaching the end of the stream
+            /// before the value starts is reported as `ReaderError::InvalidMarkerRead` with
+            /// `ErrorKind::UnexpectedEof`. The buffer contains the bytes read so far on errors.
+            pub async fn read_value_bytes_async<R>(rd: &mut R, buf: &mut Vec<u8>) -> Result<usize, ReaderError<io::Error>>
+            where
+                R: AsyncRead + Unpin + ?Sized,
+            {
+                let start = buf.len();
+                let mut pos = start;
+                let mut dec = PushDecoder::new();
+
+                loop {
+                    let (consumed, status) = dec.feed(&buf[pos..])?;
+                    pos += consumed;
+
+                    match status {
+                        PushStatus::NeedMore(len) => {
+                            let len = len.min(READ_CHUNK_LEN);
+                            let end = buf.len();
+                            buf.resize(end + len, 0);
+                            if let Err(err) = rd.read_exact(&mut buf[end..]).await {
+                                buf.truncate(end);
+                                return Err(if end == start {
+                                    ReaderError::InvalidMarkerRead(err)
+                                } else {
+                                    ReaderError::InvalidDataRead(err)
+                                });
+                            }
+                        }
+                        PushStatus::Value => return Ok(buf.len() - start),
+                        PushStatus::Event(..) | PushStatus::Data(..) => {}
+                    }
+                }
+            }
+        }
+    };
+}
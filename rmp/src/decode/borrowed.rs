@@ -0,0 +1,229 @@
+//! Zero-copy decoding of values borrowed from a [`Bytes`] slice.
+
+use core::iter::FusedIterator;
+
+use super::bytes::BytesReadError;
+use super::skip::skip_values_trusted;
+use super::{
+    read_array_len, read_bin_len, read_ext_meta, read_map_len, skip_value_with_max_depth, Bytes,
+    RmpRead, SkipValueError, ValueReadError, MAX_SKIP_DEPTH,
+};
+
+/// Attempts to read binary data from the given slice, returning it borrowed instead of copying it.
+///
+/// # Errors
+///
+/// This function will return `ValueReadError` if the value is not binary data or if the slice
+/// ends before all of its data.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{read_bin_ref, Bytes};
+///
+/// let mut rd = Bytes::new(&[0xc4, 0x02, 0xca, 0xfe, 0xc0]);
+///
+/// assert_eq!([0xca, 0xfe], read_bin_ref(&mut rd).unwrap());
+/// assert_eq!([0xc0], rd.remaining_slice());
+/// ```
+pub fn read_bin_ref<'a>(rd: &mut Bytes<'a>) -> Result<&'a [u8], ValueReadError<BytesReadError>> {
+    let len = read_bin_len(rd)?;
+    read_data_ref(rd, len as usize).map_err(ValueReadError::InvalidDataRead)
+}
+
+/// Attempts to read an extension value from the given slice, returning its type and its data
+/// borrowed instead of copying it.
+///
+/// # Errors
+///
+/// This function will return `ValueReadError` if the value is not an extension or if the slice
+/// ends before all of its data.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{read_ext_ref, Bytes};
+///
+/// let mut rd = Bytes::new(&[0xd5, 0x2a, 0x01, 0x02]);
+///
+/// assert_eq!((42, &[0x01, 0x02][..]), read_ext_ref(&mut rd).unwrap());
+/// ```
+pub fn read_ext_ref<'a>(rd: &mut Bytes<'a>) -> Result<(i8, &'a [u8]), ValueReadError<BytesReadError>> {
+    let meta = read_ext_meta(rd)?;
+    let data = read_data_ref(rd, meta.size as usize).map_err(ValueReadError::InvalidDataRead)?;
+
+    Ok((meta.typeid, data))
+}
+
+/// Attempts to read an array from the given slice, returning a lazy iterator over the encoded bytes
+/// of its elements.
+///
+/// The elements are skipped once to find the end of the array, so that the slice can be read past
+/// it right away, but nothing is decoded or copied until the iterator is advanced. Each element is
+/// a complete encoded value, which can be decoded by wrapping it in [`Bytes`] again.
+///
+/// # Errors
+///
+/// This function will return `SkipValueError` if the value is not an array, if it is malformed or
+/// nested deeper than [`MAX_SKIP_DEPTH`] levels, or if the slice ends before the whole array. On
+/// errors the slice is only advanced past the array header at most.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{read_array_ref, read_int, read_str_from_slice, Bytes};
+///
+/// // [1, "two"], nil
+/// let mut rd = Bytes::new(&[0x92, 0x01, 0xa3, 0x74, 0x77, 0x6f, 0xc0]);
+///
+/// let mut array = read_array_ref(&mut rd).unwrap();
+/// assert_eq!([0xc0], rd.remaining_slice());
+///
+/// assert_eq!(2, array.len());
+/// assert_eq!(1u8, read_int(&mut Bytes::new(array.next().unwrap())).unwrap());
+/// assert_eq!("two", read_str_from_slice(array.next().unwrap()).unwrap().0);
+/// assert_eq!(None, array.next());
+/// ```
+pub fn read_array_ref<'a>(rd: &mut Bytes<'a>) -> Result<ArrayRef<'a>, SkipValueError<BytesReadError>> {
+    let len = read_array_len(rd)?;
+    let elems = read_values_ref(rd, len as u64)?;
+
+    Ok(ArrayRef { rd: Bytes::new(elems), left: len })
+}
+
+/// Attempts to read a map from the given slice, returning a lazy iterator over the encoded bytes of
+/// its keys and values.
+///
+/// Behaves like [`read_array_ref`], yielding pairs of a key and a value.
+///
+/// # Errors
+///
+/// See [`read_array_ref`].
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{read_map_ref, read_str_from_slice, Bytes};
+///
+/// // {"a": nil}
+/// let mut rd = Bytes::new(&[0x81, 0xa1, 0x61, 0xc0]);
+///
+/// let mut map = read_map_ref(&mut rd).unwrap();
+/// let (key, val) = map.next().unwrap();
+/// assert_eq!("a", read_str_from_slice(key).unwrap().0);
+/// assert_eq!([0xc0], val);
+/// assert_eq!(None, map.next());
+/// ```
+pub fn read_map_ref<'a>(rd: &mut Bytes<'a>) -> Result<MapRef<'a>, SkipValueError<BytesReadError>> {
+    let len = read_map_len(rd)?;
+    let entries = read_values_ref(rd, len as u64 * 2)?;
+
+    Ok(MapRef { rd: Bytes::new(entries), left: len })
+}
+
+/// A lazy iterator over the encoded elements of an array, returned by [`read_array_ref`].
+#[derive(Debug, Clone)]
+pub struct ArrayRef<'a> {
+    rd: Bytes<'a>,
+    left: u32,
+}
+
+impl<'a> ArrayRef<'a> {
+    /// Returns the encoded bytes of all elements not yet yielded.
+    #[inline]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        self.rd.remaining_slice()
+    }
+}
+
+impl<'a> Iterator for ArrayRef<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+        // Cannot fail, since the elements were validated by `read_array_ref`.
+        read_trusted_value_ref(&mut self.rd).ok()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left as usize, Some(self.left as usize))
+    }
+}
+
+impl ExactSizeIterator for ArrayRef<'_> {}
+
+impl FusedIterator for ArrayRef<'_> {}
+
+/// A lazy iterator over the encoded keys and values of a map, returned by [`read_map_ref`].
+#[derive(Debug, Clone)]
+pub struct MapRef<'a> {
+    rd: Bytes<'a>,
+    left: u32,
+}
+
+impl<'a> MapRef<'a> {
+    /// Returns the encoded bytes of all entries not yet yielded.
+    #[inline]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        self.rd.remaining_slice()
+    }
+}
+
+impl<'a> Iterator for MapRef<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+        // Cannot fail, since the entries were validated by `read_map_ref`.
+        let key = read_trusted_value_ref(&mut self.rd).ok()?;
+        let val = read_trusted_value_ref(&mut self.rd).ok()?;
+        Some((key, val))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left as usize, Some(self.left as usize))
+    }
+}
+
+impl ExactSizeIterator for MapRef<'_> {}
+
+impl FusedIterator for MapRef<'_> {}
+
+/// Borrows the next `len` bytes of the slice.
+fn read_data_ref<'a>(rd: &mut Bytes<'a>, len: usize) -> Result<&'a [u8], BytesReadError> {
+    let buf = rd.remaining_slice();
+    rd.skip_bytes(len)?;
+    Ok(&buf[..len])
+}
+
+/// Borrows the encoded bytes of the next `count` values of the slice, nested in an array or a map.
+///
+/// The slice is left untouched on errors.
+fn read_values_ref<'a>(rd: &mut Bytes<'a>, count: u64) -> Result<&'a [u8], SkipValueError<BytesReadError>> {
+    let buf = rd.remaining_slice();
+    let mut cur = *rd;
+    for _ in 0..count {
+        skip_value_with_max_depth(&mut cur, MAX_SKIP_DEPTH - 1)?;
+    }
+    let len = buf.len() - cur.remaining_slice().len();
+    *rd = cur;
+
+    Ok(&buf[..len])
+}
+
+/// Borrows the encoded bytes of the next value of the slice, which is already known to be valid.
+fn read_trusted_value_ref<'a>(rd: &mut Bytes<'a>) -> Result<&'a [u8], SkipValueError<BytesReadError>> {
+    let buf = rd.remaining_slice();
+    skip_values_trusted(rd, 1)?;
+    let len = buf.len() - rd.remaining_slice().len();
+
+    Ok(&buf[..len])
+}
@@ -116,6 +116,42 @@ impl RmpRead for Bytes<'_> {
             })
         }
     }
+
+    #[inline]
+    fn skip_bytes(&mut self, len: usize) -> Result<(), Self::Error> {
+        if len <= self.bytes.len() {
+            self.bytes = &self.bytes[len..];
+            self.current_position += len as u64;
+            Ok(())
+        } else {
+            Err(BytesReadError::InsufficientBytes {
+                expected: len,
+                actual: self.bytes.len(),
+                position: self.current_position
+            })
+        }
+    }
+
+    #[inline]
+    fn read_slice_ref(&mut self, len: usize) -> Result<Option<&[u8]>, Self::Error> {
+        if len <= self.bytes.len() {
+            let (data, newly_remaining) = self.bytes.split_at(len);
+            self.bytes = newly_remaining;
+            self.current_position += len as u64;
+            Ok(Some(data))
+        } else {
+            Err(BytesReadError::InsufficientBytes {
+                expected: len,
+                actual: self.bytes.len(),
+                position: self.current_position
+            })
+        }
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        Some(self.bytes.len())
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -150,4 +186,35 @@ impl<'a> RmpRead for &'a [u8] {
             })
         }
     }
+
+    fn skip_bytes(&mut self, len: usize) -> Result<(), Self::Error> {
+        if len <= self.len() {
+            *self = &self[len..];
+            Ok(())
+        } else {
+            Err(BytesReadError::InsufficientBytes {
+                expected: len,
+                actual: self.len(),
+                position: 0
+            })
+        }
+    }
+
+    fn read_slice_ref(&mut self, len: usize) -> Result<Option<&[u8]>, Self::Error> {
+        if len <= self.len() {
+            let (data, newly_remaining) = self.split_at(len);
+            *self = newly_remaining;
+            Ok(Some(data))
+        } else {
+            Err(BytesReadError::InsufficientBytes {
+                expected: len,
+                actual: self.len(),
+                position: 0
+            })
+        }
+    }
+
+    fn remaining_hint(&self) -> Option<usize> {
+        Some(self.len())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+use alloc::vec::Vec;
+
+use super::reader::read_event;
+use super::{read_marker, Bytes, Event, RmpRead};
+use crate::Marker;
+
+/// An array or a map being checked by [`is_canonical`].
+struct Frame {
+    /// Number of values left, counting keys and values of maps separately.
+    left: u64,
+    is_map: bool,
+    /// Offset of the current key, if this is a map.
+    key_start: usize,
+    /// Span of the previous key, if this is a map.
+    prev_key: Option<(usize, usize)>,
+}
+
+/// Checks that the given buffer contains exactly one MessagePack value in the canonical form.
+///
+/// Canonical encoding gives each value a single byte representation, so that equal values can be
+/// compared and hashed by their encoded bytes. A value is canonical if:
+///
+/// - Integers use the smallest representation, and non-negative integers always use the unsigned
+///   family of markers, as written by [`write_uint`](crate::encode::write_uint) and
+///   [`write_sint`](crate::encode::write_sint).
+/// - Strings, binaries, arrays, maps and extensions use the smallest header for their length,
+///   preferring fixext markers for extensions.
+/// - Floats are written as `f32` whenever that is lossless and all NaNs are the `f32` quiet NaN
+///   `0x7fc00000`, as written by [`write_f64_canonical`](crate::encode::write_f64_canonical).
+/// - Keys of every map are unique and sorted in ascending order of their encoded bytes.
+/// - The reserved marker `0xc1` does not occur.
+///
+/// Malformed or truncated input and trailing bytes after the value are never canonical.
+///
+/// # Examples
+///
+/// ```
+/// // {"a": 1, "b": 2}
+/// assert!(rmp::decode::is_canonical(&[0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0x02]));
+///
+/// // {"b": 2, "a": 1}
+/// assert!(!rmp::decode::is_canonical(&[0x82, 0xa1, 0x62, 0x02, 0xa1, 0x61, 0x01]));
+///
+/// // 1 written as u8.
+/// assert!(!rmp::decode::is_canonical(&[0xcc, 0x01]));
+/// ```
+pub fn is_canonical(buf: &[u8]) -> bool {
+    let mut rd = Bytes::new(buf);
+    let mut stack: Vec<Frame> = Vec::new();
+
+    loop {
+        let start = rd.position() as usize;
+        if let Some(frame) = stack.last_mut() {
+            if frame.is_map && frame.left % 2 == 0 {
+                frame.key_start = start;
+            }
+        }
+
+        let marker = match read_marker(&mut rd) {
+            Ok(marker) => marker,
+            Err(..) => return false,
+        };
+        let event = match read_event(&mut rd, marker) {
+            Ok(event) => event,
+            Err(..) => return false,
+        };
+        if !is_canonical_event(marker, &event) {
+            return false;
+        }
+
+        let data_len = match event {
+            Event::Str(len) | Event::Bin(len) | Event::Ext { len, .. } => len,
+            Event::ArrayStart(len) if len > 0 => {
+                stack.push(Frame { left: len as u64, is_map: false, key_start: 0, prev_key: None });
+                continue;
+            }
+            Event::MapStart(len) if len > 0 => {
+                stack.push(Frame { left: len as u64 * 2, is_map: true, key_start: 0, prev_key: None });
+                continue;
+            }
+            _ => 0,
+        };
+        if rd.skip_bytes(data_len as usize).is_err() {
+            return false;
+        }
+
+        // A value is complete, account for it in the enclosing containers.
+        let end = rd.position() as usize;
+        loop {
+            let frame = match stack.last_mut() {
+                Some(frame) => frame,
+                None => return end == buf.len(),
+            };
+
+            frame.left -= 1;
+            if frame.is_map && frame.left % 2 == 1 {
+                let key = &buf[frame.key_start..end];
+                if let Some((prev_start, prev_end)) = frame.prev_key {
+                    if &buf[prev_start..prev_end] >= key {
+                        return false;
+                    }
+                }
+                frame.prev_key = Some((frame.key_start, end));
+            }
+
+            if frame.left > 0 {
+                break;
+            }
+            stack.pop();
+        }
+    }
+}
+
+/// Checks that the marker is the smallest one for the decoded header.
+fn is_canonical_event(marker: Marker, event: &Event) -> bool {
+    let expected = match *event {
+        Event::Nil | Event::Bool(..) => return true,
+        Event::UInt(val) => match val {
+            0..=0x7f => Marker::FixPos(val as u8),
+            0x80..=0xff => Marker::U8,
+            0x100..=0xffff => Marker::U16,
+            0x1_0000..=0xffff_ffff => Marker::U32,
+            _ => Marker::U64,
+        },
+        Event::Int(val) => match val {
+            0..=i64::MAX => return false,
+            -32..=-1 => Marker::FixNeg(val as i8),
+            -128..=-33 => Marker::I8,
+            -32768..=-129 => Marker::I16,
+            -2147483648..=-32769 => Marker::I32,
+            _ => Marker::I64,
+        },
+        Event::F32(val) => return !val.is_nan() || val.to_bits() == f32::NAN.to_bits(),
+        Event::F64(val) => return !val.is_nan() && val as f32 as f64 != val,
+        Event::Str(len) => match len {
+            0..=31 => Marker::FixStr(len as u8),
+            32..=0xff => Marker::Str8,
+            0x100..=0xffff => Marker::Str16,
+            _ => Marker::Str32,
+        },
+        Event::Bin(len) => match len {
+            0..=0xff => Marker::Bin8,
+            0x100..=0xffff => Marker::Bin16,
+            _ => Marker::Bin32,
+        },
+        Event::ArrayStart(len) => match len {
+            0..=15 => Marker::FixArray(len as u8),
+            16..=0xffff => Marker::Array16,
+            _ => Marker::Array32,
+        },
+        Event::MapStart(len) => match len {
+            0..=15 => Marker::FixMap(len as u8),
+            16..=0xffff => Marker::Map16,
+            _ => Marker::Map32,
+        },
+        Event::Ext { len, .. } => match len {
+            1 => Marker::FixExt1,
+            2 => Marker::FixExt2,
+            4 => Marker::FixExt4,
+            8 => Marker::FixExt8,
+            16 => Marker::FixExt16,
+            0..=0xff => Marker::Ext8,
+            0x100..=0xffff => Marker::Ext16,
+            _ => Marker::Ext32,
+        },
+        Event::ArrayEnd | Event::MapEnd => return false,
+    };
+
+    marker == expected
+}
@@ -1,5 +1,9 @@
-use crate::Marker;
-use super::{read_marker, RmpRead, ValueReadError};
+#[cfg(feature = "std")]
+use std::error;
+use core::fmt::{self, Display, Formatter};
+
+use crate::{Marker, Timestamp};
+use super::{read_marker, RmpRead, RmpReadErr, ValueReadError};
 
 /// Attempts to read exactly 3 bytes from the given reader and interpret them as a fixext1 type
 /// with data attached.
@@ -166,3 +170,107 @@ pub fn read_ext_meta<R: RmpRead>(rd: &mut R) -> Result<ExtMeta, ValueReadError<R
 
     Ok(meta)
 }
+
+/// An error which can occur when attempting to read a MessagePack timestamp from the reader.
+#[derive(Debug)]
+pub enum TimestampReadError<E: RmpReadErr> {
+    /// Failed to read the marker.
+    InvalidMarkerRead(E),
+    /// Failed to read the data.
+    InvalidDataRead(E),
+    /// The type decoded isn't an extension.
+    TypeMismatch(Marker),
+    /// The extension type isn't [`Timestamp::EXT_TYPE`].
+    ExtTypeMismatch(i8),
+    /// The extension data length doesn't match any timestamp layout.
+    LengthMismatch(u32),
+    /// The encoded nanoseconds are not less than `1_000_000_000`.
+    OutOfRange,
+}
+
+#[cfg(feature = "std")]
+impl<E: RmpReadErr> error::Error for TimestampReadError<E> {
+    #[cold]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            TimestampReadError::InvalidMarkerRead(ref err) |
+            TimestampReadError::InvalidDataRead(ref err) => Some(err),
+            TimestampReadError::TypeMismatch(..) |
+            TimestampReadError::ExtTypeMismatch(..) |
+            TimestampReadError::LengthMismatch(..) |
+            TimestampReadError::OutOfRange => None,
+        }
+    }
+}
+
+impl<E: RmpReadErr> Display for TimestampReadError<E> {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            TimestampReadError::InvalidMarkerRead(..) => f.write_str("failed to read MessagePack marker"),
+            TimestampReadError::InvalidDataRead(..) => f.write_str("failed to read MessagePack data"),
+            TimestampReadError::TypeMismatch(..) => {
+                f.write_str("the type decoded does not match the expected one")
+            }
+            TimestampReadError::ExtTypeMismatch(ty) => {
+                write!(f, "expected timestamp extension type -1, got {}", ty)
+            }
+            TimestampReadError::LengthMismatch(len) => {
+                write!(f, "invalid timestamp extension length {}", len)
+            }
+            TimestampReadError::OutOfRange => f.write_str("timestamp nanoseconds out of range"),
+        }
+    }
+}
+
+impl<E: RmpReadErr> From<ValueReadError<E>> for TimestampReadError<E> {
+    #[cold]
+    fn from(err: ValueReadError<E>) -> TimestampReadError<E> {
+        match err {
+            ValueReadError::InvalidMarkerRead(err) => TimestampReadError::InvalidMarkerRead(err),
+            ValueReadError::InvalidDataRead(err) => TimestampReadError::InvalidDataRead(err),
+            ValueReadError::TypeMismatch(marker) => TimestampReadError::TypeMismatch(marker),
+        }
+    }
+}
+
+/// Attempts to read a MessagePack timestamp extension value from the given reader.
+///
+/// All three layouts defined by the specification are accepted: timestamp 32, timestamp 64 and
+/// timestamp 96.
+///
+/// # Errors
+///
+/// This function will return `TimestampReadError` on any I/O error while reading either the
+/// marker or the data.
+///
+/// It also returns `TimestampReadError::ExtTypeMismatch` if the extension type is not `-1`,
+/// `TimestampReadError::LengthMismatch` if the data length doesn't match any of the layouts and
+/// `TimestampReadError::OutOfRange` if the nanoseconds are not less than `1_000_000_000`.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::Timestamp;
+///
+/// let buf = [0xd6, 0xff, 0x00, 0x00, 0x00, 0x2a];
+///
+/// assert_eq!(Timestamp::from_secs(42), rmp::decode::read_timestamp(&mut &buf[..]).unwrap());
+/// ```
+pub fn read_timestamp<R: RmpRead>(rd: &mut R) -> Result<Timestamp, TimestampReadError<R::Error>> {
+    let meta = read_ext_meta(rd)?;
+    if meta.typeid != Timestamp::EXT_TYPE {
+        return Err(TimestampReadError::ExtTypeMismatch(meta.typeid));
+    }
+
+    let mut buf = [0; 12];
+    let buf = match meta.size {
+        4 => &mut buf[..4],
+        8 => &mut buf[..8],
+        12 => &mut buf[..],
+        size => return Err(TimestampReadError::LengthMismatch(size)),
+    };
+    rd.read_exact_buf(buf).map_err(TimestampReadError::InvalidDataRead)?;
+
+    Timestamp::from_ext_data(buf).ok_or(TimestampReadError::OutOfRange)
+}
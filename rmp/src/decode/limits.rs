@@ -0,0 +1,124 @@
+//! Limits on the size of decoded values, guarding against untrusted input.
+
+use core::fmt::{self, Display, Formatter};
+
+/// Limits on the lengths and the nesting of values, and on the amount of input read while
+/// decoding a single value.
+///
+/// MessagePack headers declare the lengths of strings, binary data and containers upfront, so a
+/// few bytes of untrusted input may request gigabytes of memory. Decoders accepting these limits
+/// check every declared length before allocating anything.
+///
+/// All lengths are unlimited by default, except for the nesting depth.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{DecodeLimits, Limit};
+///
+/// let limits = DecodeLimits {
+///     max_str_len: 256,
+///     max_total_bytes: 64 * 1024,
+///     ..DecodeLimits::default()
+/// };
+///
+/// assert_eq!(Ok(()), limits.check(Limit::StrLen, 256));
+/// assert_eq!(Err(Limit::StrLen), limits.check(Limit::StrLen, 257));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    /// The maximum length of strings, in bytes.
+    pub max_str_len: u32,
+    /// The maximum length of binary data, in bytes.
+    pub max_bin_len: u32,
+    /// The maximum number of elements of arrays.
+    pub max_array_len: u32,
+    /// The maximum number of key-value pairs of maps.
+    pub max_map_len: u32,
+    /// The maximum length of extension data, in bytes.
+    pub max_ext_len: u32,
+    /// The maximum number of bytes read for a whole value, including all headers.
+    pub max_total_bytes: u64,
+    /// The maximum nesting depth of arrays and maps, 1024 by default.
+    pub max_depth: usize,
+}
+
+impl Default for DecodeLimits {
+    #[inline]
+    fn default() -> Self {
+        DecodeLimits::new()
+    }
+}
+
+impl DecodeLimits {
+    /// Constructs the default limits.
+    #[inline]
+    pub const fn new() -> Self {
+        DecodeLimits {
+            max_str_len: u32::MAX,
+            max_bin_len: u32::MAX,
+            max_array_len: u32::MAX,
+            max_map_len: u32::MAX,
+            max_ext_len: u32::MAX,
+            max_total_bytes: u64::MAX,
+            max_depth: 1024,
+        }
+    }
+
+    /// Returns the maximum allowed for the given limit.
+    #[inline]
+    pub fn get(&self, limit: Limit) -> u64 {
+        match limit {
+            Limit::StrLen => u64::from(self.max_str_len),
+            Limit::BinLen => u64::from(self.max_bin_len),
+            Limit::ArrayLen => u64::from(self.max_array_len),
+            Limit::MapLen => u64::from(self.max_map_len),
+            Limit::ExtLen => u64::from(self.max_ext_len),
+            Limit::TotalBytes => self.max_total_bytes,
+        }
+    }
+
+    /// Checks the given length against a limit, returning the limit back if it is exceeded.
+    #[inline]
+    pub fn check(&self, limit: Limit, len: u64) -> Result<(), Limit> {
+        if len > self.get(limit) {
+            Err(limit)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A limit of [`DecodeLimits`], reported when exceeded.
+///
+/// Exceeding the nesting depth is reported by the dedicated errors of each decoder instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Limit {
+    /// The length of a string.
+    StrLen,
+    /// The length of binary data.
+    BinLen,
+    /// The number of elements of an array.
+    ArrayLen,
+    /// The number of key-value pairs of a map.
+    MapLen,
+    /// The length of extension data.
+    ExtLen,
+    /// The number of bytes read for a whole value.
+    TotalBytes,
+}
+
+impl Display for Limit {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            Limit::StrLen => "string length",
+            Limit::BinLen => "binary data length",
+            Limit::ArrayLen => "array length",
+            Limit::MapLen => "map length",
+            Limit::ExtLen => "extension data length",
+            Limit::TotalBytes => "total number of bytes",
+        })
+    }
+}
@@ -9,21 +9,94 @@
 //! non-blocking socket and it returns EWOULDBLOCK) be sure that you buffer the data externally
 //! to avoid data loss (using `BufRead` readers with manual consuming or some other way).
 
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[macro_use]
+mod async_io;
+mod borrowed;
+mod canonical;
 mod dec;
 mod ext;
+mod limits;
+mod path;
+mod push;
+mod reader;
 mod sint;
+mod skip;
 mod str;
 mod uint;
+mod validate;
 
+pub use self::borrowed::{read_array_ref, read_bin_ref, read_ext_ref, read_map_ref, ArrayRef, MapRef};
+pub use self::canonical::is_canonical;
 pub use self::dec::{read_f32, read_f64};
 pub use self::ext::{
-    read_ext_meta, read_fixext1, read_fixext16, read_fixext2, read_fixext4, read_fixext8, ExtMeta,
+    read_ext_meta, read_fixext1, read_fixext16, read_fixext2, read_fixext4, read_fixext8,
+    read_timestamp, ExtMeta, TimestampReadError,
 };
+pub use self::limits::{DecodeLimits, Limit};
+pub use self::path::{LocatedError, Path, PathSegment};
+pub use self::push::{PushDecodeError, PushDecoder, PushStatus};
+pub use self::reader::{Event, Reader, ReaderError};
 pub use self::sint::{read_i16, read_i32, read_i64, read_i8, read_nfix};
+pub use self::skip::{
+    skip_value, skip_value_from_marker, skip_value_with_max_depth, SkipValueError, MAX_SKIP_DEPTH,
+};
 #[allow(deprecated)]
 // While we re-export deprecated items, we don't want to trigger warnings while compiling this crate
 pub use self::str::{read_str, read_str_from_slice, read_str_len, read_str_ref, DecodeStringError};
 pub use self::uint::{read_pfix, read_u16, read_u32, read_u64, read_u8};
+pub use self::validate::{validate, validate_with_options, ValidationError, ValidationOptions};
+
+#[cfg(feature = "tokio")]
+async_decode_module! {
+    /// Asynchronous decoding from [`tokio::io::AsyncRead`](::tokio::io::AsyncRead) readers.
+    ///
+    /// These functions mirror their synchronous counterparts, reading exactly as many bytes as
+    /// those would. Use [`read_value_bytes_async`](self::tokio::read_value_bytes_async) to receive
+    /// a complete value, which can then be decoded synchronously.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp::decode::tokio::{read_array_len_async, read_int_async};
+    ///
+    /// # futures_executor::block_on(async {
+    /// let mut rd: &[u8] = &[0x92, 0x01, 0x02];
+    ///
+    /// assert_eq!(2, read_array_len_async(&mut rd).await.unwrap());
+    /// assert_eq!(1u8, read_int_async(&mut rd).await.unwrap());
+    /// assert_eq!(2u8, read_int_async(&mut rd).await.unwrap());
+    /// # });
+    /// ```
+    tokio, ::tokio::io
+}
+
+#[cfg(feature = "futures")]
+async_decode_module! {
+    /// Asynchronous decoding from [`futures::io::AsyncRead`](::futures_util::io::AsyncRead)
+    /// readers.
+    ///
+    /// These functions mirror their synchronous counterparts, reading exactly as many bytes as
+    /// those would. Use [`read_value_bytes_async`](self::futures::read_value_bytes_async) to
+    /// receive a complete value, which can then be decoded synchronously.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp::decode::futures::read_value_bytes_async;
+    ///
+    /// # futures_executor::block_on(async {
+    /// // ["a"] followed by the next value.
+    /// let mut rd: &[u8] = &[0x91, 0xa1, 0x61, 0xc0];
+    /// let mut buf = Vec::new();
+    ///
+    /// assert_eq!(3, read_value_bytes_async(&mut rd, &mut buf).await.unwrap());
+    /// assert_eq!([0x91, 0xa1, 0x61], buf[..]);
+    /// assert_eq!([0xc0], rd);
+    /// # });
+    /// ```
+    futures, ::futures_util::io
+}
 
 #[cfg(feature = "std")]
 use std::error;
@@ -44,6 +117,8 @@ pub use crate::errors::Error;
 /// The error type for I/O operations on `RmpRead` and associated traits.
 ///
 /// For [std::io::Read], this is [std::io::Error]
+///
+/// Custom error types must also implement [std::error::Error] when the `std` feature is enabled.
 pub trait RmpReadErr: Display + Debug + crate::errors::MaybeErrBound + 'static {}
 #[cfg(feature = "std")]
 impl RmpReadErr for std::io::Error {}
@@ -65,25 +140,46 @@ macro_rules! read_byteorder_utils {
         )*
     };
 }
-mod sealed{
-    pub trait Sealed {}
-    #[cfg(feature = "std")]
-    impl<T: ?Sized + std::io::Read> Sealed for T {}
-    #[cfg(not(feature = "std"))]
-    impl<'a> Sealed for &'a [u8] {}
-    impl Sealed for super::Bytes<'_> {}
-}
-
 
 /// A type that `rmp` supports reading from.
 ///
-/// The methods of this trait should be considered an implementation detail (for now).
-/// It is currently sealed (can not be implemented by the user).
+/// Its primary implementations are [std::io::Read] and [Bytes], but it can be implemented for
+/// any other source of bytes, like a ring buffer or a DMA channel on `no_std` targets. Only
+/// [`read_exact_buf`](Self::read_exact_buf) is required, the other methods have default
+/// implementations built on top of it, which can be overridden when the source allows doing better.
+///
+/// The contract of every method is that it either consumes exactly the requested number of bytes
+/// or fails. After an error the number of bytes consumed is unspecified, so the stream should be
+/// considered broken.
 ///
 /// See also [std::io::Read] and [byteorder::ReadBytesExt]
 ///
-/// Its primary implementations are [std::io::Read] and [Bytes].
-pub trait RmpRead: sealed::Sealed {
+/// # Examples
+///
+/// ```
+/// use core::convert::Infallible;
+/// use rmp::decode::RmpRead;
+///
+/// /// An endless stream of consecutive bytes.
+/// struct Counter(u8);
+///
+/// impl RmpRead for Counter {
+///     type Error = Infallible;
+///
+///     fn read_exact_buf(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+///         for byte in buf {
+///             *byte = self.0;
+///             self.0 += 1;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// // 0x01 is a positive fixint.
+/// assert_eq!(1, rmp::decode::read_int::<u8, _>(&mut Counter(1)).unwrap());
+/// ```
+pub trait RmpRead {
+    /// The error returned when reading fails, [std::io::Error] for [std::io::Read].
     type Error: RmpReadErr;
     /// Read a single (unsigned) byte from this stream
     #[inline]
@@ -100,6 +196,44 @@ pub trait RmpRead: sealed::Sealed {
     /// See also [std::io::Read::read_exact]
     fn read_exact_buf(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
 
+    /// Skip the exact number of bytes, discarding them.
+    ///
+    /// If there are not enough bytes, this will return an error.
+    ///
+    /// The default implementation reads the bytes through a small stack buffer, while in-memory
+    /// readers like [Bytes] simply advance past them.
+    fn skip_bytes(&mut self, mut len: usize) -> Result<(), Self::Error> {
+        let mut buf = [0; 64];
+        while len > 0 {
+            let n = core::cmp::min(len, buf.len());
+            self.read_exact_buf(&mut buf[..n])?;
+            len -= n;
+        }
+        Ok(())
+    }
+
+    /// Read exactly `len` bytes, borrowing them from the internal buffer of this stream.
+    ///
+    /// Returns `Ok(None)` without consuming anything if the stream can't lend that many contiguous
+    /// bytes, in which case the caller should fall back to [`read_exact_buf`](Self::read_exact_buf).
+    /// This is what the default implementation always does, while in-memory readers like [Bytes]
+    /// return a subslice of their input.
+    #[inline]
+    fn read_slice_ref(&mut self, len: usize) -> Result<Option<&[u8]>, Self::Error> {
+        let _ = len;
+        Ok(None)
+    }
+
+    /// Return the number of bytes left in this stream, if it is known.
+    ///
+    /// Returning `Some(n)` guarantees that reading more than `n` bytes fails, which lets callers
+    /// reject lengths that can't be satisfied before allocating buffers for them. The default
+    /// implementation returns `None`.
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        None
+    }
+
     // Internal helper functions to map I/O error into the `InvalidDataRead` error.
 
     /// Read a single (unsigned) byte from this stream.
@@ -169,6 +303,16 @@ impl<T: std::io::Read> RmpRead for T {
     fn read_exact_buf(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
         std::io::Read::read_exact(self, buf)
     }
+
+    #[inline]
+    fn skip_bytes(&mut self, len: usize) -> Result<(), Self::Error> {
+        let mut rd = std::io::Read::take(std::io::Read::by_ref(self), len as u64);
+        if std::io::copy(&mut rd, &mut std::io::sink())? == len as u64 {
+            Ok(())
+        } else {
+            Err(std::io::ErrorKind::UnexpectedEof.into())
+        }
+    }
 }
 
 // An error returned from the `write_marker` and `write_fixval` functions.
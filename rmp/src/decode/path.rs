@@ -0,0 +1,135 @@
+//! Locations of errors in the input, reported by decoders of values.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+/// A step from a value to one nested in it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// An element of an array, or the value of a map with this unsigned integer key.
+    Index(u64),
+    /// A field of a struct, by name.
+    Field(&'static str),
+    /// The value of a map with this string key.
+    Key(String),
+    /// A map key, or the value of a map with a key of any other type.
+    Other,
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            PathSegment::Index(idx) => write!(f, "[{}]", idx),
+            PathSegment::Field(name) => write!(f, ".{}", name),
+            PathSegment::Key(ref key) if is_identifier(key) => write!(f, ".{}", key),
+            PathSegment::Key(ref key) => write!(f, "[{:?}]", key),
+            PathSegment::Other => f.write_str("[?]"),
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The path from the top-level value to a nested one, displayed like `.users[42].address.zip`.
+///
+/// The top-level value itself has an empty path, displayed as `.`.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{Path, PathSegment};
+///
+/// let mut path = Path::new();
+/// path.push(PathSegment::Field("users"));
+/// path.push(PathSegment::Index(42));
+/// path.push(PathSegment::Key("zip code".into()));
+///
+/// assert_eq!(".users[42][\"zip code\"]", path.to_string());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    /// Constructs the path of the top-level value.
+    #[inline]
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    /// Returns the steps from the top-level value, outermost first.
+    #[inline]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Steps into a nested value.
+    #[inline]
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Steps out of the innermost value, returning the step into it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return f.write_str(".");
+        }
+        for segment in &self.segments {
+            Display::fmt(segment, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<PathSegment> for Path {
+    fn from_iter<I: IntoIterator<Item = PathSegment>>(iter: I) -> Self {
+        Path { segments: iter.into_iter().collect() }
+    }
+}
+
+/// An error along with where it occurred in the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocatedError<E> {
+    /// The error itself, which can be matched on as usual.
+    pub error: E,
+    /// The offset in bytes from the start of the input of the marker of the value that failed.
+    pub offset: u64,
+    /// The path to the value that failed.
+    pub path: Path,
+}
+
+impl<E> LocatedError<E> {
+    /// Returns the error without its location.
+    #[inline]
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E: Display> Display for LocatedError<E> {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}, path {}", self.error, self.offset, self.path)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for LocatedError<E> {
+    #[cold]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
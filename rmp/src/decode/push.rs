@@ -0,0 +1,227 @@
+//! Implementation of the resumable [PushDecoder].
+
+#[cfg(feature = "std")]
+use std::error;
+use core::fmt::{self, Display, Formatter};
+
+use super::reader::{read_event, Nesting};
+use super::{Bytes, Event};
+use crate::Marker;
+
+/// Outcome of a single [`PushDecoder::feed`] call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PushStatus<'a> {
+    /// The input is exhausted, at least this many more bytes are required to make progress.
+    NeedMore(usize),
+    /// The next event of the stream has been decoded.
+    Event(Event),
+    /// A chunk of data of the current string, binary or extension, borrowed from the input.
+    ///
+    /// The data may be split into any number of chunks depending on how the input is fed.
+    Data(&'a [u8]),
+    /// A complete top-level value has been decoded, the next event starts another value.
+    Value,
+}
+
+/// An error which can occur when decoding with a [`PushDecoder`].
+///
+/// The decoder should not be used anymore after an error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PushDecodeError {
+    /// The reserved marker `0xc1` was encountered.
+    TypeMismatch(Marker),
+    /// Arrays and maps are nested deeper than allowed.
+    DepthLimitExceeded,
+}
+
+#[cfg(feature = "std")]
+impl error::Error for PushDecodeError {}
+
+impl Display for PushDecodeError {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match *self {
+            PushDecodeError::TypeMismatch(..) => "encountered the reserved MessagePack marker",
+            PushDecodeError::DepthLimitExceeded => "depth limit exceeded",
+        })
+    }
+}
+
+/// A resumable decoder, that is fed with arbitrary chunks of a MessagePack stream as they arrive.
+///
+/// Unlike the [`Reader`](super::Reader), which pulls bytes from an [`RmpRead`](super::RmpRead)
+/// and fails when they run out, this decoder keeps partially received headers in its state and
+/// asks for more input instead, so it never needs the whole message to be buffered. Each call to
+/// [`feed`](Self::feed) makes a single step of progress and returns the number of bytes consumed
+/// from the input along with a [`PushStatus`]:
+///
+/// - [`PushStatus::Event`] for every marker with its header, the same as [`Reader::next_event`](super::Reader::next_event).
+/// - [`PushStatus::Data`] for chunks of string, binary and extension data, borrowed from the input.
+/// - [`PushStatus::Value`] once a complete top-level value has been decoded.
+/// - [`PushStatus::NeedMore`] when the input is exhausted.
+///
+/// Unconsumed input must be fed again on the next call, possibly extended with newly received
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{Event, PushDecoder, PushStatus};
+///
+/// // ["ab"], received in two chunks.
+/// let mut dec = PushDecoder::new();
+///
+/// assert_eq!((1, PushStatus::Event(Event::ArrayStart(1))), dec.feed(&[0x91, 0xa2, 0x61]).unwrap());
+/// assert_eq!((1, PushStatus::Event(Event::Str(2))), dec.feed(&[0xa2, 0x61]).unwrap());
+/// assert_eq!((1, PushStatus::Data(&b"a"[..])), dec.feed(&[0x61]).unwrap());
+/// assert_eq!((0, PushStatus::NeedMore(1)), dec.feed(&[]).unwrap());
+///
+/// assert_eq!((1, PushStatus::Data(&b"b"[..])), dec.feed(&[0x62]).unwrap());
+/// assert_eq!((0, PushStatus::Event(Event::ArrayEnd)), dec.feed(&[]).unwrap());
+/// assert_eq!((0, PushStatus::Value), dec.feed(&[]).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct PushDecoder {
+    nesting: Nesting,
+    /// The marker and the header data received so far.
+    header: [u8; 9],
+    header_len: usize,
+    /// Number of bytes of string, binary or extension data not received yet.
+    data_left: u32,
+    /// Whether the last step has completed a top-level value.
+    value_done: bool,
+}
+
+impl Default for PushDecoder {
+    #[inline]
+    fn default() -> Self {
+        PushDecoder::new()
+    }
+}
+
+impl PushDecoder {
+    /// Constructs a new decoder, allowing arrays and maps to be nested up to 1024 levels deep.
+    #[inline]
+    pub fn new() -> Self {
+        PushDecoder {
+            nesting: Nesting::new(1024),
+            header: [0; 9],
+            header_len: 0,
+            data_left: 0,
+            value_done: false,
+        }
+    }
+
+    /// Changes the maximum nesting depth of arrays and maps that is allowed.
+    #[inline]
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.nesting.set_max_depth(depth);
+    }
+
+    /// Returns the number of arrays and maps currently open.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.nesting.depth()
+    }
+
+    /// Returns `true` if the decoder is in between top-level values, i.e. it has not received any
+    /// part of the next value yet.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.nesting.depth() == 0 && self.header_len == 0 && self.data_left == 0 && !self.value_done
+    }
+
+    /// Makes a single step of decoding using the given input, returning the number of bytes
+    /// consumed from it and the outcome of the step.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `PushDecodeError` when the reserved marker is encountered or when
+    /// the depth limit is exceeded.
+    pub fn feed<'a>(&mut self, buf: &'a [u8]) -> Result<(usize, PushStatus<'a>), PushDecodeError> {
+        if self.data_left > 0 {
+            if buf.is_empty() {
+                return Ok((0, PushStatus::NeedMore(self.data_left as usize)));
+            }
+
+            let len = core::cmp::min(buf.len(), self.data_left as usize);
+            self.data_left -= len as u32;
+            self.value_done = self.data_left == 0 && self.nesting.depth() == 0;
+            return Ok((len, PushStatus::Data(&buf[..len])));
+        }
+
+        if self.value_done {
+            self.value_done = false;
+            return Ok((0, PushStatus::Value));
+        }
+
+        if let Some(event) = self.nesting.pop_end() {
+            self.value_done = self.nesting.depth() == 0;
+            return Ok((0, PushStatus::Event(event)));
+        }
+
+        let mut consumed = 0;
+        if self.header_len == 0 {
+            match buf.first() {
+                Some(&byte) => {
+                    self.header[0] = byte;
+                    self.header_len = 1;
+                    consumed = 1;
+                }
+                None => return Ok((0, PushStatus::NeedMore(1))),
+            }
+        }
+
+        let marker = Marker::from_u8(self.header[0]);
+        let header_len = 1 + header_data_len(marker)?;
+
+        let len = core::cmp::min(header_len - self.header_len, buf.len() - consumed);
+        self.header[self.header_len..self.header_len + len].copy_from_slice(&buf[consumed..consumed + len]);
+        self.header_len += len;
+        consumed += len;
+
+        if self.header_len < header_len {
+            return Ok((consumed, PushStatus::NeedMore(header_len - self.header_len)));
+        }
+        self.header_len = 0;
+
+        let event = read_event(&mut Bytes::new(&self.header[1..header_len]), marker)
+            .expect("complete header must be decodable");
+
+        if !self.nesting.push(&event) {
+            return Err(PushDecodeError::DepthLimitExceeded);
+        }
+        if let Event::Str(len) | Event::Bin(len) | Event::Ext { len, .. } = event {
+            self.data_left = len;
+        }
+        self.value_done = self.nesting.depth() == 0 && self.data_left == 0;
+
+        Ok((consumed, PushStatus::Event(event)))
+    }
+}
+
+/// Returns the length of the header data following the given marker.
+pub(super) fn header_data_len(marker: Marker) -> Result<usize, PushDecodeError> {
+    let len = match marker {
+        Marker::FixPos(..) |
+        Marker::FixNeg(..) |
+        Marker::Null |
+        Marker::True |
+        Marker::False |
+        Marker::FixStr(..) |
+        Marker::FixArray(..) |
+        Marker::FixMap(..) => 0,
+        Marker::U8 | Marker::I8 | Marker::Str8 | Marker::Bin8 => 1,
+        Marker::U16 | Marker::I16 | Marker::Str16 | Marker::Bin16 | Marker::Array16 | Marker::Map16 => 2,
+        Marker::U32 | Marker::I32 | Marker::F32 | Marker::Str32 | Marker::Bin32 | Marker::Array32 | Marker::Map32 => 4,
+        Marker::U64 | Marker::I64 | Marker::F64 => 8,
+        // Extensions are followed by the optional length and the type byte.
+        Marker::FixExt1 | Marker::FixExt2 | Marker::FixExt4 | Marker::FixExt8 | Marker::FixExt16 => 1,
+        Marker::Ext8 => 2,
+        Marker::Ext16 => 3,
+        Marker::Ext32 => 5,
+        Marker::Reserved => return Err(PushDecodeError::TypeMismatch(marker)),
+    };
+
+    Ok(len)
+}
@@ -0,0 +1,377 @@
+//! Implementation of the pull-based [Reader] of MessagePack events.
+
+#[cfg(feature = "std")]
+use std::error;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use super::{read_marker, MarkerReadError, RmpRead, RmpReadErr, ValueReadError};
+use crate::Marker;
+
+/// A single token of a MessagePack stream produced by [`Reader::next_event`].
+///
+/// Strings, binaries and extensions only carry the length of their data, which follows the event
+/// in the stream and can be consumed with [`Reader::read_data`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// Nil.
+    Nil,
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer, encoded either as a positive fixnum or as one of the `uint` types.
+    UInt(u64),
+    /// A signed integer, encoded either as a negative fixnum or as one of the `int` types.
+    Int(i64),
+    /// A 32-bit floating point number.
+    F32(f32),
+    /// A 64-bit floating point number.
+    F64(f64),
+    /// A string with the given length of its UTF-8 data in bytes.
+    Str(u32),
+    /// A binary with the given length of its data.
+    Bin(u32),
+    /// An extension with the given type and length of its data.
+    Ext {
+        /// The extension type.
+        ty: i8,
+        /// The length of the extension data.
+        len: u32,
+    },
+    /// The beginning of an array with the given number of elements.
+    ArrayStart(u32),
+    /// The end of the most recently started array.
+    ArrayEnd,
+    /// The beginning of a map with the given number of key-value pairs.
+    MapStart(u32),
+    /// The end of the most recently started map.
+    MapEnd,
+}
+
+/// An error which can occur when reading events with a [`Reader`].
+#[derive(Debug)]
+pub enum ReaderError<E: RmpReadErr> {
+    /// Failed to read the marker.
+    InvalidMarkerRead(E),
+    /// Failed to read the data.
+    InvalidDataRead(E),
+    /// The reserved marker `0xc1` was encountered.
+    TypeMismatch(Marker),
+    /// Arrays and maps are nested deeper than allowed.
+    DepthLimitExceeded,
+}
+
+#[cfg(feature = "std")]
+impl<E: RmpReadErr> error::Error for ReaderError<E> {
+    #[cold]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ReaderError::InvalidMarkerRead(ref err) |
+            ReaderError::InvalidDataRead(ref err) => Some(err),
+            ReaderError::TypeMismatch(..) |
+            ReaderError::DepthLimitExceeded => None,
+        }
+    }
+}
+
+impl<E: RmpReadErr> Display for ReaderError<E> {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match *self {
+            ReaderError::InvalidMarkerRead(..) => "failed to read MessagePack marker",
+            ReaderError::InvalidDataRead(..) => "failed to read MessagePack data",
+            ReaderError::TypeMismatch(..) => "encountered the reserved MessagePack marker",
+            ReaderError::DepthLimitExceeded => "depth limit exceeded",
+        })
+    }
+}
+
+impl<E: RmpReadErr> From<MarkerReadError<E>> for ReaderError<E> {
+    #[cold]
+    fn from(err: MarkerReadError<E>) -> ReaderError<E> {
+        ReaderError::InvalidMarkerRead(err.0)
+    }
+}
+
+impl<E: RmpReadErr> From<ValueReadError<E>> for ReaderError<E> {
+    #[cold]
+    fn from(err: ValueReadError<E>) -> ReaderError<E> {
+        match err {
+            ValueReadError::InvalidMarkerRead(err) => ReaderError::InvalidMarkerRead(err),
+            ValueReadError::InvalidDataRead(err) => ReaderError::InvalidDataRead(err),
+            ValueReadError::TypeMismatch(marker) => ReaderError::TypeMismatch(marker),
+        }
+    }
+}
+
+/// An open array or map.
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    /// Number of values left, counting keys and values of maps separately.
+    left: u64,
+    is_map: bool,
+}
+
+/// Tracks arrays and maps opened by a sequence of events, to tell when they are closed.
+#[derive(Debug)]
+pub(super) struct Nesting {
+    stack: Vec<Frame>,
+    max_depth: usize,
+}
+
+impl Nesting {
+    #[inline]
+    pub(super) fn new(max_depth: usize) -> Self {
+        Nesting { stack: Vec::new(), max_depth }
+    }
+
+    #[inline]
+    pub(super) fn set_max_depth(&mut self, depth: usize) {
+        self.max_depth = depth;
+    }
+
+    #[inline]
+    pub(super) fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Closes the innermost array or map if all of its elements have been read, returning the
+    /// corresponding end event.
+    #[inline]
+    pub(super) fn pop_end(&mut self) -> Option<Event> {
+        match self.stack.last() {
+            Some(frame) if frame.left == 0 => {
+                let is_map = frame.is_map;
+                self.stack.pop();
+                Some(if is_map { Event::MapEnd } else { Event::ArrayEnd })
+            }
+            _ => None,
+        }
+    }
+
+    /// Accounts for an event starting a new value, returning `false` if it opens an array or a map
+    /// beyond the depth limit.
+    #[inline]
+    pub(super) fn push(&mut self, event: &Event) -> bool {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.left -= 1;
+        }
+
+        let frame = match *event {
+            Event::ArrayStart(len) => Frame { left: len as u64, is_map: false },
+            Event::MapStart(len) => Frame { left: len as u64 * 2, is_map: true },
+            _ => return true,
+        };
+        if self.stack.len() >= self.max_depth {
+            return false;
+        }
+        self.stack.push(frame);
+        true
+    }
+}
+
+/// Reads the rest of the header following the given marker, returning the event it starts.
+pub(super) fn read_event<R: RmpRead>(rd: &mut R, marker: Marker) -> Result<Event, ValueReadError<R::Error>> {
+    let event = match marker {
+        Marker::Null => Event::Nil,
+        Marker::True => Event::Bool(true),
+        Marker::False => Event::Bool(false),
+        Marker::FixPos(val) => Event::UInt(val as u64),
+        Marker::FixNeg(val) => Event::Int(val as i64),
+        Marker::U8 => Event::UInt(rd.read_data_u8()? as u64),
+        Marker::U16 => Event::UInt(rd.read_data_u16()? as u64),
+        Marker::U32 => Event::UInt(rd.read_data_u32()? as u64),
+        Marker::U64 => Event::UInt(rd.read_data_u64()?),
+        Marker::I8 => Event::Int(rd.read_data_i8()? as i64),
+        Marker::I16 => Event::Int(rd.read_data_i16()? as i64),
+        Marker::I32 => Event::Int(rd.read_data_i32()? as i64),
+        Marker::I64 => Event::Int(rd.read_data_i64()?),
+        Marker::F32 => Event::F32(rd.read_data_f32()?),
+        Marker::F64 => Event::F64(rd.read_data_f64()?),
+        Marker::FixStr(len) => Event::Str(len as u32),
+        Marker::Str8 => Event::Str(rd.read_data_u8()? as u32),
+        Marker::Str16 => Event::Str(rd.read_data_u16()? as u32),
+        Marker::Str32 => Event::Str(rd.read_data_u32()?),
+        Marker::Bin8 => Event::Bin(rd.read_data_u8()? as u32),
+        Marker::Bin16 => Event::Bin(rd.read_data_u16()? as u32),
+        Marker::Bin32 => Event::Bin(rd.read_data_u32()?),
+        Marker::FixArray(len) => Event::ArrayStart(len as u32),
+        Marker::Array16 => Event::ArrayStart(rd.read_data_u16()? as u32),
+        Marker::Array32 => Event::ArrayStart(rd.read_data_u32()?),
+        Marker::FixMap(len) => Event::MapStart(len as u32),
+        Marker::Map16 => Event::MapStart(rd.read_data_u16()? as u32),
+        Marker::Map32 => Event::MapStart(rd.read_data_u32()?),
+        Marker::FixExt1 => Event::Ext { ty: rd.read_data_i8()?, len: 1 },
+        Marker::FixExt2 => Event::Ext { ty: rd.read_data_i8()?, len: 2 },
+        Marker::FixExt4 => Event::Ext { ty: rd.read_data_i8()?, len: 4 },
+        Marker::FixExt8 => Event::Ext { ty: rd.read_data_i8()?, len: 8 },
+        Marker::FixExt16 => Event::Ext { ty: rd.read_data_i8()?, len: 16 },
+        Marker::Ext8 => {
+            let len = rd.read_data_u8()? as u32;
+            Event::Ext { ty: rd.read_data_i8()?, len }
+        }
+        Marker::Ext16 => {
+            let len = rd.read_data_u16()? as u32;
+            Event::Ext { ty: rd.read_data_i8()?, len }
+        }
+        Marker::Ext32 => {
+            let len = rd.read_data_u32()?;
+            Event::Ext { ty: rd.read_data_i8()?, len }
+        }
+        Marker::Reserved => return Err(ValueReadError::TypeMismatch(marker)),
+    };
+
+    Ok(event)
+}
+
+/// A pull-based reader, that splits a MessagePack stream into a sequence of [`Event`]s.
+///
+/// This sits between the `read_*` functions, which require knowing the type of the next value in
+/// advance, and fully decoded value trees: each call to [`next_event`](Self::next_event) reads a
+/// single marker with its header, and the reader keeps track of open arrays and maps to emit
+/// [`Event::ArrayEnd`] and [`Event::MapEnd`] once all of their elements have been read.
+///
+/// The data of strings, binaries and extensions can be read with [`read_data`](Self::read_data),
+/// possibly in several chunks. Whatever is left unread is skipped by the next call to
+/// `next_event`.
+///
+/// The reader does not detect the end of the stream by itself. A top-level value is complete when
+/// [`depth`](Self::depth) drops back to zero, after which the next event starts another value.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::{Event, Reader};
+///
+/// // {"a": [1, -2]}
+/// let buf = [0x81, 0xa1, 0x61, 0x92, 0x01, 0xfe];
+/// let mut rd = Reader::new(&buf[..]);
+///
+/// assert_eq!(Event::MapStart(1), rd.next_event().unwrap());
+/// assert_eq!(Event::Str(1), rd.next_event().unwrap());
+///
+/// let mut key = [0; 1];
+/// assert_eq!(1, rd.read_data(&mut key).unwrap());
+/// assert_eq!(b"a", &key);
+///
+/// assert_eq!(Event::ArrayStart(2), rd.next_event().unwrap());
+/// assert_eq!(Event::UInt(1), rd.next_event().unwrap());
+/// assert_eq!(Event::Int(-2), rd.next_event().unwrap());
+/// assert_eq!(Event::ArrayEnd, rd.next_event().unwrap());
+/// assert_eq!(Event::MapEnd, rd.next_event().unwrap());
+/// assert_eq!(0, rd.depth());
+/// ```
+#[derive(Debug)]
+pub struct Reader<R> {
+    rd: R,
+    nesting: Nesting,
+    /// Number of bytes of string, binary or extension data not consumed yet.
+    data_left: u32,
+}
+
+impl<R: RmpRead> Reader<R> {
+    /// Constructs a new reader over the given input, allowing arrays and maps to be nested up to
+    /// 1024 levels deep.
+    #[inline]
+    pub fn new(rd: R) -> Self {
+        Reader {
+            rd,
+            nesting: Nesting::new(1024),
+            data_left: 0,
+        }
+    }
+
+    /// Changes the maximum nesting depth of arrays and maps that is allowed.
+    #[inline]
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.nesting.set_max_depth(depth);
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.rd
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly messes up the state of this reader, unless a complete value is read
+    /// while [`depth`](Self::depth) is zero and no data is left unread.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rd
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.rd
+    }
+
+    /// Returns the number of arrays and maps currently open.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.nesting.depth()
+    }
+
+    /// Returns the number of bytes of the current string, binary or extension data not read yet.
+    #[inline]
+    pub fn data_left(&self) -> u32 {
+        self.data_left
+    }
+
+    /// Reads the data of the current string, binary or extension into the given buffer, returning
+    /// the number of bytes read.
+    ///
+    /// This reads either the whole buffer or all data left, whichever is smaller, so zero is
+    /// returned once all data has been read.
+    pub fn read_data(&mut self, buf: &mut [u8]) -> Result<usize, R::Error> {
+        let len = core::cmp::min(buf.len(), self.data_left as usize);
+        self.rd.read_exact_buf(&mut buf[..len])?;
+        self.data_left -= len as u32;
+        Ok(len)
+    }
+
+    /// Reads all data left of the current string, binary or extension, borrowing it from the
+    /// underlying reader.
+    ///
+    /// Returns `Ok(None)` without reading anything if the underlying reader can't lend the data,
+    /// see [`RmpRead::read_slice_ref`], in which case it can still be read with
+    /// [`read_data`](Self::read_data).
+    pub fn read_data_slice(&mut self) -> Result<Option<&[u8]>, R::Error> {
+        let len = self.data_left as usize;
+        let data = self.rd.read_slice_ref(len)?;
+        if data.is_some() {
+            self.data_left = 0;
+        }
+        Ok(data)
+    }
+
+    /// Reads the next event from the stream.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `ReaderError` on any I/O error while reading either the marker or
+    /// the data, when the reserved marker is encountered or when the depth limit is exceeded.
+    pub fn next_event(&mut self) -> Result<Event, ReaderError<R::Error>> {
+        if self.data_left > 0 {
+            self.rd.skip_bytes(self.data_left as usize).map_err(ReaderError::InvalidDataRead)?;
+            self.data_left = 0;
+        }
+
+        if let Some(event) = self.nesting.pop_end() {
+            return Ok(event);
+        }
+
+        let marker = read_marker(&mut self.rd)?;
+        let event = read_event(&mut self.rd, marker)?;
+
+        if !self.nesting.push(&event) {
+            return Err(ReaderError::DepthLimitExceeded);
+        }
+        if let Event::Str(len) | Event::Bin(len) | Event::Ext { len, .. } = event {
+            self.data_left = len;
+        }
+
+        Ok(event)
+    }
+}
@@ -0,0 +1,247 @@
+#[cfg(feature = "std")]
+use std::error;
+use core::fmt::{self, Display, Formatter};
+
+use super::{read_marker, MarkerReadError, RmpRead, RmpReadErr, ValueReadError};
+use crate::Marker;
+
+/// The maximum nesting depth supported by [`skip_value_with_max_depth`].
+///
+/// Also used as the depth limit of [`skip_value`].
+pub const MAX_SKIP_DEPTH: usize = 1024;
+
+/// An error which can occur when attempting to skip a MessagePack value.
+#[derive(Debug)]
+pub enum SkipValueError<E: RmpReadErr> {
+    /// Failed to read the marker.
+    InvalidMarkerRead(E),
+    /// Failed to read the data.
+    InvalidDataRead(E),
+    /// An unexpected marker was encountered, like the reserved marker `0xc1`.
+    TypeMismatch(Marker),
+    /// Arrays and maps are nested deeper than allowed.
+    DepthLimitExceeded,
+}
+
+#[cfg(feature = "std")]
+impl<E: RmpReadErr> error::Error for SkipValueError<E> {
+    #[cold]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            SkipValueError::InvalidMarkerRead(ref err) |
+            SkipValueError::InvalidDataRead(ref err) => Some(err),
+            SkipValueError::TypeMismatch(..) |
+            SkipValueError::DepthLimitExceeded => None,
+        }
+    }
+}
+
+impl<E: RmpReadErr> Display for SkipValueError<E> {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match *self {
+            SkipValueError::InvalidMarkerRead(..) => "failed to read MessagePack marker",
+            SkipValueError::InvalidDataRead(..) => "failed to read MessagePack data",
+            SkipValueError::TypeMismatch(..) => "encountered an unexpected MessagePack marker",
+            SkipValueError::DepthLimitExceeded => "depth limit exceeded",
+        })
+    }
+}
+
+impl<E: RmpReadErr> From<MarkerReadError<E>> for SkipValueError<E> {
+    #[cold]
+    fn from(err: MarkerReadError<E>) -> SkipValueError<E> {
+        SkipValueError::InvalidMarkerRead(err.0)
+    }
+}
+
+impl<E: RmpReadErr> From<ValueReadError<E>> for SkipValueError<E> {
+    #[cold]
+    fn from(err: ValueReadError<E>) -> SkipValueError<E> {
+        match err {
+            ValueReadError::InvalidMarkerRead(err) => SkipValueError::InvalidMarkerRead(err),
+            ValueReadError::InvalidDataRead(err) => SkipValueError::InvalidDataRead(err),
+            ValueReadError::TypeMismatch(marker) => SkipValueError::TypeMismatch(marker),
+        }
+    }
+}
+
+/// Attempts to read and discard one complete MessagePack value, including all nested values of
+/// arrays and maps.
+///
+/// This never allocates: scalars are skipped without any bookkeeping, arrays and maps are
+/// traversed iteratively with a small stack, and string, binary and extension data is skipped by [`RmpRead::skip_bytes`], which just advances the position for in-memory readers
+/// like [`Bytes`](super::Bytes).
+///
+/// Arrays and maps may be nested up to [`MAX_SKIP_DEPTH`] levels deep, see
+/// [`skip_value_with_max_depth`] for choosing a lower limit.
+///
+/// # Errors
+///
+/// This function will return `SkipValueError` on any I/O error while reading either markers or
+/// data, when the reserved marker is encountered or when the depth limit is exceeded.
+///
+/// # Examples
+///
+/// ```
+/// // {"a": [1, 2], "b": "c"}, 42
+/// let buf = [0x82, 0xa1, 0x61, 0x92, 0x01, 0x02, 0xa1, 0x62, 0xa1, 0x63, 0x2a];
+/// let mut rd = &buf[..];
+///
+/// rmp::decode::skip_value(&mut rd).unwrap();
+///
+/// assert_eq!([0x2a], rd);
+/// ```
+#[inline]
+pub fn skip_value<R: RmpRead>(rd: &mut R) -> Result<(), SkipValueError<R::Error>> {
+    skip_value_with_max_depth(rd, MAX_SKIP_DEPTH)
+}
+
+/// Attempts to read and discard one complete MessagePack value, allowing arrays and maps to be
+/// nested at most `max_depth` levels deep.
+///
+/// A `max_depth` of zero only accepts scalar values. Limits above [`MAX_SKIP_DEPTH`] are lowered
+/// to it.
+///
+/// # Errors
+///
+/// See [`skip_value`].
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::SkipValueError;
+///
+/// // [[[]]]
+/// let buf = [0x91, 0x91, 0x90];
+///
+/// rmp::decode::skip_value_with_max_depth(&mut &buf[..], 3).unwrap();
+///
+/// match rmp::decode::skip_value_with_max_depth(&mut &buf[..], 2) {
+///     Err(SkipValueError::DepthLimitExceeded) => {}
+///     res => panic!("unexpected result: {:?}", res),
+/// }
+/// ```
+pub fn skip_value_with_max_depth<R: RmpRead>(rd: &mut R, max_depth: usize) -> Result<(), SkipValueError<R::Error>> {
+    let marker = read_marker(rd)?;
+    skip_value_from_marker(rd, marker, max_depth)
+}
+
+/// Attempts to discard the rest of a MessagePack value whose marker has already been read.
+///
+/// This is useful when the marker has been peeked to decide how to handle the value. Otherwise
+/// behaves like [`skip_value_with_max_depth`].
+pub fn skip_value_from_marker<R: RmpRead>(rd: &mut R, marker: Marker, max_depth: usize) -> Result<(), SkipValueError<R::Error>> {
+    match container_len(rd, marker)? {
+        Some(len) => skip_container(rd, len, max_depth.min(MAX_SKIP_DEPTH)),
+        None => skip_scalar_data(rd, marker),
+    }
+}
+
+/// Number of nesting levels [`skip_container`] tracks in a single stack frame.
+const INLINE_DEPTH: usize = 32;
+
+/// Skips the `len` values of an array or a map, counting keys and values of maps separately,
+/// where the container itself is the first of at most `max_depth` nesting levels.
+///
+/// Containers nested deeper than [`INLINE_DEPTH`] levels are skipped by a recursive call, so the
+/// common shallow values only need a small stack.
+fn skip_container<R: RmpRead>(rd: &mut R, len: u64, max_depth: usize) -> Result<(), SkipValueError<R::Error>> {
+    if max_depth == 0 {
+        return Err(SkipValueError::DepthLimitExceeded);
+    }
+
+    // Number of values left in each of the containers being skipped.
+    let mut stack = [0u64; INLINE_DEPTH];
+    stack[0] = len;
+    let mut depth = 1;
+
+    loop {
+        // Leave the containers that have been skipped completely.
+        while stack[depth - 1] == 0 {
+            depth -= 1;
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+        stack[depth - 1] -= 1;
+
+        let marker = read_marker(rd)?;
+        match container_len(rd, marker)? {
+            Some(_) if depth == max_depth => return Err(SkipValueError::DepthLimitExceeded),
+            Some(len) if depth == INLINE_DEPTH => skip_container(rd, len, max_depth - depth)?,
+            Some(len) => {
+                stack[depth] = len;
+                depth += 1;
+            }
+            None => skip_scalar_data(rd, marker)?,
+        }
+    }
+}
+
+/// Skips `count` values that are known to be valid, for example because they were skipped by
+/// [`skip_value_with_max_depth`] before.
+///
+/// Only the number of values left is tracked, so the nesting depth isn't checked again.
+pub(crate) fn skip_values_trusted<R: RmpRead>(rd: &mut R, mut count: u64) -> Result<(), SkipValueError<R::Error>> {
+    while count > 0 {
+        count -= 1;
+        let marker = read_marker(rd)?;
+        match container_len(rd, marker)? {
+            Some(len) => count += len,
+            None => skip_scalar_data(rd, marker)?,
+        }
+    }
+    Ok(())
+}
+
+/// Reads the length of an array or a map following its marker, counting keys and values of maps
+/// separately, or returns `None` for any other value.
+#[inline]
+fn container_len<R: RmpRead>(rd: &mut R, marker: Marker) -> Result<Option<u64>, SkipValueError<R::Error>> {
+    Ok(Some(match marker {
+        Marker::FixArray(len) => len as u64,
+        Marker::Array16 => rd.read_data_u16()? as u64,
+        Marker::Array32 => rd.read_data_u32()? as u64,
+        Marker::FixMap(len) => len as u64 * 2,
+        Marker::Map16 => rd.read_data_u16()? as u64 * 2,
+        Marker::Map32 => rd.read_data_u32()? as u64 * 2,
+        _ => return Ok(None),
+    }))
+}
+
+/// Skips the data following the marker of any value except arrays and maps.
+fn skip_scalar_data<R: RmpRead>(rd: &mut R, marker: Marker) -> Result<(), SkipValueError<R::Error>> {
+    let len = match marker {
+        Marker::FixPos(..) |
+        Marker::FixNeg(..) |
+        Marker::Null |
+        Marker::True |
+        Marker::False => 0,
+        Marker::U8 | Marker::I8 => 1,
+        Marker::U16 | Marker::I16 => 2,
+        Marker::U32 | Marker::I32 | Marker::F32 => 4,
+        Marker::U64 | Marker::I64 | Marker::F64 => 8,
+        Marker::FixStr(len) => len as usize,
+        Marker::Str8 | Marker::Bin8 => rd.read_data_u8()? as usize,
+        Marker::Str16 | Marker::Bin16 => rd.read_data_u16()? as usize,
+        Marker::Str32 | Marker::Bin32 => rd.read_data_u32()? as usize,
+        // Extensions are followed by the type byte and the data.
+        Marker::FixExt1 => 2,
+        Marker::FixExt2 => 3,
+        Marker::FixExt4 => 5,
+        Marker::FixExt8 => 9,
+        Marker::FixExt16 => 17,
+        Marker::Ext8 => rd.read_data_u8()? as usize + 1,
+        Marker::Ext16 => rd.read_data_u16()? as usize + 1,
+        Marker::Ext32 => {
+            // Skipped separately, since the length plus one may overflow on 32-bit platforms.
+            let len = rd.read_data_u32()? as usize;
+            rd.read_data_u8()?;
+            len
+        }
+        marker => return Err(SkipValueError::TypeMismatch(marker)),
+    };
+
+    rd.skip_bytes(len).map_err(SkipValueError::InvalidDataRead)
+}
@@ -0,0 +1,236 @@
+#[cfg(feature = "std")]
+use std::error;
+use core::fmt::{self, Display, Formatter};
+
+use super::reader::{read_event, Nesting};
+use super::{read_marker, Bytes, Event, RmpRead};
+use crate::Marker;
+
+/// Options controlling which inputs are accepted by [`validate_with_options`].
+///
+/// The default options reject the reserved marker, allow arrays and maps to be nested up to 1024
+/// levels deep and accept anything else that is structurally well-formed.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::ValidationOptions;
+///
+/// let opts = ValidationOptions::new()
+///     .reject_invalid_utf8(true)
+///     .reject_trailing_bytes(true)
+///     .max_depth(16)
+///     .max_container_len(1024);
+///
+/// assert_eq!(Ok(1), rmp::decode::validate_with_options(&[0xc0], &opts));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationOptions {
+    reject_reserved: bool,
+    reject_invalid_utf8: bool,
+    reject_trailing_bytes: bool,
+    max_depth: usize,
+    max_container_len: u32,
+}
+
+impl Default for ValidationOptions {
+    #[inline]
+    fn default() -> Self {
+        ValidationOptions::new()
+    }
+}
+
+impl ValidationOptions {
+    /// Constructs the default options.
+    #[inline]
+    pub fn new() -> Self {
+        ValidationOptions {
+            reject_reserved: true,
+            reject_invalid_utf8: false,
+            reject_trailing_bytes: false,
+            max_depth: 1024,
+            max_container_len: u32::MAX,
+        }
+    }
+
+    /// Sets whether the reserved marker `0xc1` is rejected, enabled by default.
+    ///
+    /// Otherwise it is accepted as a value without any data.
+    #[inline]
+    pub fn reject_reserved(mut self, reject: bool) -> Self {
+        self.reject_reserved = reject;
+        self
+    }
+
+    /// Sets whether strings must contain valid UTF-8, disabled by default.
+    #[inline]
+    pub fn reject_invalid_utf8(mut self, reject: bool) -> Self {
+        self.reject_invalid_utf8 = reject;
+        self
+    }
+
+    /// Sets whether the value must span the whole input, disabled by default.
+    #[inline]
+    pub fn reject_trailing_bytes(mut self, reject: bool) -> Self {
+        self.reject_trailing_bytes = reject;
+        self
+    }
+
+    /// Sets the maximum nesting depth of arrays and maps.
+    #[inline]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets the maximum number of elements of arrays and of key-value pairs of maps.
+    #[inline]
+    pub fn max_container_len(mut self, len: u32) -> Self {
+        self.max_container_len = len;
+        self
+    }
+}
+
+/// An error which can occur when validating a MessagePack value.
+///
+/// Offsets point at the marker of the offending value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The input ended before the value was complete.
+    UnexpectedEof,
+    /// The reserved marker `0xc1` was encountered.
+    ReservedMarker {
+        /// Offset of the marker.
+        offset: usize,
+    },
+    /// A string contains invalid UTF-8.
+    InvalidUtf8 {
+        /// Offset of the string marker.
+        offset: usize,
+    },
+    /// Arrays and maps are nested deeper than allowed.
+    DepthLimitExceeded {
+        /// Offset of the array or map marker.
+        offset: usize,
+    },
+    /// An array or a map has more elements than allowed.
+    ContainerTooLong {
+        /// Offset of the array or map marker.
+        offset: usize,
+        /// The number of elements or key-value pairs.
+        len: u32,
+    },
+    /// There are bytes left after the value.
+    TrailingBytes {
+        /// Offset of the first trailing byte.
+        offset: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ValidationError {}
+
+impl Display for ValidationError {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            ValidationError::UnexpectedEof => f.write_str("unexpected end of MessagePack data"),
+            ValidationError::ReservedMarker { offset } => {
+                write!(f, "reserved MessagePack marker at offset {}", offset)
+            }
+            ValidationError::InvalidUtf8 { offset } => {
+                write!(f, "string with invalid UTF-8 at offset {}", offset)
+            }
+            ValidationError::DepthLimitExceeded { offset } => {
+                write!(f, "depth limit exceeded at offset {}", offset)
+            }
+            ValidationError::ContainerTooLong { offset, len } => {
+                write!(f, "container of length {} at offset {} is too long", len, offset)
+            }
+            ValidationError::TrailingBytes { offset } => {
+                write!(f, "trailing bytes after MessagePack value at offset {}", offset)
+            }
+        }
+    }
+}
+
+/// Checks that the given buffer starts with a well-formed MessagePack value, returning its length
+/// in bytes.
+///
+/// This uses the default [`ValidationOptions`], see [`validate_with_options`] for stricter checks.
+/// Nothing is decoded or allocated besides the bookkeeping of nested arrays and maps.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::decode::ValidationError;
+///
+/// // [1, "a"] followed by a trailing byte.
+/// assert_eq!(Ok(4), rmp::decode::validate(&[0x92, 0x01, 0xa1, 0x61, 0xc0]));
+///
+/// assert_eq!(Err(ValidationError::UnexpectedEof), rmp::decode::validate(&[0x92, 0x01]));
+/// ```
+#[inline]
+pub fn validate(buf: &[u8]) -> Result<usize, ValidationError> {
+    validate_with_options(buf, &ValidationOptions::new())
+}
+
+/// Checks that the given buffer starts with a MessagePack value that is well-formed and satisfies
+/// the given options, returning its length in bytes.
+///
+/// # Errors
+///
+/// Returns `ValidationError` describing the first problem found.
+pub fn validate_with_options(buf: &[u8], opts: &ValidationOptions) -> Result<usize, ValidationError> {
+    let mut rd = Bytes::new(buf);
+    let mut nesting = Nesting::new(opts.max_depth);
+
+    loop {
+        let offset = rd.position() as usize;
+        let marker = read_marker(&mut rd).map_err(|_| ValidationError::UnexpectedEof)?;
+
+        let event = match marker {
+            Marker::Reserved if opts.reject_reserved => {
+                return Err(ValidationError::ReservedMarker { offset });
+            }
+            // Accepted as an opaque value, which has no data as far as anyone knows.
+            Marker::Reserved => Event::Nil,
+            marker => read_event(&mut rd, marker).map_err(|_| ValidationError::UnexpectedEof)?,
+        };
+
+        if let Event::ArrayStart(len) | Event::MapStart(len) = event {
+            if len > opts.max_container_len {
+                return Err(ValidationError::ContainerTooLong { offset, len });
+            }
+        }
+        if !nesting.push(&event) {
+            return Err(ValidationError::DepthLimitExceeded { offset });
+        }
+
+        match event {
+            Event::Str(len) => {
+                let data = rd.remaining_slice().get(..len as usize).ok_or(ValidationError::UnexpectedEof)?;
+                if opts.reject_invalid_utf8 && core::str::from_utf8(data).is_err() {
+                    return Err(ValidationError::InvalidUtf8 { offset });
+                }
+                rd.skip_bytes(len as usize).map_err(|_| ValidationError::UnexpectedEof)?;
+            }
+            Event::Bin(len) | Event::Ext { len, .. } => {
+                rd.skip_bytes(len as usize).map_err(|_| ValidationError::UnexpectedEof)?;
+            }
+            _ => {}
+        }
+
+        while nesting.pop_end().is_some() {}
+        if nesting.depth() == 0 {
+            break;
+        }
+    }
+
+    let len = rd.position() as usize;
+    if opts.reject_trailing_bytes && len < buf.len() {
+        return Err(ValidationError::TrailingBytes { offset: len });
+    }
+
+    Ok(len)
+}
@@ -1,7 +1,6 @@
 //! Implementation of the [ByteBuf] type
 
-use super::RmpWrite;
-#[cfg(not(feature = "std"))]
+use super::{RmpWrite, RmpWriteErr};
 use core::fmt::{self, Display, Formatter};
 use alloc::vec::Vec;
 
@@ -36,7 +35,7 @@ impl Display for FixedBufCapacityOverflow {
     }
 }
 #[cfg(not(feature = "std"))]
-impl crate::encode::RmpWriteErr for FixedBufCapacityOverflow {}
+impl RmpWriteErr for FixedBufCapacityOverflow {}
 
 /// Fallback implementation for fixed-capacity buffers
 ///
@@ -181,4 +180,163 @@ impl<'a> RmpWrite for Vec<u8> {
         self.extend_from_slice(buf);
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// Indicates that an error occurred writing to a [SliceWriter]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SliceWriteError {
+    /// Indicates that there was not enough space left in the buffer.
+    ///
+    /// Nothing of the rejected write is stored in the buffer.
+    InsufficientSpace {
+        /// The number of bytes that were attempted to be written.
+        expected: usize,
+        /// The number of bytes left in the buffer.
+        actual: usize,
+        /// The number of bytes written before.
+        position: usize,
+    }
+}
+
+impl Display for SliceWriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            SliceWriteError::InsufficientSpace { expected, actual, position } => {
+                write!(f, "Expected space for {} bytes, but only {} left (pos {})", expected, actual, position)
+            }
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for SliceWriteError {}
+impl RmpWriteErr for SliceWriteError {}
+
+/// A writer into a caller-provided buffer of fixed capacity, like a stack array.
+///
+/// Unlike a plain `&mut [u8]` this tracks the number of bytes written (See [Self::written]) and
+/// never stores a part of a write that doesn't fit. It never allocates, so it is suitable for
+/// `#![no_std]` targets without a heap.
+///
+/// A MessagePack value is usually made of several writes, so a value that doesn't fit may still
+/// be stored partially. Save a checkpoint with [Self::written] before writing it and
+/// [rollback](Self::rollback) to it on error to drop such partial values.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::encode::buffer::SliceWriter;
+///
+/// let mut buf = [0; 8];
+/// let mut wr = SliceWriter::new(&mut buf);
+///
+/// for reading in ["ok", "a long string that does not fit"] {
+///     let checkpoint = wr.written();
+///     if rmp::encode::write_str(&mut wr, reading).is_err() {
+///         wr.rollback(checkpoint);
+///     }
+/// }
+///
+/// assert_eq!(&[0xa2, 0x6f, 0x6b], wr.as_slice());
+/// ```
+#[derive(Debug)]
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Wrap an existing buffer, writing from its beginning.
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, pos: 0 }
+    }
+    /// Return the number of bytes written so far.
+    #[inline]
+    pub fn written(&self) -> usize {
+        self.pos
+    }
+    /// Return the number of bytes that can still be written.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+    /// Return the capacity of the underlying buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+    /// Discard everything written after the given checkpoint, previously returned by
+    /// [Self::written].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint is past the number of bytes written.
+    #[inline]
+    pub fn rollback(&mut self, checkpoint: usize) {
+        assert!(checkpoint <= self.pos, "checkpoint {} is past the written bytes {}", checkpoint, self.pos);
+        self.pos = checkpoint;
+    }
+    /// Discard everything written, as if the writer were just created.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.pos = 0;
+    }
+    /// Get a reference to the bytes written so far.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+    /// Get a mutable reference to the bytes written so far.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.pos]
+    }
+    /// Unwrap this writer, returning the bytes written with the lifetime of the buffer.
+    #[inline]
+    pub fn into_slice(self) -> &'a mut [u8] {
+        &mut self.buf[..self.pos]
+    }
+}
+
+impl AsRef<[u8]> for SliceWriter<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl RmpWrite for SliceWriter<'_> {
+    type Error = SliceWriteError;
+
+    #[inline]
+    fn write_u8(&mut self, val: u8) -> Result<(), Self::Error> {
+        match self.buf.get_mut(self.pos) {
+            Some(byte) => {
+                *byte = val;
+                self.pos += 1;
+                Ok(())
+            }
+            None => Err(SliceWriteError::InsufficientSpace {
+                expected: 1,
+                actual: 0,
+                position: self.pos
+            })
+        }
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let to_write = buf.len();
+        if to_write <= self.remaining() {
+            self.buf[self.pos..self.pos + to_write].copy_from_slice(buf);
+            self.pos += to_write;
+            Ok(())
+        } else {
+            Err(SliceWriteError::InsufficientSpace {
+                expected: to_write,
+                actual: self.remaining(),
+                position: self.pos
+            })
+        }
+    }
+}
@@ -29,3 +29,53 @@ pub fn write_f64<W: RmpWrite>(wr: &mut W, val: f64) -> Result<(), ValueWriteErro
     wr.write_data_f64(val)?;
     Ok(())
 }
+
+/// Encodes and attempts to write an `f32` value in the canonical form, returning the marker used.
+///
+/// This is the same as [`write_f64_canonical`], since every `f32` value round-trips through
+/// `f64`: it is written as an `f32`, except that all NaNs are replaced by a single quiet NaN.
+///
+/// # Errors
+///
+/// This function will return `ValueWriteError` on any I/O error occurred while writing either the
+/// marker or the data.
+#[inline]
+pub fn write_f32_canonical<W: RmpWrite>(wr: &mut W, val: f32) -> Result<Marker, ValueWriteError<W::Error>> {
+    write_f64_canonical(wr, val as f64)
+}
+
+/// Encodes and attempts to write an `f64` value in the canonical form, returning the marker used.
+///
+/// The value is written as an `f32` whenever that is lossless, i.e. converting it to `f32` and
+/// back yields the same value, including infinities and signed zeros. All NaNs are written as the
+/// `f32` quiet NaN `0x7fc00000`. Otherwise the value is written as an `f64`.
+///
+/// # Errors
+///
+/// This function will return `ValueWriteError` on any I/O error occurred while writing either the
+/// marker or the data.
+///
+/// # Examples
+///
+/// ```
+/// let mut buf = Vec::new();
+///
+/// rmp::encode::write_f64_canonical(&mut buf, 0.5).unwrap();
+/// assert_eq!([0xca, 0x3f, 0x00, 0x00, 0x00], buf[..]);
+///
+/// buf.clear();
+/// rmp::encode::write_f64_canonical(&mut buf, 0.1).unwrap();
+/// assert_eq!([0xcb, 0x3f, 0xb9, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a], buf[..]);
+/// ```
+pub fn write_f64_canonical<W: RmpWrite>(wr: &mut W, val: f64) -> Result<Marker, ValueWriteError<W::Error>> {
+    if val.is_nan() {
+        write_f32(wr, f32::NAN)?;
+        Ok(Marker::F32)
+    } else if val as f32 as f64 == val {
+        write_f32(wr, val as f32)?;
+        Ok(Marker::F32)
+    } else {
+        write_f64(wr, val)?;
+        Ok(Marker::F64)
+    }
+}
@@ -1 +1,37 @@
+use super::{write_ext_meta, RmpWrite};
+use crate::encode::ValueWriteError;
+use crate::{Marker, Timestamp};
 
+/// Encodes and attempts to write the given timestamp using the most efficient layout of the
+/// MessagePack timestamp extension type, returning the marker used.
+///
+/// The layout is chosen as follows:
+///
+/// - timestamp 32 (`fixext4`) if there are no nanoseconds and the seconds fit in `u32`.
+/// - timestamp 64 (`fixext8`) if the seconds fit in an unsigned 34-bit integer.
+/// - timestamp 96 (`ext8`) otherwise.
+///
+/// # Errors
+///
+/// This function will return `ValueWriteError` on any I/O error occurred while writing either the
+/// marker or the data.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::{Marker, Timestamp};
+///
+/// let mut buf = Vec::new();
+///
+/// assert_eq!(Marker::FixExt4, rmp::encode::write_timestamp(&mut buf, Timestamp::from_secs(42)).unwrap());
+/// assert_eq!([0xd6, 0xff, 0x00, 0x00, 0x00, 0x2a], buf[..]);
+/// ```
+pub fn write_timestamp<W: RmpWrite>(wr: &mut W, ts: Timestamp) -> Result<Marker, ValueWriteError<W::Error>> {
+    let mut buf = [0; 12];
+    let data = ts.to_ext_data(&mut buf);
+
+    let marker = write_ext_meta(wr, data.len() as u32, Timestamp::EXT_TYPE)?;
+    wr.write_bytes(data).map_err(ValueWriteError::InvalidDataWrite)?;
+
+    Ok(marker)
+}
@@ -4,14 +4,25 @@ mod bin;
 mod dec;
 mod ext;
 mod map;
+mod patch;
 mod sint;
+mod size;
 mod str;
 mod uint;
 mod vec;
 
 pub use self::bin::{write_bin, write_bin_len};
-pub use self::dec::{write_f32, write_f64};
+pub use self::dec::{write_f32, write_f32_canonical, write_f64, write_f64_canonical};
+pub use self::ext::write_timestamp;
+pub use self::patch::{
+    begin_array_unknown, begin_map_unknown, finish_array, finish_array_minimal, finish_map, finish_map_minimal,
+    ReservedLen, RmpPatch,
+};
 pub use self::sint::{write_i16, write_i32, write_i64, write_i8, write_nfix, write_sint};
+pub use self::size::{
+    size_of_array_header, size_of_bin_header, size_of_ext_header, size_of_map_header, size_of_sint,
+    size_of_str_header, size_of_uint,
+};
 pub use self::str::{write_str, write_str_len};
 pub use self::uint::{write_pfix, write_u16, write_u32, write_u64, write_u8, write_uint};
 
@@ -22,7 +33,7 @@ use core::fmt::{self, Display, Debug, Formatter};
 use crate::Marker;
 
 pub mod buffer;
-pub use buffer::ByteBuf;
+pub use buffer::{ByteBuf, SliceWriter};
 
 #[doc(inline)]
 #[allow(deprecated)]
@@ -32,6 +43,8 @@ pub use crate::errors::Error;
 ///
 /// For [std::io::Write], this is [std::io::Error]
 /// For [ByteBuf], this is [core::convert::Infallible]
+///
+/// Custom error types must also implement [std::error::Error] when the `std` feature is enabled.
 pub trait RmpWriteErr: Display + Debug + crate::errors::MaybeErrBound + 'static {}
 #[cfg(feature = "std")]
 impl RmpWriteErr for std::io::Error {}
@@ -104,18 +117,6 @@ pub fn write_bool<W: RmpWrite>(wr: &mut W, val: bool) -> Result<(), W::Error> {
     write_marker(wr, marker).map_err(|e| e.0)
 }
 
-mod sealed{
-    pub trait Sealed {}
-    #[cfg(feature = "std")]
-    impl<T: ?Sized + std::io::Write> Sealed for T {}
-    #[cfg(not(feature = "std"))]
-    impl Sealed for &mut [u8] {}
-    #[cfg(not(feature = "std"))]
-    impl Sealed for alloc::vec::Vec<u8> {}
-    impl Sealed for super::ByteBuf {}
-}
-
-
 macro_rules! write_byteorder_utils {
     ($($name:ident => $tp:ident),* $(,)?) => {
         $(
@@ -135,13 +136,39 @@ macro_rules! write_byteorder_utils {
 
 /// A type that `rmp` supports writing into.
 ///
-/// The methods of this trait should be considered an implementation detail (for now).
-/// It is currently sealed (can not be implemented by the user).
+/// Its primary implementations are [std::io::Write] and [ByteBuf], but it can be implemented for
+/// any other sink of bytes, like a ring buffer or a DMA channel on `no_std` targets. Only
+/// [`write_bytes`](Self::write_bytes) is required.
 ///
-/// See also [std::uo::Write] and [byteorder::WriteBytesExt]
+/// The contract of every method is that it either writes all of the given bytes or fails. After
+/// an error the number of bytes written is unspecified, so the output should be considered broken.
+///
+/// See also [std::io::Write] and [byteorder::WriteBytesExt]
+///
+/// # Examples
 ///
-/// Its primary implementations are [std::io::Write] and [ByteBuf].
-pub trait RmpWrite: sealed::Sealed {
+/// ```
+/// use core::convert::Infallible;
+/// use rmp::encode::RmpWrite;
+///
+/// /// Counts the bytes written without storing them.
+/// struct Counter(usize);
+///
+/// impl RmpWrite for Counter {
+///     type Error = Infallible;
+///
+///     fn write_bytes(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+///         self.0 += buf.len();
+///         Ok(())
+///     }
+/// }
+///
+/// let mut wr = Counter(0);
+/// rmp::encode::write_str(&mut wr, "le message").unwrap();
+/// assert_eq!(11, wr.0);
+/// ```
+pub trait RmpWrite {
+    /// The error returned when writing fails, [std::io::Error] for [std::io::Write].
     type Error: RmpWriteErr;
 
     /// Write a single byte to this stream
@@ -0,0 +1,273 @@
+//! Writing arrays and maps whose length is not known upfront, by patching their headers.
+
+use alloc::vec::Vec;
+
+use super::buffer::{ByteBuf, SliceWriter};
+use super::{write_array_len, write_map_len, RmpWrite, ValueWriteError};
+use crate::Marker;
+
+/// An in-memory writer that can modify the bytes it has already written.
+///
+/// This allows reserving the header of an array or a map with [`begin_array_unknown`] or
+/// [`begin_map_unknown`], writing the elements directly and patching the header with the actual
+/// length afterwards, without buffering the elements elsewhere.
+pub trait RmpPatch: RmpWrite {
+    /// Returns the number of bytes written so far, which is the position of the next byte.
+    fn position(&self) -> usize;
+
+    /// Returns the bytes written so far.
+    fn written_bytes(&self) -> &[u8];
+
+    /// Overwrites the bytes at the given position with the given ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the overwritten bytes were not written yet.
+    fn patch(&mut self, pos: usize, buf: &[u8]);
+
+    /// Removes `len` bytes at the given position, moving all bytes written after them back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the removed bytes were not written yet.
+    fn remove(&mut self, pos: usize, len: usize);
+}
+
+impl RmpPatch for Vec<u8> {
+    #[inline]
+    fn position(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn written_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    fn patch(&mut self, pos: usize, buf: &[u8]) {
+        self[pos..pos + buf.len()].copy_from_slice(buf);
+    }
+
+    #[inline]
+    fn remove(&mut self, pos: usize, len: usize) {
+        self.drain(pos..pos + len);
+    }
+}
+
+impl RmpPatch for ByteBuf {
+    #[inline]
+    fn position(&self) -> usize {
+        RmpPatch::position(self.as_vec())
+    }
+
+    #[inline]
+    fn written_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn patch(&mut self, pos: usize, buf: &[u8]) {
+        RmpPatch::patch(self.as_mut_vec(), pos, buf);
+    }
+
+    #[inline]
+    fn remove(&mut self, pos: usize, len: usize) {
+        RmpPatch::remove(self.as_mut_vec(), pos, len);
+    }
+}
+
+impl RmpPatch for SliceWriter<'_> {
+    #[inline]
+    fn position(&self) -> usize {
+        self.written()
+    }
+
+    #[inline]
+    fn written_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn patch(&mut self, pos: usize, buf: &[u8]) {
+        self.as_mut_slice()[pos..pos + buf.len()].copy_from_slice(buf);
+    }
+
+    #[inline]
+    fn remove(&mut self, pos: usize, len: usize) {
+        let end = self.written();
+        self.as_mut_slice().copy_within(pos + len..end, pos);
+        self.rollback(end - len);
+    }
+}
+
+impl<'a, W: RmpPatch + ?Sized> RmpPatch for &'a mut W
+where
+    &'a mut W: RmpWrite,
+{
+    #[inline]
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    #[inline]
+    fn written_bytes(&self) -> &[u8] {
+        (**self).written_bytes()
+    }
+
+    #[inline]
+    fn patch(&mut self, pos: usize, buf: &[u8]) {
+        (**self).patch(pos, buf);
+    }
+
+    #[inline]
+    fn remove(&mut self, pos: usize, len: usize) {
+        (**self).remove(pos, len);
+    }
+}
+
+/// The header of an array or a map of unknown length, reserved in a writer.
+///
+/// It must be completed by one of the `finish_*` functions once all elements are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use = "the reserved header must be finished once the length is known"]
+pub struct ReservedLen {
+    pos: usize,
+}
+
+impl ReservedLen {
+    /// Returns the position of the reserved header in the writer.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+/// Reserves the header of an array whose length is not known yet, as an `Array32` marker followed
+/// by a placeholder length.
+///
+/// The elements can be written right after this, and the header must be completed by
+/// [`finish_array`] or [`finish_array_minimal`] afterwards.
+///
+/// Only in-memory writers implementing [`RmpPatch`] are supported. Files and other `Write + Seek`
+/// writers are out of scope, since shrinking a header moves all bytes written after it.
+///
+/// # Errors
+///
+/// This function will return `ValueWriteError` on any I/O error occurred while writing either the
+/// marker or the data.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::encode::{begin_array_unknown, finish_array_minimal, write_uint};
+///
+/// let mut buf = Vec::new();
+/// let header = begin_array_unknown(&mut buf).unwrap();
+///
+/// let mut len = 0;
+/// for val in (1..10).filter(|val| val % 4 == 0) {
+///     write_uint(&mut buf, val).unwrap();
+///     len += 1;
+/// }
+///
+/// finish_array_minimal(&mut buf, header, len);
+/// assert_eq!(vec![0x92, 0x04, 0x08], buf);
+/// ```
+pub fn begin_array_unknown<W: RmpPatch>(wr: &mut W) -> Result<ReservedLen, ValueWriteError<W::Error>> {
+    begin_unknown(wr, Marker::Array32)
+}
+
+/// Reserves the header of a map whose length is not known yet, as a `Map32` marker followed by a
+/// placeholder length.
+///
+/// The entries can be written right after this, and the header must be completed by
+/// [`finish_map`] or [`finish_map_minimal`] afterwards.
+///
+/// # Errors
+///
+/// This function will return `ValueWriteError` on any I/O error occurred while writing either the
+/// marker or the data.
+pub fn begin_map_unknown<W: RmpPatch>(wr: &mut W) -> Result<ReservedLen, ValueWriteError<W::Error>> {
+    begin_unknown(wr, Marker::Map32)
+}
+
+fn begin_unknown<W: RmpPatch>(wr: &mut W, marker: Marker) -> Result<ReservedLen, ValueWriteError<W::Error>> {
+    let pos = wr.position();
+    wr.write_u8(marker.to_u8()).map_err(ValueWriteError::InvalidMarkerWrite)?;
+    wr.write_bytes(&[0; 4]).map_err(ValueWriteError::InvalidDataWrite)?;
+
+    Ok(ReservedLen { pos })
+}
+
+/// Completes the reserved header of an array with its length, keeping the `Array32` marker.
+///
+/// This never moves the elements already written.
+///
+/// # Panics
+///
+/// Panics if the header was not reserved in this writer.
+pub fn finish_array<W: RmpPatch>(wr: &mut W, header: ReservedLen, len: u32) -> Marker {
+    finish_fixed(wr, header, Marker::Array32, len)
+}
+
+/// Completes the reserved header of a map with its number of entries, keeping the `Map32` marker.
+///
+/// This never moves the entries already written.
+///
+/// # Panics
+///
+/// Panics if the header was not reserved in this writer.
+pub fn finish_map<W: RmpPatch>(wr: &mut W, header: ReservedLen, len: u32) -> Marker {
+    finish_fixed(wr, header, Marker::Map32, len)
+}
+
+fn finish_fixed<W: RmpPatch>(wr: &mut W, header: ReservedLen, marker: Marker, len: u32) -> Marker {
+    let len = len.to_be_bytes();
+    wr.patch(header.pos, &[marker.to_u8(), len[0], len[1], len[2], len[3]]);
+
+    marker
+}
+
+/// Completes the reserved header of an array with its length, replacing it with the smallest one
+/// like [`write_array_len`] does and returning the marker used.
+///
+/// Unless the `Array32` marker is needed anyway, the elements already written are moved back to
+/// close the gap, so the output is the same as if the length were known upfront.
+///
+/// # Panics
+///
+/// Panics if the header was not reserved in this writer.
+pub fn finish_array_minimal<W: RmpPatch>(wr: &mut W, header: ReservedLen, len: u32) -> Marker {
+    let mut buf = [0; 5];
+    let mut hdr = SliceWriter::new(&mut buf);
+    let marker = write_array_len(&mut hdr, len).expect("the header buffer is large enough");
+    finish_minimal(wr, header, hdr.as_slice());
+
+    marker
+}
+
+/// Completes the reserved header of a map with its number of entries, replacing it with the
+/// smallest one like [`write_map_len`] does and returning the marker used.
+///
+/// Unless the `Map32` marker is needed anyway, the entries already written are moved back to
+/// close the gap, so the output is the same as if the length were known upfront.
+///
+/// # Panics
+///
+/// Panics if the header was not reserved in this writer.
+pub fn finish_map_minimal<W: RmpPatch>(wr: &mut W, header: ReservedLen, len: u32) -> Marker {
+    let mut buf = [0; 5];
+    let mut hdr = SliceWriter::new(&mut buf);
+    let marker = write_map_len(&mut hdr, len).expect("the header buffer is large enough");
+    finish_minimal(wr, header, hdr.as_slice());
+
+    marker
+}
+
+fn finish_minimal<W: RmpPatch>(wr: &mut W, header: ReservedLen, buf: &[u8]) {
+    wr.patch(header.pos, buf);
+    if buf.len() < 5 {
+        wr.remove(header.pos + buf.len(), 5 - buf.len());
+    }
+}
@@ -0,0 +1,104 @@
+//! Functions computing the number of bytes written by the encoding functions, without writing.
+
+/// Returns the number of bytes [`write_uint`](super::write_uint) writes for the given value.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(1, rmp::encode::size_of_uint(127));
+/// assert_eq!(3, rmp::encode::size_of_uint(300));
+/// ```
+#[inline]
+pub const fn size_of_uint(val: u64) -> usize {
+    if val < 128 {
+        1
+    } else if val < 256 {
+        2
+    } else if val < 65536 {
+        3
+    } else if val < 4294967296 {
+        5
+    } else {
+        9
+    }
+}
+
+/// Returns the number of bytes [`write_sint`](super::write_sint) writes for the given value.
+///
+/// Non-negative values are written the same way as by [`write_uint`](super::write_uint).
+#[inline]
+pub const fn size_of_sint(val: i64) -> usize {
+    if val >= 0 {
+        size_of_uint(val as u64)
+    } else if val >= -32 {
+        1
+    } else if val >= -128 {
+        2
+    } else if val >= -32768 {
+        3
+    } else if val >= -2147483648 {
+        5
+    } else {
+        9
+    }
+}
+
+/// Returns the number of bytes [`write_str_len`](super::write_str_len) writes for the given
+/// length, not including the string itself.
+#[inline]
+pub const fn size_of_str_header(len: u32) -> usize {
+    if len < 32 {
+        1
+    } else if len < 256 {
+        2
+    } else if len <= u16::MAX as u32 {
+        3
+    } else {
+        5
+    }
+}
+
+/// Returns the number of bytes [`write_bin_len`](super::write_bin_len) writes for the given
+/// length, not including the data itself.
+#[inline]
+pub const fn size_of_bin_header(len: u32) -> usize {
+    if len < 256 {
+        2
+    } else if len <= u16::MAX as u32 {
+        3
+    } else {
+        5
+    }
+}
+
+/// Returns the number of bytes [`write_array_len`](super::write_array_len) writes for the given
+/// length, not including the elements.
+#[inline]
+pub const fn size_of_array_header(len: u32) -> usize {
+    if len < 16 {
+        1
+    } else if len <= u16::MAX as u32 {
+        3
+    } else {
+        5
+    }
+}
+
+/// Returns the number of bytes [`write_map_len`](super::write_map_len) writes for the given
+/// length, not including the entries.
+#[inline]
+pub const fn size_of_map_header(len: u32) -> usize {
+    size_of_array_header(len)
+}
+
+/// Returns the number of bytes [`write_ext_meta`](super::write_ext_meta) writes for the given
+/// length, not including the data itself.
+#[inline]
+pub const fn size_of_ext_header(len: u32) -> usize {
+    match len {
+        1 | 2 | 4 | 8 | 16 => 2,
+        len if len < 256 => 3,
+        len if len < 65536 => 4,
+        _ => 6,
+    }
+}
@@ -36,6 +36,11 @@
 //!   RMP allows to decode bytes from a buffer in a zero-copy manner easily and blazingly fast, while Rust
 //!   static checks guarantees that the data will be valid until buffer lives.
 //!
+//! - **Async I/O**
+//!
+//!   With the `tokio` or `futures` feature enabled, values can be decoded directly from the
+//!   corresponding `AsyncRead` streams. The `async` feature enables both.
+//!
 //! - **Clear error handling**
 //!
 //!   RMP's error system guarantees that you never receive an error enum with unreachable variant.
@@ -155,8 +160,10 @@ pub mod decode;
 pub mod encode;
 mod marker;
 mod errors;
+mod timestamp;
 
 pub use crate::marker::Marker;
+pub use crate::timestamp::Timestamp;
 
 /// Version of the MessagePack [spec](http://github.com/msgpack/msgpack/blob/master/spec.md).
 pub const MSGPACK_VERSION: u32 = 5;
@@ -0,0 +1,259 @@
+//! Implementation of the [Timestamp] type
+
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// A point in time as defined by the MessagePack timestamp extension type.
+///
+/// The timestamp is represented as a number of seconds elapsed since `1970-01-01 00:00:00 UTC`
+/// and a number of nanoseconds within that second. Seconds may be negative for points in time
+/// before the epoch, nanoseconds are always non-negative and less than `1_000_000_000`.
+///
+/// According to the MessagePack specification, timestamps are encoded as an extension with type
+/// [`-1`](Self::EXT_TYPE) in one of three layouts:
+///
+/// - timestamp 32 stores unsigned 32-bit seconds in a fixext4.
+/// - timestamp 64 stores 30-bit nanoseconds and unsigned 34-bit seconds in a fixext8.
+/// - timestamp 96 stores 32-bit nanoseconds and signed 64-bit seconds in an ext8 of length 12.
+///
+/// # Examples
+///
+/// ```
+/// use rmp::Timestamp;
+///
+/// let ts = Timestamp::new(1_500_000_000, 42).unwrap();
+///
+/// assert_eq!(1_500_000_000, ts.secs());
+/// assert_eq!(42, ts.nanos());
+///
+/// // Nanoseconds must fit in one second.
+/// assert_eq!(None, Timestamp::new(0, 1_000_000_000));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Timestamp {
+    secs: i64,
+    nanos: u32,
+}
+
+impl Timestamp {
+    /// The extension type reserved by the MessagePack specification for timestamps.
+    pub const EXT_TYPE: i8 = -1;
+
+    /// Constructs a new timestamp from seconds since the epoch and nanoseconds within the second.
+    ///
+    /// Returns `None` if `nanos` is not less than `1_000_000_000`.
+    #[inline]
+    pub fn new(secs: i64, nanos: u32) -> Option<Self> {
+        if nanos < NANOS_PER_SEC {
+            Some(Timestamp { secs, nanos })
+        } else {
+            None
+        }
+    }
+
+    /// Constructs a new timestamp from whole seconds since the epoch.
+    #[inline]
+    pub fn from_secs(secs: i64) -> Self {
+        Timestamp { secs, nanos: 0 }
+    }
+
+    /// Returns the number of whole seconds since the epoch.
+    #[inline]
+    pub fn secs(&self) -> i64 {
+        self.secs
+    }
+
+    /// Returns the fractional part of this timestamp in nanoseconds.
+    #[inline]
+    pub fn nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    /// Returns the length of the extension data of the most compact layout able to represent this
+    /// timestamp, i.e. either 4, 8 or 12 bytes.
+    #[inline]
+    pub fn ext_len(&self) -> u32 {
+        if self.secs >> 34 == 0 {
+            if self.nanos == 0 && self.secs >> 32 == 0 {
+                4
+            } else {
+                8
+            }
+        } else {
+            12
+        }
+    }
+
+    /// Encodes this timestamp as the extension data of the most compact layout into the given
+    /// buffer, returning the encoded part of it.
+    ///
+    /// The extension header is not written, see [`write_timestamp`](crate::encode::write_timestamp)
+    /// for encoding a complete value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp::Timestamp;
+    ///
+    /// let mut buf = [0; 12];
+    ///
+    /// assert_eq!([0x00, 0x00, 0x00, 0x2a], Timestamp::from_secs(42).to_ext_data(&mut buf));
+    /// ```
+    pub fn to_ext_data<'a>(&self, buf: &'a mut [u8; 12]) -> &'a [u8] {
+        match self.ext_len() {
+            4 => {
+                buf[..4].copy_from_slice(&(self.secs as u32).to_be_bytes());
+                &buf[..4]
+            }
+            8 => {
+                let data = ((self.nanos as u64) << 34) | self.secs as u64;
+                buf[..8].copy_from_slice(&data.to_be_bytes());
+                &buf[..8]
+            }
+            _ => {
+                buf[..4].copy_from_slice(&self.nanos.to_be_bytes());
+                buf[4..].copy_from_slice(&self.secs.to_be_bytes());
+                &buf[..]
+            }
+        }
+    }
+
+    /// Decodes a timestamp from extension data of any of the three layouts.
+    ///
+    /// Returns `None` if the length of the data does not match any layout or if the encoded
+    /// nanoseconds are out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmp::Timestamp;
+    ///
+    /// assert_eq!(Some(Timestamp::from_secs(42)), Timestamp::from_ext_data(&[0x00, 0x00, 0x00, 0x2a]));
+    /// assert_eq!(None, Timestamp::from_ext_data(&[0x00, 0x2a]));
+    /// ```
+    pub fn from_ext_data(data: &[u8]) -> Option<Self> {
+        match data.len() {
+            4 => {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(data);
+                Some(Timestamp::from_secs(u32::from_be_bytes(buf) as i64))
+            }
+            8 => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(data);
+                let data = u64::from_be_bytes(buf);
+                Timestamp::new((data & 0x0000_0003_ffff_ffff) as i64, (data >> 34) as u32)
+            }
+            12 => {
+                let mut nanos = [0; 4];
+                let mut secs = [0; 8];
+                nanos.copy_from_slice(&data[..4]);
+                secs.copy_from_slice(&data[4..]);
+                Timestamp::new(i64::from_be_bytes(secs), u32::from_be_bytes(nanos))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts a system time into a timestamp.
+    ///
+    /// Returns `None` if the number of seconds from the epoch does not fit in `i64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use rmp::Timestamp;
+    ///
+    /// let time = UNIX_EPOCH - Duration::new(1, 500_000_000);
+    ///
+    /// assert_eq!(Timestamp::new(-2, 500_000_000), Timestamp::from_system_time(time));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: SystemTime) -> Option<Self> {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(dur) => Timestamp::from_duration(dur),
+            Err(err) => {
+                let dur = err.duration();
+                let secs = i64::try_from(dur.as_secs()).ok()?;
+                if dur.subsec_nanos() == 0 {
+                    Some(Timestamp::from_secs(-secs))
+                } else {
+                    Some(Timestamp {
+                        secs: (-secs).checked_sub(1)?,
+                        nanos: NANOS_PER_SEC - dur.subsec_nanos(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Converts this timestamp into a system time.
+    ///
+    /// Returns `None` if the point in time cannot be represented by the platform's `SystemTime`.
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        if self.secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(self.secs as u64, self.nanos))
+        } else {
+            UNIX_EPOCH
+                .checked_sub(Duration::from_secs(self.secs.unsigned_abs()))?
+                .checked_add(Duration::from_nanos(self.nanos as u64))
+        }
+    }
+
+    /// Converts a duration elapsed since the epoch into a timestamp.
+    ///
+    /// Returns `None` if the number of seconds does not fit in `i64`.
+    #[cfg(feature = "std")]
+    pub fn from_duration(dur: Duration) -> Option<Self> {
+        let secs = i64::try_from(dur.as_secs()).ok()?;
+        Some(Timestamp { secs, nanos: dur.subsec_nanos() })
+    }
+
+    /// Converts this timestamp into a duration elapsed since the epoch.
+    ///
+    /// Returns `None` if the timestamp is before the epoch.
+    #[cfg(feature = "std")]
+    pub fn to_duration(&self) -> Option<Duration> {
+        if self.secs >= 0 {
+            Some(Duration::new(self.secs as u64, self.nanos))
+        } else {
+            None
+        }
+    }
+}
+
+/// Formats the timestamp as an RFC 3339 date and time in UTC, e.g. `2018-01-02T03:04:05.678901234Z`.
+///
+/// The fractional part is omitted when there are no nanoseconds.
+impl Display for Timestamp {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let days = self.secs.div_euclid(SECS_PER_DAY);
+        let rem = self.secs.rem_euclid(SECS_PER_DAY);
+
+        // Converts days since the epoch to a proleptic Gregorian civil date, see
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days as i128 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year, month, day, rem / 3600, rem / 60 % 60, rem % 60)?;
+        if self.nanos != 0 {
+            write!(f, ".{:09}", self.nanos)?;
+        }
+        f.write_str("Z")
+    }
+}
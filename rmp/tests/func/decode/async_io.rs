@@ -0,0 +1,157 @@
+use std::io::ErrorKind;
+
+use futures_executor::block_on;
+
+use crate::msgpack::decode::*;
+use crate::msgpack::Marker;
+
+macro_rules! async_tests {
+    ($name:ident) => {
+        mod $name {
+            use super::*;
+            use crate::msgpack::decode::$name::*;
+
+            #[test]
+            fn read_scalars() {
+                block_on(async {
+                    let buf = [
+                        0xc0, 0xc3, 0xcd, 0x01, 0x2c, 0xd0, 0x80,
+                        0xca, 0x3f, 0x80, 0x00, 0x00,
+                        0xcb, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                    ];
+                    let mut rd = &buf[..];
+
+                    read_nil_async(&mut rd).await.unwrap();
+                    assert!(read_bool_async(&mut rd).await.unwrap());
+                    assert_eq!(300u16, read_int_async(&mut rd).await.unwrap());
+                    assert_eq!(-128i64, read_int_async(&mut rd).await.unwrap());
+                    assert_eq!(1.0, read_f32_async(&mut rd).await.unwrap());
+                    assert_eq!(1.0, read_f64_async(&mut rd).await.unwrap());
+                    assert!(rd.is_empty());
+                });
+            }
+
+            #[test]
+            fn read_headers() {
+                block_on(async {
+                    let buf = [
+                        0xd9, 0x20, 0xc5, 0x01, 0x00, 0xdc, 0x00, 0x10, 0xdf, 0x00, 0x01, 0x00, 0x00,
+                        0xc7, 0x03, 0x05,
+                    ];
+                    let mut rd = &buf[..];
+
+                    assert_eq!(32, read_str_len_async(&mut rd).await.unwrap());
+                    assert_eq!(256, read_bin_len_async(&mut rd).await.unwrap());
+                    assert_eq!(16, read_array_len_async(&mut rd).await.unwrap());
+                    assert_eq!(65536, read_map_len_async(&mut rd).await.unwrap());
+                    assert_eq!(ExtMeta { typeid: 5, size: 3 }, read_ext_meta_async(&mut rd).await.unwrap());
+                    assert!(rd.is_empty());
+                });
+            }
+
+            #[test]
+            fn read_type_mismatch_consumes_marker_only() {
+                block_on(async {
+                    let buf = [0xcd, 0x01, 0x2c];
+                    let mut rd = &buf[..];
+
+                    match read_str_len_async(&mut rd).await {
+                        Err(ValueReadError::TypeMismatch(Marker::U16)) => {}
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                    assert_eq!([0x01, 0x2c], rd);
+                });
+            }
+
+            #[test]
+            fn read_int_out_of_range() {
+                block_on(async {
+                    let buf = [0xcd, 0x01, 0x2c];
+
+                    match read_int_async::<u8, _>(&mut &buf[..]).await {
+                        Err(NumValueReadError::OutOfRange) => {}
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                });
+            }
+
+            #[test]
+            fn read_value_bytes_stops_at_value_end() {
+                block_on(async {
+                    // {"a": [1, "bc"], "d": bin(3)}, 42
+                    let value = [
+                        0x82, 0xa1, 0x61, 0x92, 0x01, 0xa2, 0x62, 0x63,
+                        0xa1, 0x64, 0xc4, 0x03, 0x00, 0x01, 0x02,
+                    ];
+                    let mut input = value.to_vec();
+                    input.push(0x2a);
+                    let mut rd = &input[..];
+
+                    let mut buf = vec![0xff];
+                    assert_eq!(value.len(), read_value_bytes_async(&mut rd, &mut buf).await.unwrap());
+                    assert_eq!(0xff, buf[0]);
+                    assert_eq!(value, buf[1..]);
+                    assert_eq!([0x2a], rd);
+                });
+            }
+
+            #[test]
+            fn read_value_bytes_large_data() {
+                block_on(async {
+                    let mut input = vec![0xc6, 0x00, 0x01, 0x00, 0x00];
+                    input.extend((0..65536).map(|i| i as u8));
+
+                    let mut buf = Vec::new();
+                    assert_eq!(input.len(), read_value_bytes_async(&mut &input[..], &mut buf).await.unwrap());
+                    assert_eq!(input, buf);
+                });
+            }
+
+            #[test]
+            fn read_value_bytes_eof() {
+                block_on(async {
+                    let mut buf = Vec::new();
+                    match read_value_bytes_async(&mut &[][..], &mut buf).await {
+                        Err(ReaderError::InvalidMarkerRead(err)) => assert_eq!(ErrorKind::UnexpectedEof, err.kind()),
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+
+                    // A string claiming 4 GiB of data.
+                    let mut buf = Vec::new();
+                    match read_value_bytes_async(&mut &[0xdb, 0xff, 0xff, 0xff, 0xff, 0x61][..], &mut buf).await {
+                        Err(ReaderError::InvalidDataRead(err)) => assert_eq!(ErrorKind::UnexpectedEof, err.kind()),
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                    assert!(buf.len() < 16);
+                });
+            }
+
+            #[test]
+            fn futures_are_send() {
+                fn assert_send<T: Send>(_: T) {}
+
+                let mut rd = &[0xc0][..];
+                let mut buf = Vec::new();
+                assert_send(read_value_bytes_async(&mut rd, &mut buf));
+                assert_send(read_int_async::<u8, _>(&mut rd));
+            }
+
+            #[test]
+            fn read_value_bytes_reserved() {
+                block_on(async {
+                    let mut buf = Vec::new();
+                    match read_value_bytes_async(&mut &[0x91, 0xc1][..], &mut buf).await {
+                        Err(ReaderError::TypeMismatch(Marker::Reserved)) => {}
+                        res => panic!("unexpected result: {:?}", res),
+                    }
+                });
+            }
+        }
+    };
+}
+
+#[cfg(feature = "tokio")]
+async_tests!(tokio);
+
+#[cfg(feature = "futures")]
+async_tests!(futures);
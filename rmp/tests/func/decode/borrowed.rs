@@ -0,0 +1,97 @@
+use crate::msgpack::decode::bytes::BytesReadError;
+use crate::msgpack::decode::*;
+use crate::msgpack::encode;
+use crate::msgpack::Marker;
+
+#[test]
+fn pass_read_bin_ref() {
+    let mut buf = Vec::new();
+    encode::write_bin(&mut buf, &[0x2a; 300]).unwrap();
+    encode::write_nil(&mut buf).unwrap();
+    let mut rd = Bytes::new(&buf);
+
+    let data = read_bin_ref(&mut rd).unwrap();
+    assert_eq!(&[0x2a; 300][..], data);
+    // The data is borrowed from the input.
+    assert_eq!(buf[3..].as_ptr(), data.as_ptr());
+    assert_eq!(303, rd.position());
+}
+
+#[test]
+fn fail_read_bin_ref_eof() {
+    let mut rd = Bytes::new(&[0xc4, 0x03, 0x01, 0x02]);
+
+    match read_bin_ref(&mut rd) {
+        Err(ValueReadError::InvalidDataRead(BytesReadError::InsufficientBytes { expected: 3, actual: 2, .. })) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_read_bin_ref_type_mismatch() {
+    let mut rd = Bytes::new(&[0xa1, 0x61]);
+
+    match read_bin_ref(&mut rd) {
+        Err(ValueReadError::TypeMismatch(Marker::FixStr(1))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_read_ext_ref() {
+    let mut rd = Bytes::new(&[0xc7, 0x03, 0xff, 0x01, 0x02, 0x03, 0xd4, 0x01, 0x00]);
+
+    assert_eq!((-1, &[0x01, 0x02, 0x03][..]), read_ext_ref(&mut rd).unwrap());
+    assert_eq!((1, &[0x00][..]), read_ext_ref(&mut rd).unwrap());
+    assert!(rd.remaining_slice().is_empty());
+}
+
+#[test]
+fn pass_read_array_ref_nested() {
+    // [[1, 2], {"a": "b"}, bin 0x00], 42
+    let buf = [0x93, 0x92, 0x01, 0x02, 0x81, 0xa1, 0x61, 0xa1, 0x62, 0xc4, 0x01, 0x00, 0x2a];
+    let mut rd = Bytes::new(&buf);
+
+    let mut array = read_array_ref(&mut rd).unwrap();
+    assert_eq!([0x2a], rd.remaining_slice());
+    assert_eq!(3, array.len());
+
+    let mut inner = read_array_ref(&mut Bytes::new(array.next().unwrap())).unwrap();
+    assert_eq!(Some(&[0x01][..]), inner.next());
+    assert_eq!(Some(&[0x02][..]), inner.next());
+    assert_eq!(None, inner.next());
+
+    let mut map = read_map_ref(&mut Bytes::new(array.next().unwrap())).unwrap();
+    assert_eq!(1, map.len());
+    assert_eq!(Some((&[0xa1, 0x61][..], &[0xa1, 0x62][..])), map.next());
+    assert_eq!(None, map.next());
+
+    assert_eq!(&[0xc4, 0x01, 0x00], array.remaining_slice());
+    assert_eq!(&[0x00], read_bin_ref(&mut Bytes::new(array.next().unwrap())).unwrap());
+    assert_eq!(0, array.len());
+    assert_eq!(None, array.next());
+}
+
+#[test]
+fn fail_read_array_ref_truncated() {
+    // [1, "ab"] missing its last byte.
+    let buf = [0x92, 0x01, 0xa2, 0x61];
+    let mut rd = Bytes::new(&buf);
+
+    match read_array_ref(&mut rd) {
+        Err(SkipValueError::InvalidDataRead(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Only the header was consumed.
+    assert_eq!(1, rd.position());
+}
+
+#[test]
+fn fail_read_map_ref_type_mismatch() {
+    let mut rd = Bytes::new(&[0x90]);
+
+    match read_map_ref(&mut rd) {
+        Err(SkipValueError::TypeMismatch(Marker::FixArray(0))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
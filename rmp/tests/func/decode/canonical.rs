@@ -0,0 +1,84 @@
+use crate::msgpack::decode::*;
+
+#[test]
+fn canonical_scalars() {
+    assert!(is_canonical(&[0xc0]));
+    assert!(is_canonical(&[0x7f]));
+    assert!(is_canonical(&[0xcc, 0x80]));
+    assert!(is_canonical(&[0xe0]));
+    assert!(is_canonical(&[0xd0, 0xdf]));
+    assert!(is_canonical(&[0xca, 0x3f, 0x00, 0x00, 0x00]));
+    assert!(is_canonical(&[0xcb, 0x3f, 0xb9, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]));
+    assert!(is_canonical(&[0xa1, 0x61]));
+    assert!(is_canonical(&[0xc4, 0x00]));
+    assert!(is_canonical(&[0xd4, 0x01, 0x00]));
+    assert!(is_canonical(&[0xc7, 0x03, 0x01, 0x00, 0x00, 0x00]));
+}
+
+#[test]
+fn non_canonical_integers() {
+    // 127 as u8.
+    assert!(!is_canonical(&[0xcc, 0x7f]));
+    // 255 as u16.
+    assert!(!is_canonical(&[0xcd, 0x00, 0xff]));
+    // 1 as i8.
+    assert!(!is_canonical(&[0xd0, 0x01]));
+    // -1 as i8.
+    assert!(!is_canonical(&[0xd0, 0xff]));
+    // -128 as i16.
+    assert!(!is_canonical(&[0xd1, 0xff, 0x80]));
+}
+
+#[test]
+fn non_canonical_floats() {
+    // 0.5 as f64.
+    assert!(!is_canonical(&[0xcb, 0x3f, 0xe0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+    // NaN as f64.
+    assert!(!is_canonical(&[0xcb, 0x7f, 0xf8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+    // Signaling NaN as f32.
+    assert!(!is_canonical(&[0xca, 0x7f, 0x80, 0x00, 0x01]));
+    assert!(is_canonical(&[0xca, 0x7f, 0xc0, 0x00, 0x00]));
+}
+
+#[test]
+fn non_canonical_headers() {
+    assert!(!is_canonical(&[0xd9, 0x01, 0x61]));
+    assert!(!is_canonical(&[0xc5, 0x00, 0x00]));
+    assert!(!is_canonical(&[0xdc, 0x00, 0x00]));
+    assert!(!is_canonical(&[0xde, 0x00, 0x00]));
+    assert!(!is_canonical(&[0xc7, 0x01, 0x01, 0x00]));
+}
+
+#[test]
+fn canonical_map_key_order() {
+    // {1: nil, "a": nil, "bc": nil}
+    assert!(is_canonical(&[0x83, 0x01, 0xc0, 0xa1, 0x61, 0xc0, 0xa2, 0x62, 0x63, 0xc0]));
+    // {"bc": nil, "a": nil}, shorter strings sort first by their header.
+    assert!(!is_canonical(&[0x82, 0xa2, 0x62, 0x63, 0xc0, 0xa1, 0x61, 0xc0]));
+    // {"a": nil, "a": nil}
+    assert!(!is_canonical(&[0x82, 0xa1, 0x61, 0xc0, 0xa1, 0x61, 0xc0]));
+}
+
+#[test]
+fn canonical_nested_maps() {
+    // {{}: [1], []: {"a": 1, "b": 2}}
+    let buf = [0x82, 0x80, 0x91, 0x01, 0x90, 0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0x02];
+    assert!(is_canonical(&buf));
+
+    // {[]: {"a": 1, "b": 2}, {}: nil}
+    let buf = [0x82, 0x90, 0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0x02, 0x80, 0xc0];
+    assert!(!is_canonical(&buf));
+
+    // {[]: {"b": 2, "a": 1}}, the inner map is not sorted.
+    let buf = [0x81, 0x90, 0x82, 0xa1, 0x62, 0x02, 0xa1, 0x61, 0x01];
+    assert!(!is_canonical(&buf));
+}
+
+#[test]
+fn non_canonical_malformed() {
+    assert!(!is_canonical(&[]));
+    assert!(!is_canonical(&[0xc1]));
+    assert!(!is_canonical(&[0x92, 0x01]));
+    assert!(!is_canonical(&[0xa2, 0x61]));
+    assert!(!is_canonical(&[0xc0, 0xc0]));
+}
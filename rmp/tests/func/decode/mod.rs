@@ -1,13 +1,22 @@
 mod array;
+#[cfg(any(feature = "tokio", feature = "futures"))]
+mod async_io;
 mod bin;
+mod borrowed;
 mod bool;
+mod canonical;
 mod ext;
 mod float;
 mod map;
 mod null;
+mod push;
+mod reader;
 mod sint;
+mod skip;
 mod string;
+mod timestamp;
 mod uint;
+mod validate;
 
 #[cfg(feature = "std")]
 pub type Cursor<'a> = std::io::Cursor<&'a [u8]>;
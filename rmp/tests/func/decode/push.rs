@@ -0,0 +1,138 @@
+use super::Cursor;
+
+use crate::msgpack::decode::*;
+use crate::msgpack::Marker;
+
+// [{"key": [1, -300, 1.5]}, <bin 3>, <ext 5 len 2>, nil, 4294967296]
+const MESSAGE: &[u8] = &[
+    0x95,
+    0x81, 0xa3, 0x6b, 0x65, 0x79, 0x93, 0x01, 0xd1, 0xfe, 0xd4, 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0,
+    0xc4, 0x03, 0x01, 0x02, 0x03,
+    0xd5, 0x05, 0xaa, 0xbb,
+    0xc0,
+    0xcf, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Event(Event),
+    Data(Vec<u8>),
+    Value,
+}
+
+/// Feeds the input in chunks of the given size, merging adjacent data chunks.
+fn feed_in_chunks(buf: &[u8], chunk_len: usize) -> Vec<Step> {
+    let mut dec = PushDecoder::new();
+    let mut steps = Vec::new();
+    let mut available = 0;
+    let mut pos = 0;
+
+    loop {
+        let (consumed, status) = dec.feed(&buf[pos..available]).unwrap();
+        pos += consumed;
+
+        match status {
+            PushStatus::NeedMore(n) => {
+                assert!(n > 0);
+                if available == buf.len() {
+                    return steps;
+                }
+                available = core::cmp::min(available + chunk_len, buf.len());
+            }
+            PushStatus::Event(event) => steps.push(Step::Event(event)),
+            PushStatus::Data(data) => {
+                if let Some(Step::Data(prev)) = steps.last_mut() {
+                    prev.extend_from_slice(data);
+                } else {
+                    steps.push(Step::Data(data.to_vec()));
+                }
+            }
+            PushStatus::Value => steps.push(Step::Value),
+        }
+    }
+}
+
+fn read_with_reader(buf: &[u8]) -> Vec<Step> {
+    let mut rd = Reader::new(Cursor::new(buf));
+    let mut steps = Vec::new();
+
+    loop {
+        let event = rd.next_event().unwrap();
+        steps.push(Step::Event(event));
+
+        if rd.data_left() > 0 {
+            let mut data = vec![0; rd.data_left() as usize];
+            rd.read_data(&mut data).unwrap();
+            steps.push(Step::Data(data));
+        }
+        if rd.depth() == 0 {
+            steps.push(Step::Value);
+            return steps;
+        }
+    }
+}
+
+#[test]
+fn feed_matches_reader_for_any_chunk_size() {
+    let expected = read_with_reader(MESSAGE);
+
+    for chunk_len in 1..=MESSAGE.len() {
+        assert_eq!(expected, feed_in_chunks(MESSAGE, chunk_len), "chunk length {}", chunk_len);
+    }
+}
+
+#[test]
+fn feed_reports_missing_header_bytes() {
+    let mut dec = PushDecoder::new();
+
+    assert_eq!((0, PushStatus::NeedMore(1)), dec.feed(&[]).unwrap());
+    assert_eq!((1, PushStatus::NeedMore(4)), dec.feed(&[0xce]).unwrap());
+    assert!(!dec.is_idle());
+    assert_eq!((3, PushStatus::NeedMore(1)), dec.feed(&[0x00, 0x01, 0x00]).unwrap());
+    assert_eq!((1, PushStatus::Event(Event::UInt(65536))), dec.feed(&[0x00, 0xc0]).unwrap());
+    assert_eq!((0, PushStatus::Value), dec.feed(&[0xc0]).unwrap());
+    assert!(dec.is_idle());
+    assert_eq!((1, PushStatus::Event(Event::Nil)), dec.feed(&[0xc0]).unwrap());
+}
+
+#[test]
+fn feed_reports_missing_data_bytes() {
+    let mut dec = PushDecoder::new();
+
+    assert_eq!((2, PushStatus::Event(Event::Bin(3))), dec.feed(&[0xc4, 0x03]).unwrap());
+    assert_eq!((0, PushStatus::NeedMore(3)), dec.feed(&[]).unwrap());
+    assert_eq!((1, PushStatus::Data(&[0x01][..])), dec.feed(&[0x01]).unwrap());
+    assert_eq!((0, PushStatus::NeedMore(2)), dec.feed(&[]).unwrap());
+    assert_eq!((2, PushStatus::Data(&[0x02, 0x03][..])), dec.feed(&[0x02, 0x03, 0xc0]).unwrap());
+    assert_eq!((0, PushStatus::Value), dec.feed(&[0xc0]).unwrap());
+}
+
+#[test]
+fn feed_empty_containers() {
+    let mut dec = PushDecoder::new();
+
+    assert_eq!((1, PushStatus::Event(Event::MapStart(0))), dec.feed(&[0x80, 0x90]).unwrap());
+    assert_eq!((0, PushStatus::Event(Event::MapEnd)), dec.feed(&[0x90]).unwrap());
+    assert_eq!((0, PushStatus::Value), dec.feed(&[0x90]).unwrap());
+    assert_eq!((1, PushStatus::Event(Event::ArrayStart(0))), dec.feed(&[0x90]).unwrap());
+    assert_eq!((0, PushStatus::Event(Event::ArrayEnd)), dec.feed(&[]).unwrap());
+    assert_eq!((0, PushStatus::Value), dec.feed(&[]).unwrap());
+    assert_eq!((0, PushStatus::NeedMore(1)), dec.feed(&[]).unwrap());
+}
+
+#[test]
+fn fail_feed_reserved() {
+    let mut dec = PushDecoder::new();
+
+    assert_eq!(Err(PushDecodeError::TypeMismatch(Marker::Reserved)), dec.feed(&[0xc1]));
+}
+
+#[test]
+fn fail_feed_depth_limit() {
+    let mut dec = PushDecoder::new();
+    dec.set_max_depth(2);
+
+    dec.feed(&[0x91]).unwrap();
+    dec.feed(&[0x91]).unwrap();
+    assert_eq!(Err(PushDecodeError::DepthLimitExceeded), dec.feed(&[0x91]));
+}
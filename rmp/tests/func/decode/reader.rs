@@ -0,0 +1,168 @@
+use super::Cursor;
+
+use crate::msgpack::decode::*;
+use crate::msgpack::Marker;
+
+fn read_all(buf: &[u8]) -> Vec<Event> {
+    let mut rd = Reader::new(Cursor::new(buf));
+    let mut events = Vec::new();
+    loop {
+        events.push(rd.next_event().unwrap());
+        if rd.depth() == 0 {
+            return events;
+        }
+    }
+}
+
+#[test]
+fn read_scalar_events() {
+    let buf: &[u8] = &[
+        0xc0,
+        0xc2,
+        0xc3,
+        0x2a,
+        0xe0,
+        0xcc, 0xff,
+        0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xd0, 0x80,
+        0xd3, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xca, 0x3f, 0x80, 0x00, 0x00,
+        0xcb, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let mut rd = Reader::new(Cursor::new(buf));
+
+    assert_eq!(Event::Nil, rd.next_event().unwrap());
+    assert_eq!(Event::Bool(false), rd.next_event().unwrap());
+    assert_eq!(Event::Bool(true), rd.next_event().unwrap());
+    assert_eq!(Event::UInt(42), rd.next_event().unwrap());
+    assert_eq!(Event::Int(-32), rd.next_event().unwrap());
+    assert_eq!(Event::UInt(255), rd.next_event().unwrap());
+    assert_eq!(Event::UInt(u64::MAX), rd.next_event().unwrap());
+    assert_eq!(Event::Int(-128), rd.next_event().unwrap());
+    assert_eq!(Event::Int(i64::MIN), rd.next_event().unwrap());
+    assert_eq!(Event::F32(1.0), rd.next_event().unwrap());
+    assert_eq!(Event::F64(1.0), rd.next_event().unwrap());
+    assert_eq!(0, rd.depth());
+    assert_eq!(buf.len() as u64, rd.get_ref().position());
+}
+
+#[test]
+fn read_data_in_chunks() {
+    let buf: &[u8] = &[0xa5, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0xc0];
+    let mut rd = Reader::new(Cursor::new(buf));
+
+    assert_eq!(Event::Str(5), rd.next_event().unwrap());
+
+    let mut chunk = [0; 2];
+    assert_eq!(2, rd.read_data(&mut chunk).unwrap());
+    assert_eq!(b"he", &chunk);
+    assert_eq!(2, rd.read_data(&mut chunk).unwrap());
+    assert_eq!(b"ll", &chunk);
+    assert_eq!(1, rd.read_data(&mut chunk).unwrap());
+    assert_eq!(b'o', chunk[0]);
+    assert_eq!(0, rd.read_data(&mut chunk).unwrap());
+    assert_eq!(0, rd.data_left());
+
+    assert_eq!(Event::Nil, rd.next_event().unwrap());
+}
+
+#[test]
+fn skip_unread_data() {
+    let buf: &[u8] = &[
+        0xc4, 0x02, 0x00, 0x00,
+        0xd5, 0x05, 0x00, 0x00,
+        0xc9, 0x00, 0x00, 0x00, 0x01, 0xff, 0x00,
+        0xc0,
+    ];
+    let mut rd = Reader::new(Cursor::new(buf));
+
+    assert_eq!(Event::Bin(2), rd.next_event().unwrap());
+    assert_eq!(Event::Ext { ty: 5, len: 2 }, rd.next_event().unwrap());
+    let mut byte = [0; 1];
+    assert_eq!(1, rd.read_data(&mut byte).unwrap());
+    assert_eq!(1, rd.data_left());
+    assert_eq!(Event::Ext { ty: -1, len: 1 }, rd.next_event().unwrap());
+    assert_eq!(Event::Nil, rd.next_event().unwrap());
+}
+
+#[test]
+fn read_nested_events() {
+    // [{"a": [1, []]}, {}, nil]
+    let buf: &[u8] = &[0x93, 0x81, 0xa1, 0x61, 0x92, 0x01, 0x90, 0x80, 0xc0];
+
+    assert_eq!(vec![
+        Event::ArrayStart(3),
+        Event::MapStart(1),
+        Event::Str(1),
+        Event::ArrayStart(2),
+        Event::UInt(1),
+        Event::ArrayStart(0),
+        Event::ArrayEnd,
+        Event::ArrayEnd,
+        Event::MapEnd,
+        Event::MapStart(0),
+        Event::MapEnd,
+        Event::Nil,
+        Event::ArrayEnd,
+    ], read_all(buf));
+}
+
+#[test]
+fn read_consecutive_values() {
+    let buf: &[u8] = &[0x91, 0x01, 0x90, 0x02];
+    let mut rd = Reader::new(Cursor::new(buf));
+
+    assert_eq!(Event::ArrayStart(1), rd.next_event().unwrap());
+    assert_eq!(1, rd.depth());
+    assert_eq!(Event::UInt(1), rd.next_event().unwrap());
+    assert_eq!(Event::ArrayEnd, rd.next_event().unwrap());
+    assert_eq!(0, rd.depth());
+    assert_eq!(Event::ArrayStart(0), rd.next_event().unwrap());
+    assert_eq!(Event::ArrayEnd, rd.next_event().unwrap());
+    assert_eq!(Event::UInt(2), rd.next_event().unwrap());
+    assert_eq!(0, rd.depth());
+}
+
+#[test]
+fn fail_depth_limit() {
+    let buf: &[u8] = &[0x91, 0x91, 0x91, 0x90];
+    let mut rd = Reader::new(Cursor::new(buf));
+    rd.set_max_depth(3);
+
+    for _ in 0..3 {
+        rd.next_event().unwrap();
+    }
+
+    match rd.next_event() {
+        Err(ReaderError::DepthLimitExceeded) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_reserved() {
+    let mut rd = Reader::new(Cursor::new(&[0xc1][..]));
+
+    match rd.next_event() {
+        Err(ReaderError::TypeMismatch(Marker::Reserved)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_truncated() {
+    let mut rd = Reader::new(Cursor::new(&[0xcd, 0x01][..]));
+
+    match rd.next_event() {
+        Err(ReaderError::InvalidDataRead(..)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut rd = Reader::new(Cursor::new(&[0xa3, 0x61][..]));
+
+    assert_eq!(Event::Str(3), rd.next_event().unwrap());
+    match rd.next_event() {
+        Err(ReaderError::InvalidDataRead(..)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,162 @@
+use super::Cursor;
+
+use crate::msgpack::decode::*;
+use crate::msgpack::Marker;
+
+#[test]
+fn skip_scalars() {
+    let buf: &[u8] = &[
+        0xc0,
+        0xc3,
+        0x2a,
+        0xe0,
+        0xcc, 0xff,
+        0xd1, 0x80, 0x00,
+        0xca, 0x00, 0x00, 0x00, 0x00,
+        0xcb, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xd3, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let mut cur = Cursor::new(buf);
+
+    for pos in [1, 2, 3, 4, 6, 9, 14, 23, 32] {
+        skip_value(&mut cur).unwrap();
+        assert_eq!(pos, cur.position());
+    }
+}
+
+#[test]
+fn skip_str_and_bin() {
+    let buf: &[u8] = &[
+        0xa3, 0x61, 0x62, 0x63,
+        0xd9, 0x02, 0x61, 0x62,
+        0xda, 0x00, 0x01, 0x61,
+        0xdb, 0x00, 0x00, 0x00, 0x00,
+        0xc4, 0x01, 0x00,
+        0xc5, 0x00, 0x02, 0x00, 0x00,
+        0xc6, 0x00, 0x00, 0x00, 0x01, 0x00,
+    ];
+    let mut cur = Cursor::new(buf);
+
+    for pos in [4, 8, 12, 17, 20, 25, 31] {
+        skip_value(&mut cur).unwrap();
+        assert_eq!(pos, cur.position());
+    }
+}
+
+#[test]
+fn skip_ext() {
+    let buf: &[u8] = &[
+        0xd4, 0x01, 0x00,
+        0xd5, 0x01, 0x00, 0x00,
+        0xd6, 0xff, 0x00, 0x00, 0x00, 0x00,
+        0xd8, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xc7, 0x02, 0x01, 0x00, 0x00,
+        0xc8, 0x00, 0x01, 0x01, 0x00,
+        0xc9, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
+    ];
+    let mut cur = Cursor::new(buf);
+
+    for pos in [3, 7, 13, 31, 36, 41, 48] {
+        skip_value(&mut cur).unwrap();
+        assert_eq!(pos, cur.position());
+    }
+}
+
+#[test]
+fn skip_nested_containers() {
+    // [{"a": [1, [], {}]}, {nil: [[2]]}], 42
+    let buf: &[u8] = &[
+        0x92,
+        0x81, 0xa1, 0x61, 0x93, 0x01, 0x90, 0x80,
+        0xde, 0x00, 0x01, 0xc0, 0xdc, 0x00, 0x01, 0xdd, 0x00, 0x00, 0x00, 0x01, 0x02,
+        0x2a,
+    ];
+    let mut cur = Cursor::new(buf);
+
+    skip_value(&mut cur).unwrap();
+    assert_eq!(21, cur.position());
+}
+
+#[test]
+fn skip_with_max_depth() {
+    // [[{}], [[]]]
+    let buf: &[u8] = &[0x92, 0x91, 0x80, 0x91, 0x90];
+
+    skip_value_with_max_depth(&mut Cursor::new(buf), 3).unwrap();
+
+    match skip_value_with_max_depth(&mut Cursor::new(buf), 2) {
+        Err(SkipValueError::DepthLimitExceeded) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    skip_value_with_max_depth(&mut Cursor::new(&[0x2a][..]), 0).unwrap();
+}
+
+#[test]
+fn skip_deeply_nested_exceeds_default_depth() {
+    let buf = [0x91; MAX_SKIP_DEPTH + 1];
+
+    match skip_value(&mut Cursor::new(&buf[..])) {
+        Err(SkipValueError::DepthLimitExceeded) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn skip_nested_containers_around_depth_limits() {
+    for depth in [1, 2, 31, 32, 33, 64, 65, 100, MAX_SKIP_DEPTH] {
+        // [[...[[], 1]..., 1], 1], 42
+        let mut buf = vec![0x92; depth - 1];
+        buf.push(0x90);
+        buf.extend(vec![0x01; depth - 1]);
+        buf.push(0x2a);
+
+        let mut cur = Cursor::new(&buf[..]);
+        skip_value_with_max_depth(&mut cur, depth).unwrap();
+        assert_eq!(2 * depth as u64 - 1, cur.position());
+
+        match skip_value_with_max_depth(&mut Cursor::new(&buf[..]), depth - 1) {
+            Err(SkipValueError::DepthLimitExceeded) => (),
+            other => panic!("unexpected result at depth {}: {:?}", depth, other),
+        }
+    }
+}
+
+#[test]
+fn skip_from_marker() {
+    let buf: &[u8] = &[0x92, 0x01, 0xa1, 0x61, 0x2a];
+    let mut cur = Cursor::new(buf);
+
+    let marker = read_marker(&mut cur).unwrap();
+    skip_value_from_marker(&mut cur, marker, MAX_SKIP_DEPTH).unwrap();
+    assert_eq!(4, cur.position());
+}
+
+#[test]
+fn skip_reserved_fails() {
+    let buf: &[u8] = &[0x91, 0xc1];
+
+    match skip_value(&mut Cursor::new(buf)) {
+        Err(SkipValueError::TypeMismatch(Marker::Reserved)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn skip_truncated_fails() {
+    match skip_value(&mut Cursor::new(&[0xa3, 0x61][..])) {
+        Err(SkipValueError::InvalidDataRead(..)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    match skip_value(&mut Cursor::new(&[0x92, 0x01][..])) {
+        Err(SkipValueError::InvalidMarkerRead(..)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    match skip_value(&mut Cursor::new(&[0xc6, 0xff, 0xff, 0xff, 0xff][..])) {
+        Err(SkipValueError::InvalidDataRead(..)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
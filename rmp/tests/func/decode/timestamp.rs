@@ -0,0 +1,95 @@
+use super::Cursor;
+
+use crate::msgpack::decode::*;
+use crate::msgpack::{Marker, Timestamp};
+
+#[test]
+fn from_timestamp32_read_timestamp() {
+    let buf: &[u8] = &[0xd6, 0xff, 0x5a, 0x4a, 0xf6, 0xa5];
+    let mut cur = Cursor::new(buf);
+
+    assert_eq!(Timestamp::from_secs(1514862245), read_timestamp(&mut cur).unwrap());
+    assert_eq!(6, cur.position());
+}
+
+#[test]
+fn from_timestamp64_read_timestamp() {
+    let buf: &[u8] = &[0xd7, 0xff, 0xa1, 0xdc, 0xd7, 0xc8, 0x5a, 0x4a, 0xf6, 0xa5];
+    let mut cur = Cursor::new(buf);
+
+    assert_eq!(Timestamp::new(1514862245, 678901234).unwrap(), read_timestamp(&mut cur).unwrap());
+    assert_eq!(10, cur.position());
+}
+
+#[test]
+fn from_timestamp96_read_timestamp() {
+    let buf: &[u8] = &[
+        0xc7, 0x0c, 0xff,
+        0x3b, 0x9a, 0xc9, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff
+    ];
+    let mut cur = Cursor::new(buf);
+
+    assert_eq!(Timestamp::new(-1, 999999999).unwrap(), read_timestamp(&mut cur).unwrap());
+    assert_eq!(15, cur.position());
+}
+
+#[test]
+fn from_timestamp64_nanos_out_of_range_read_timestamp() {
+    // 0x3b9aca00 nanoseconds (1_000_000_000) shifted by 34 bits.
+    let buf: &[u8] = &[0xd7, 0xff, 0xee, 0x6b, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let mut cur = Cursor::new(buf);
+
+    match read_timestamp(&mut cur) {
+        Err(TimestampReadError::OutOfRange) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn from_timestamp96_nanos_out_of_range_read_timestamp() {
+    let buf: &[u8] = &[
+        0xc7, 0x0c, 0xff,
+        0x3b, 0x9a, 0xca, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+    let mut cur = Cursor::new(buf);
+
+    match read_timestamp(&mut cur) {
+        Err(TimestampReadError::OutOfRange) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn from_ext_with_wrong_type_read_timestamp() {
+    let buf: &[u8] = &[0xd6, 0x01, 0x00, 0x00, 0x00, 0x00];
+    let mut cur = Cursor::new(buf);
+
+    match read_timestamp(&mut cur) {
+        Err(TimestampReadError::ExtTypeMismatch(1)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn from_ext_with_wrong_length_read_timestamp() {
+    let buf: &[u8] = &[0xd5, 0xff, 0x00, 0x00];
+    let mut cur = Cursor::new(buf);
+
+    match read_timestamp(&mut cur) {
+        Err(TimestampReadError::LengthMismatch(2)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn from_nil_read_timestamp() {
+    let buf: &[u8] = &[0xc0];
+    let mut cur = Cursor::new(buf);
+
+    match read_timestamp(&mut cur) {
+        Err(TimestampReadError::TypeMismatch(Marker::Null)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,80 @@
+use crate::msgpack::decode::*;
+
+#[test]
+fn validate_scalars() {
+    assert_eq!(Ok(1), validate(&[0xc0]));
+    assert_eq!(Ok(1), validate(&[0x2a, 0x2a]));
+    assert_eq!(Ok(9), validate(&[0xcb, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+    assert_eq!(Ok(4), validate(&[0xa3, 0x61, 0x62, 0x63]));
+    assert_eq!(Ok(4), validate(&[0xc4, 0x02, 0x00, 0x00]));
+    assert_eq!(Ok(6), validate(&[0xd6, 0xff, 0x00, 0x00, 0x00, 0x2a]));
+    assert_eq!(Ok(7), validate(&[0xc9, 0x00, 0x00, 0x00, 0x01, 0x05, 0x00]));
+}
+
+#[test]
+fn validate_nested() {
+    // [{"a": [1, []]}, {}, nil]
+    let buf = [0x93, 0x81, 0xa1, 0x61, 0x92, 0x01, 0x90, 0x80, 0xc0, 0xc0];
+
+    assert_eq!(Ok(9), validate(&buf));
+}
+
+#[test]
+fn fail_validate_truncated() {
+    assert_eq!(Err(ValidationError::UnexpectedEof), validate(&[]));
+    assert_eq!(Err(ValidationError::UnexpectedEof), validate(&[0xcd, 0x00]));
+    assert_eq!(Err(ValidationError::UnexpectedEof), validate(&[0xa3, 0x61, 0x62]));
+    assert_eq!(Err(ValidationError::UnexpectedEof), validate(&[0xc6, 0xff, 0xff, 0xff, 0xff]));
+    assert_eq!(Err(ValidationError::UnexpectedEof), validate(&[0x82, 0xc0, 0xc0, 0xc0]));
+}
+
+#[test]
+fn validate_reserved() {
+    assert_eq!(Err(ValidationError::ReservedMarker { offset: 2 }), validate(&[0x92, 0xc0, 0xc1]));
+
+    let opts = ValidationOptions::new().reject_reserved(false);
+    assert_eq!(Ok(3), validate_with_options(&[0x92, 0xc0, 0xc1], &opts));
+}
+
+#[test]
+fn validate_utf8() {
+    let buf = [0x91, 0xa2, 0xc3, 0x28];
+
+    assert_eq!(Ok(4), validate(&buf));
+
+    let opts = ValidationOptions::new().reject_invalid_utf8(true);
+    assert_eq!(Err(ValidationError::InvalidUtf8 { offset: 1 }), validate_with_options(&buf, &opts));
+    assert_eq!(Ok(4), validate_with_options(&[0x91, 0xa2, 0xc3, 0xa9], &opts));
+}
+
+#[test]
+fn validate_trailing_bytes() {
+    let opts = ValidationOptions::new().reject_trailing_bytes(true);
+
+    assert_eq!(Ok(2), validate_with_options(&[0x91, 0xc0], &opts));
+    assert_eq!(Err(ValidationError::TrailingBytes { offset: 2 }), validate_with_options(&[0x91, 0xc0, 0xc0], &opts));
+}
+
+#[test]
+fn validate_max_depth() {
+    let buf = [0x91, 0x91, 0x90];
+
+    assert_eq!(Ok(3), validate_with_options(&buf, &ValidationOptions::new().max_depth(3)));
+    assert_eq!(Err(ValidationError::DepthLimitExceeded { offset: 2 }),
+        validate_with_options(&buf, &ValidationOptions::new().max_depth(2)));
+    assert_eq!(Err(ValidationError::DepthLimitExceeded { offset: 0 }),
+        validate_with_options(&buf, &ValidationOptions::new().max_depth(0)));
+}
+
+#[test]
+fn validate_max_container_len() {
+    let opts = ValidationOptions::new().max_container_len(2);
+
+    assert_eq!(Ok(3), validate_with_options(&[0x92, 0xc0, 0xc0], &opts));
+    assert_eq!(Ok(5), validate_with_options(&[0x82, 0xc0, 0xc0, 0xc0, 0xc0], &opts));
+    assert_eq!(Err(ValidationError::ContainerTooLong { offset: 1, len: 3 }),
+        validate_with_options(&[0x91, 0x83, 0xc0, 0xc0, 0xc0, 0xc0, 0xc0, 0xc0], &opts));
+    // The length is checked before reading any elements.
+    assert_eq!(Err(ValidationError::ContainerTooLong { offset: 0, len: u32::MAX }),
+        validate_with_options(&[0xdd, 0xff, 0xff, 0xff, 0xff], &opts));
+}
@@ -0,0 +1,85 @@
+use crate::msgpack::encode::buffer::SliceWriteError;
+use crate::msgpack::encode::*;
+use crate::msgpack::Marker;
+
+#[test]
+fn pass_slice_writer_written() {
+    let mut buf = [0x00; 8];
+    let mut wr = SliceWriter::new(&mut buf);
+    assert_eq!(0, wr.written());
+    assert_eq!(8, wr.remaining());
+
+    assert_eq!(Marker::FixArray(2), write_array_len(&mut wr, 2).unwrap());
+    write_uint(&mut wr, 300).unwrap();
+    write_nil(&mut wr).unwrap();
+
+    assert_eq!(5, wr.written());
+    assert_eq!(3, wr.remaining());
+    assert_eq!(8, wr.capacity());
+    assert_eq!(&[0x92, 0xcd, 0x01, 0x2c, 0xc0], wr.as_slice());
+    assert_eq!(&[0x92, 0xcd, 0x01, 0x2c, 0xc0], wr.into_slice());
+}
+
+#[test]
+fn pass_slice_writer_exact_fit() {
+    let mut buf = [0x00; 3];
+    let mut wr = SliceWriter::new(&mut buf);
+
+    write_str(&mut wr, "ab").unwrap();
+
+    assert_eq!(0, wr.remaining());
+    assert_eq!([0xa2, 0x61, 0x62], buf);
+}
+
+#[test]
+fn fail_slice_writer_overflow_stores_nothing() {
+    let mut buf = [0x00; 4];
+    let mut wr = SliceWriter::new(&mut buf);
+    write_uint(&mut wr, 1).unwrap();
+
+    match write_u32(&mut wr, 1) {
+        Err(ValueWriteError::InvalidMarkerWrite(..)) => panic!("the marker must fit"),
+        Err(ValueWriteError::InvalidDataWrite(err)) => {
+            assert_eq!(SliceWriteError::InsufficientSpace { expected: 4, actual: 2, position: 2 }, err);
+        }
+        Ok(()) => panic!("the data must not fit"),
+    }
+    // The marker of the failed value was stored, but its data wasn't.
+    assert_eq!(&[0x01, 0xce], wr.as_slice());
+
+    wr.write_u8(0xc0).unwrap();
+    wr.write_u8(0xc0).unwrap();
+    assert!(wr.write_u8(0xc0).is_err());
+    assert_eq!(4, wr.written());
+}
+
+#[test]
+fn pass_slice_writer_rollback() {
+    let mut buf = [0x00; 8];
+    let mut wr = SliceWriter::new(&mut buf);
+    write_str(&mut wr, "ok").unwrap();
+
+    let checkpoint = wr.written();
+    assert!(write_str(&mut wr, "too long").is_err());
+    assert_eq!(4, wr.written());
+
+    wr.rollback(checkpoint);
+    assert_eq!(&[0xa2, 0x6f, 0x6b], wr.as_slice());
+
+    write_bool(&mut wr, true).unwrap();
+    assert_eq!(&[0xa2, 0x6f, 0x6b, 0xc3], wr.as_slice());
+
+    wr.clear();
+    assert_eq!(0, wr.written());
+    assert_eq!(8, wr.remaining());
+}
+
+#[test]
+#[should_panic]
+fn fail_slice_writer_rollback_past_written() {
+    let mut buf = [0x00; 8];
+    let mut wr = SliceWriter::new(&mut buf);
+    write_nil(&mut wr).unwrap();
+
+    wr.rollback(2);
+}
@@ -1,4 +1,5 @@
 use crate::msgpack::encode::*;
+use crate::msgpack::Marker;
 
 #[test]
 fn pass_pack_f32() {
@@ -19,3 +20,33 @@ fn pass_pack_f64() {
 
     assert_eq!([0xcb, 0x7f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], buf);
 }
+
+#[test]
+fn pass_pack_f64_canonical_as_f32() {
+    let mut buf = Vec::new();
+
+    assert_eq!(Marker::F32, write_f64_canonical(&mut buf, -0.0).unwrap());
+    assert_eq!([0xca, 0x80, 0x00, 0x00, 0x00], buf[..]);
+
+    buf.clear();
+    assert_eq!(Marker::F32, write_f64_canonical(&mut buf, f64::INFINITY).unwrap());
+    assert_eq!([0xca, 0x7f, 0x80, 0x00, 0x00], buf[..]);
+}
+
+#[test]
+fn pass_pack_f64_canonical_as_f64() {
+    let mut buf = Vec::new();
+
+    assert_eq!(Marker::F64, write_f64_canonical(&mut buf, 1.0 + f64::EPSILON).unwrap());
+    assert_eq!([0xcb, 0x3f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], buf[..]);
+}
+
+#[test]
+fn pass_pack_canonical_nan() {
+    let mut buf = Vec::new();
+
+    write_f64_canonical(&mut buf, -f64::NAN).unwrap();
+    write_f32_canonical(&mut buf, f32::from_bits(0x7f800001)).unwrap();
+
+    assert_eq!([0xca, 0x7f, 0xc0, 0x00, 0x00, 0xca, 0x7f, 0xc0, 0x00, 0x00], buf[..]);
+}
@@ -1,9 +1,13 @@
 mod array;
 mod bin;
 mod bool;
+mod buffer;
 mod ext;
 mod float;
 mod int;
 mod map;
 mod null;
+mod patch;
+mod size;
 mod string;
+mod timestamp;
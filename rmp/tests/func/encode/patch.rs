@@ -0,0 +1,88 @@
+use crate::msgpack::encode::*;
+use crate::msgpack::Marker;
+
+#[test]
+fn pass_finish_array_fixed() {
+    let mut buf = Vec::new();
+    write_nil(&mut buf).unwrap();
+
+    let header = begin_array_unknown(&mut buf).unwrap();
+    assert_eq!(1, header.position());
+    write_uint(&mut buf, 1).unwrap();
+    write_uint(&mut buf, 2).unwrap();
+
+    assert_eq!(Marker::Array32, finish_array(&mut buf, header, 2));
+    assert_eq!(vec![0xc0, 0xdd, 0x00, 0x00, 0x00, 0x02, 0x01, 0x02], buf);
+}
+
+#[test]
+fn pass_finish_map_fixed() {
+    let mut buf = Vec::new();
+
+    let header = begin_map_unknown(&mut buf).unwrap();
+    write_uint(&mut buf, 1).unwrap();
+    write_nil(&mut buf).unwrap();
+
+    assert_eq!(Marker::Map32, finish_map(&mut buf, header, 1));
+    assert_eq!(vec![0xdf, 0x00, 0x00, 0x00, 0x01, 0x01, 0xc0], buf);
+}
+
+#[test]
+fn pass_finish_minimal_matches_known_len() {
+    for &len in &[0, 15, 16, 65535, 65536] {
+        let mut expected = Vec::new();
+        write_array_len(&mut expected, len).unwrap();
+        write_map_len(&mut expected, len).unwrap();
+        for _ in 0..len {
+            write_bool(&mut expected, true).unwrap();
+        }
+
+        let mut buf = Vec::new();
+        let array = begin_array_unknown(&mut buf).unwrap();
+        let map = begin_map_unknown(&mut buf).unwrap();
+        for _ in 0..len {
+            write_bool(&mut buf, true).unwrap();
+        }
+        // Inner headers must be finished first, as shrinking a header moves everything after it.
+        finish_map_minimal(&mut buf, map, len);
+        finish_array_minimal(&mut buf, array, len);
+
+        assert_eq!(expected, buf, "len = {}", len);
+    }
+}
+
+#[test]
+fn pass_finish_minimal_byte_buf() {
+    let mut buf = ByteBuf::new();
+
+    let header = begin_map_unknown(&mut buf).unwrap();
+    write_str(&mut buf, "a").unwrap();
+    write_uint(&mut buf, 1).unwrap();
+
+    assert_eq!(Marker::FixMap(1), finish_map_minimal(&mut buf, header, 1));
+    assert_eq!(&[0x81, 0xa1, 0x61, 0x01], buf.as_slice());
+}
+
+#[test]
+fn pass_finish_minimal_slice_writer() {
+    let mut buf = [0x00; 8];
+    let mut wr = SliceWriter::new(&mut buf);
+
+    let header = begin_array_unknown(&mut wr).unwrap();
+    write_uint(&mut wr, 300).unwrap();
+
+    assert_eq!(Marker::FixArray(1), finish_array_minimal(&mut wr, header, 1));
+    assert_eq!(4, wr.written());
+    assert_eq!(&[0x91, 0xcd, 0x01, 0x2c], wr.as_slice());
+}
+
+#[test]
+fn fail_begin_array_no_space() {
+    let mut buf = [0x00; 4];
+    let mut wr = SliceWriter::new(&mut buf);
+
+    match begin_array_unknown(&mut wr) {
+        Err(ValueWriteError::InvalidDataWrite(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
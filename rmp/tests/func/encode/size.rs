@@ -0,0 +1,56 @@
+use crate::msgpack::encode::*;
+
+#[test]
+fn pass_size_of_int_matches_written() {
+    let vals = [
+        0, 1, 127, 128, 255, 256, 65535, 65536, 4294967295, 4294967296, u64::MAX,
+    ];
+    for &val in &vals {
+        let mut buf = ByteBuf::new();
+        write_uint(&mut buf, val).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_uint(val), "{}", val);
+    }
+
+    let vals = [
+        i64::MIN, -2147483649, -2147483648, -32769, -32768, -129, -128, -33, -32, -1,
+        0, 127, 128, 65536, i64::MAX,
+    ];
+    for &val in &vals {
+        let mut buf = ByteBuf::new();
+        write_sint(&mut buf, val).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_sint(val), "{}", val);
+    }
+}
+
+#[test]
+fn pass_size_of_header_matches_written() {
+    let lens = [0, 1, 2, 3, 4, 8, 15, 16, 17, 31, 32, 255, 256, 65535, 65536, u32::MAX];
+    for &len in &lens {
+        let mut buf = ByteBuf::new();
+        write_str_len(&mut buf, len).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_str_header(len), "str {}", len);
+
+        let mut buf = ByteBuf::new();
+        write_bin_len(&mut buf, len).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_bin_header(len), "bin {}", len);
+
+        let mut buf = ByteBuf::new();
+        write_array_len(&mut buf, len).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_array_header(len), "array {}", len);
+
+        let mut buf = ByteBuf::new();
+        write_map_len(&mut buf, len).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_map_header(len), "map {}", len);
+
+        let mut buf = ByteBuf::new();
+        write_ext_meta(&mut buf, len, 1).unwrap();
+        assert_eq!(buf.as_slice().len(), size_of_ext_header(len), "ext {}", len);
+    }
+}
+
+#[test]
+fn pass_size_of_is_const() {
+    const FRAME: [u8; size_of_array_header(3) + 3 * size_of_uint(255)] = [0; 7];
+
+    assert_eq!(7, FRAME.len());
+}
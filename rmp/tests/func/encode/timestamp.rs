@@ -0,0 +1,73 @@
+use crate::msgpack::encode::*;
+use crate::msgpack::{Marker, Timestamp};
+
+#[test]
+fn pass_pack_timestamp32() {
+    let mut buf = [0x00; 6];
+
+    assert_eq!(Marker::FixExt4, write_timestamp(&mut &mut buf[..], Timestamp::from_secs(1514862245)).unwrap());
+
+    assert_eq!([0xd6, 0xff, 0x5a, 0x4a, 0xf6, 0xa5], buf);
+}
+
+#[test]
+fn pass_pack_timestamp32_max() {
+    let mut buf = [0x00; 6];
+
+    assert_eq!(Marker::FixExt4, write_timestamp(&mut &mut buf[..], Timestamp::from_secs(u32::MAX as i64)).unwrap());
+
+    assert_eq!([0xd6, 0xff, 0xff, 0xff, 0xff, 0xff], buf);
+}
+
+#[test]
+fn pass_pack_timestamp64_with_nanos() {
+    let mut buf = [0x00; 10];
+    let ts = Timestamp::new(1514862245, 678901234).unwrap();
+
+    assert_eq!(Marker::FixExt8, write_timestamp(&mut &mut buf[..], ts).unwrap());
+
+    assert_eq!([0xd7, 0xff, 0xa1, 0xdc, 0xd7, 0xc8, 0x5a, 0x4a, 0xf6, 0xa5], buf);
+}
+
+#[test]
+fn pass_pack_timestamp64_secs_over_u32() {
+    let mut buf = [0x00; 10];
+    let ts = Timestamp::from_secs(u32::MAX as i64 + 1);
+
+    assert_eq!(Marker::FixExt8, write_timestamp(&mut &mut buf[..], ts).unwrap());
+
+    assert_eq!([0xd7, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00], buf);
+}
+
+#[test]
+fn pass_pack_timestamp96_negative() {
+    let mut buf = [0x00; 15];
+    let ts = Timestamp::new(-1, 999999999).unwrap();
+
+    assert_eq!(Marker::Ext8, write_timestamp(&mut &mut buf[..], ts).unwrap());
+
+    assert_eq!([
+        0xc7, 0x0c, 0xff,
+        0x3b, 0x9a, 0xc9, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff
+    ], buf);
+}
+
+#[test]
+fn pass_pack_timestamp96_secs_over_34_bits() {
+    let mut buf = [0x00; 15];
+    let ts = Timestamp::from_secs(1 << 34);
+
+    assert_eq!(Marker::Ext8, write_timestamp(&mut &mut buf[..], ts).unwrap());
+
+    assert_eq!([
+        0xc7, 0x0c, 0xff,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00
+    ], buf);
+}
+
+#[test]
+fn fail_pack_timestamp_nanos_out_of_range() {
+    assert_eq!(None, Timestamp::new(0, 1_000_000_000));
+}
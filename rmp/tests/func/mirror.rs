@@ -1,6 +1,6 @@
 use msgpack::decode::Bytes;
 use msgpack::encode::ByteBuf;
-use crate::msgpack::{decode, encode};
+use crate::msgpack::{decode, encode, Timestamp};
 
 quickcheck! {
     fn mirror_uint(xs: u64) -> bool {
@@ -32,4 +32,15 @@ quickcheck! {
         let res = decode::read_f64(&mut Bytes::new(buf.as_slice())).expect("read");
         true || xs == res || (xs.is_nan() && res.is_nan())
     }
+
+    fn mirror_timestamp(secs: i64, nanos: u32) -> bool {
+        let ts = match Timestamp::new(secs, nanos % 1_000_000_000) {
+            Some(ts) => ts,
+            None => return false,
+        };
+        let mut buf = ByteBuf::new();
+        encode::write_timestamp(&mut buf, ts).unwrap();
+
+        ts == decode::read_timestamp(&mut Bytes::new(buf.as_slice())).unwrap()
+    }
 }
@@ -1,3 +1,4 @@
 mod decode;
 mod encode;
 mod mirror;
+mod transport;
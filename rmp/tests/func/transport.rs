@@ -0,0 +1,151 @@
+use core::fmt::{self, Display, Formatter};
+
+use crate::msgpack::decode::{self, Bytes, Event, Reader, RmpRead, RmpReadErr};
+use crate::msgpack::encode::{self, RmpWrite, RmpWriteErr};
+
+/// A fixed-capacity ring buffer, standing in for a custom no_std transport.
+struct Ring {
+    buf: [u8; 32],
+    head: usize,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct RingError;
+
+impl Display for RingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ring buffer overflow or underflow")
+    }
+}
+
+impl std::error::Error for RingError {}
+impl RmpReadErr for RingError {}
+impl RmpWriteErr for RingError {}
+
+impl Ring {
+    fn new() -> Self {
+        Ring { buf: [0; 32], head: 0, len: 0 }
+    }
+}
+
+impl RmpRead for Ring {
+    type Error = RingError;
+
+    fn read_exact_buf(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if buf.len() > self.len {
+            return Err(RingError);
+        }
+        for byte in buf {
+            *byte = self.buf[self.head];
+            self.head = (self.head + 1) % self.buf.len();
+            self.len -= 1;
+        }
+        Ok(())
+    }
+
+    fn read_slice_ref(&mut self, len: usize) -> Result<Option<&[u8]>, Self::Error> {
+        if len > self.len {
+            return Err(RingError);
+        }
+        if self.head + len > self.buf.len() {
+            // The data wraps around, so it isn't contiguous.
+            return Ok(None);
+        }
+        let start = self.head;
+        self.head = (self.head + len) % self.buf.len();
+        self.len -= len;
+        Ok(Some(&self.buf[start..start + len]))
+    }
+
+    fn remaining_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl RmpWrite for Ring {
+    type Error = RingError;
+
+    fn write_bytes(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        if buf.len() > self.buf.len() - self.len {
+            return Err(RingError);
+        }
+        for &byte in buf {
+            let tail = (self.head + self.len) % self.buf.len();
+            self.buf[tail] = byte;
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn pass_custom_transport_roundtrip() {
+    let mut ring = Ring::new();
+
+    encode::write_array_len(&mut ring, 3).unwrap();
+    encode::write_uint(&mut ring, 300).unwrap();
+    encode::write_str(&mut ring, "abc").unwrap();
+    encode::write_f64(&mut ring, 0.5).unwrap();
+    assert_eq!(Some(1 + 3 + 4 + 9), ring.remaining_hint());
+
+    assert_eq!(3, decode::read_array_len(&mut ring).unwrap());
+    assert_eq!(300u16, decode::read_int(&mut ring).unwrap());
+    assert_eq!(3, decode::read_str_len(&mut ring).unwrap());
+    let mut buf = [0; 3];
+    ring.read_exact_buf(&mut buf).unwrap();
+    assert_eq!(b"abc", &buf);
+    assert_eq!(0.5, decode::read_f64(&mut ring).unwrap());
+    assert_eq!(Some(0), ring.remaining_hint());
+}
+
+#[test]
+fn fail_custom_transport_errors() {
+    let mut ring = Ring::new();
+
+    match encode::write_str(&mut ring, &"x".repeat(40)) {
+        Err(encode::ValueWriteError::InvalidDataWrite(RingError)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut ring = Ring::new();
+    match decode::read_nil(&mut ring) {
+        Err(decode::ValueReadError::InvalidMarkerRead(RingError)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_reader_data_slice() {
+    let mut ring = Ring::new();
+    // Move the head, so that the second string wraps around.
+    ring.write_bytes(&[0; 26]).unwrap();
+    ring.read_exact_buf(&mut [0; 26]).unwrap();
+
+    encode::write_str(&mut ring, "ab").unwrap();
+    encode::write_str(&mut ring, "cdef").unwrap();
+
+    let mut rd = Reader::new(ring);
+    assert_eq!(Event::Str(2), rd.next_event().unwrap());
+    assert_eq!(Some(&b"ab"[..]), rd.read_data_slice().unwrap());
+    assert_eq!(0, rd.data_left());
+
+    assert_eq!(Event::Str(4), rd.next_event().unwrap());
+    assert_eq!(None, rd.read_data_slice().unwrap());
+    assert_eq!(4, rd.data_left());
+    let mut buf = [0; 4];
+    assert_eq!(4, rd.read_data(&mut buf).unwrap());
+    assert_eq!(b"cdef", &buf);
+}
+
+#[test]
+fn pass_bytes_read_slice() {
+    let mut rd = Bytes::new(&[0x01, 0x02, 0x03]);
+    assert_eq!(Some(3), rd.remaining_hint());
+
+    assert_eq!(Some(&[0x01, 0x02][..]), rd.read_slice_ref(2).unwrap());
+    assert_eq!(2, rd.position());
+    assert_eq!(Some(1), rd.remaining_hint());
+
+    assert!(rd.read_slice_ref(2).is_err());
+}
@@ -0,0 +1,155 @@
+//! Differential tests between `rmpv` and `rmp-serde`, over arbitrary values and corruptions of
+//! their encodings.
+
+#[macro_use]
+extern crate quickcheck;
+extern crate rmp_serde as rmps;
+
+use quickcheck::{Arbitrary, Gen};
+use serde::Deserialize;
+
+use rmpv::decode::{read_value, read_value_ref, read_value_with_limits, DecodeLimits};
+use rmpv::encode::write_value;
+use rmpv::Value;
+
+/// An arbitrary value, nested at most a few levels deep.
+#[derive(Clone, Debug)]
+struct ArbValue(Value);
+
+impl Arbitrary for ArbValue {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ArbValue(arbitrary_value(g, 3))
+    }
+}
+
+fn arbitrary_value(g: &mut Gen, depth: usize) -> Value {
+    let kinds = if depth == 0 { 9 } else { 11 };
+    match u8::arbitrary(g) % kinds {
+        0 => Value::Nil,
+        1 => Value::Boolean(bool::arbitrary(g)),
+        2 => Value::from(u64::arbitrary(g)),
+        3 => Value::from(i64::arbitrary(g)),
+        // NaN never equals itself, which would fail the comparisons for no reason.
+        4 => Value::F32(Some(f32::arbitrary(g)).filter(|v| !v.is_nan()).unwrap_or_default()),
+        5 => Value::F64(Some(f64::arbitrary(g)).filter(|v| !v.is_nan()).unwrap_or_default()),
+        6 => Value::from(String::arbitrary(g)),
+        7 => Value::Binary(Vec::arbitrary(g)),
+        8 => Value::Ext(i8::arbitrary(g), Vec::arbitrary(g)),
+        9 => {
+            let len = usize::arbitrary(g) % 4;
+            Value::Array((0..len).map(|_| arbitrary_value(g, depth - 1)).collect())
+        }
+        _ => {
+            let len = usize::arbitrary(g) % 4;
+            Value::Map((0..len).map(|_| (arbitrary_value(g, depth - 1), arbitrary_value(g, depth - 1))).collect())
+        }
+    }
+}
+
+fn encode(val: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_value(&mut buf, val).unwrap();
+    buf
+}
+
+/// Returns whether all strings nested in the value are valid UTF-8.
+///
+/// `rmp-serde` decodes invalid strings as binary data instead, so such values are not comparable.
+fn is_utf8(val: &Value) -> bool {
+    match *val {
+        Value::String(ref s) => s.is_str(),
+        Value::Array(ref vec) => vec.iter().all(is_utf8),
+        Value::Map(ref vec) => vec.iter().all(|(k, v)| is_utf8(k) && is_utf8(v)),
+        _ => true,
+    }
+}
+
+quickcheck! {
+    fn mirror_value(val: ArbValue) -> bool {
+        let buf = encode(&val.0);
+
+        val.0 == read_value(&mut &buf[..]).unwrap() && val.0 == read_value_ref(&mut &buf[..]).unwrap().to_owned()
+    }
+
+    fn mirror_value_serde(val: ArbValue) -> bool {
+        let buf = rmps::to_vec(&val.0).unwrap();
+
+        val.0 == rmps::from_slice::<Value>(&buf).unwrap()
+    }
+
+    fn encoders_agree(val: ArbValue) -> bool {
+        encode(&val.0) == rmps::to_vec(&val.0).unwrap()
+    }
+
+    fn decoders_agree(val: ArbValue) -> bool {
+        let buf = encode(&val.0);
+
+        read_value(&mut &buf[..]).unwrap() == rmps::from_slice::<Value>(&buf).unwrap()
+    }
+
+    fn decoders_agree_on_corrupted_input(val: ArbValue, patches: Vec<(usize, u8)>) -> bool {
+        let mut buf = encode(&val.0);
+        for (pos, byte) in patches {
+            let len = buf.len();
+            buf[pos % len] = byte;
+        }
+
+        match (read_value(&mut &buf[..]), rmps::from_slice::<Value>(&buf)) {
+            // Compare the encodings rather than the values, since corrupted floats may be NaN.
+            (Ok(lhs), Ok(rhs)) => !is_utf8(&lhs) || encode(&lhs) == encode(&rhs),
+            _ => true,
+        }
+    }
+
+    fn read_value_never_panics(buf: Vec<u8>) -> bool {
+        let _ = read_value(&mut &buf[..]);
+        let _ = read_value_ref(&mut &buf[..]);
+        let _ = rmps::from_slice::<Value>(&buf);
+        true
+    }
+}
+
+/// Encodes the given scalar nested in `depth` levels of arrays and maps, alternating between them.
+fn nested(depth: usize, scalar: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for level in 0..depth {
+        if level % 2 == 0 {
+            buf.push(0x91);
+        } else {
+            // {"k": ...}
+            buf.extend_from_slice(&[0x81, 0xa1, 0x6b]);
+        }
+    }
+    buf.extend_from_slice(scalar);
+    buf
+}
+
+#[test]
+fn depth_limits_agree() {
+    // "a", 42 and an extension.
+    let scalars: &[&[u8]] = &[&[0xa1, 0x61], &[0x2a], &[0xd4, 0x01, 0x00]];
+    let mut cases = Vec::new();
+    for max_depth in 0..6 {
+        for depth in 0..8 {
+            cases.push((depth, max_depth));
+        }
+    }
+    cases.push((64, 64));
+    cases.push((65, 64));
+
+    for scalar in scalars {
+        for &(depth, max_depth) in &cases {
+            let buf = nested(depth, scalar);
+            let limits = DecodeLimits { max_depth, ..DecodeLimits::default() };
+
+            let rmpv = read_value_with_limits(&mut &buf[..], &limits);
+            let mut de = rmps::Deserializer::new(&buf[..]);
+            de.set_limits(limits);
+            let rmps = Value::deserialize(&mut de);
+
+            let accepted = depth <= max_depth;
+            assert_eq!(accepted, rmpv.is_ok(), "rmpv, depth {} of {}: {:?}", depth, max_depth, rmpv);
+            assert_eq!(accepted, rmps.is_ok(), "rmp-serde, depth {} of {}: {:?}", depth, max_depth, rmps);
+        }
+    }
+}
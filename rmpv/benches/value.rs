@@ -224,3 +224,48 @@ fn read_array_1mib(b: &mut Bencher) {
 fn read_array_20mib(b: &mut Bencher) {
     read_large_array(b, 20 * 1024 * 1024);
 }
+
+/// Encodes a map of `len` entries like `"key123": [123, "value"]`.
+fn encode_keyed_map(len: usize) -> Vec<u8> {
+    use rmpv::Value;
+
+    let entries = (0..len)
+        .map(|i| (Value::from(format!("key{}", i)), Value::from(vec![Value::from(i), Value::from("value")])))
+        .collect();
+    let mut buf = Vec::new();
+    rmpv::encode::write_value(&mut buf, &Value::Map(entries)).unwrap();
+    buf
+}
+
+#[bench]
+fn from_keyed_map_lazy_get_last(b: &mut Bencher) {
+    let buf = encode_keyed_map(1000);
+
+    b.iter(|| {
+        let res = rmpv::lazy::DocRef::new(&buf).get("key999").unwrap().unwrap();
+        test::black_box(res);
+    });
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn from_keyed_map_read_value_ref(b: &mut Bencher) {
+    let buf = encode_keyed_map(1000);
+
+    b.iter(|| {
+        let res = read_value_ref(&mut &buf[..]).unwrap();
+        test::black_box(res);
+    });
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn from_keyed_map_read_value(b: &mut Bencher) {
+    let buf = encode_keyed_map(1000);
+
+    b.iter(|| {
+        let res = read_value(&mut &buf[..]).unwrap();
+        test::black_box(res);
+    });
+    b.bytes = buf.len() as u64;
+}
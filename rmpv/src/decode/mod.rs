@@ -2,12 +2,13 @@ use std::error;
 use std::fmt::{self, Display, Formatter};
 use std::io::{self, ErrorKind};
 
-use rmp::decode::{MarkerReadError, ValueReadError};
+use rmp::decode::{MarkerReadError, SkipValueError, ValueReadError};
+pub use rmp::decode::{DecodeLimits, Limit, LocatedError, Path, PathSegment};
 
 pub mod value;
 pub mod value_ref;
 
-pub use self::value::{read_value, read_value_with_max_depth};
+pub use self::value::{read_value, read_value_located, read_value_with_limits, read_value_with_max_depth};
 pub use self::value_ref::{read_value_ref, read_value_ref_with_max_depth};
 
 /// The maximum recursion depth before [`Error::DepthLimitExceeded`] is returned.
@@ -22,6 +23,8 @@ pub enum Error {
     InvalidDataRead(io::Error),
     /// The depth limit [`MAX_DEPTH`] was exceeded.
     DepthLimitExceeded,
+    /// A limit of the [`DecodeLimits`] passed to [`read_value_with_limits`] was exceeded.
+    LimitExceeded(Limit),
 }
 
 fn decrement_depth(depth: usize) -> Result<usize, Error> {
@@ -39,6 +42,7 @@ impl Error {
             Error::InvalidMarkerRead(ref err) => err.kind(),
             Error::InvalidDataRead(ref err) => err.kind(),
             Error::DepthLimitExceeded => ErrorKind::Unsupported,
+            Error::LimitExceeded(..) => ErrorKind::InvalidData,
         }
     }
 }
@@ -49,7 +53,8 @@ impl error::Error for Error {
         match *self {
             Error::InvalidMarkerRead(ref err) => Some(err),
             Error::InvalidDataRead(ref err) => Some(err),
-            Error::DepthLimitExceeded => None,
+            Error::DepthLimitExceeded |
+            Error::LimitExceeded(..) => None,
         }
     }
 }
@@ -67,6 +72,9 @@ impl Display for Error {
             Error::DepthLimitExceeded => {
                 write!(fmt, "depth limit exceeded")
             }
+            Error::LimitExceeded(limit) => {
+                write!(fmt, "{} limit exceeded", limit)
+            }
         }
     }
 }
@@ -91,13 +99,28 @@ impl From<ValueReadError> for Error {
     }
 }
 
+impl From<SkipValueError<io::Error>> for Error {
+    #[cold]
+    fn from(err: SkipValueError<io::Error>) -> Error {
+        match err {
+            SkipValueError::InvalidMarkerRead(err) => Error::InvalidMarkerRead(err),
+            SkipValueError::InvalidDataRead(err) => Error::InvalidDataRead(err),
+            SkipValueError::TypeMismatch(..) => {
+                Error::InvalidMarkerRead(io::Error::new(ErrorKind::Other, "type mismatch"))
+            }
+            SkipValueError::DepthLimitExceeded => Error::DepthLimitExceeded,
+        }
+    }
+}
+
 impl From<Error> for io::Error {
     #[cold]
     fn from(val: Error) -> Self {
         match val {
             Error::InvalidMarkerRead(err) |
             Error::InvalidDataRead(err) => err,
-            Error::DepthLimitExceeded => io::Error::new(val.kind(), val),
+            Error::DepthLimitExceeded |
+            Error::LimitExceeded(..) => io::Error::new(val.kind(), val),
         }
     }
 }
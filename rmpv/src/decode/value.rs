@@ -1,49 +1,76 @@
 use std::cmp::min;
 use std::io::{self, Read};
 
-use rmp::decode::{RmpRead, read_marker};
+use rmp::decode::{RmpRead, read_marker, LocatedError, Path, PathSegment};
 use rmp::Marker;
 
-use super::Error;
+use super::{DecodeLimits, Error, Limit};
 use crate::{Utf8String, Value};
 
 // See https://github.com/3Hren/msgpack-rust/issues/151
 const PREALLOC_MAX: usize = 64 * 1024; // 64 KiB
 
-fn read_array_data<R: Read>(rd: &mut R, mut len: usize, depth: usize) -> Result<Vec<Value>, Error> {
+fn check_len(limits: &DecodeLimits, limit: Limit, len: usize) -> Result<(), Error> {
+    limits.check(limit, len as u64).map_err(Error::LimitExceeded)
+}
+
+fn read_array_data<R: ValueRead>(rd: &mut R, len: usize, depth: usize, limits: &DecodeLimits) -> Result<Vec<Value>, Error> {
     let depth = super::decrement_depth(depth)?;
+    rd.enter()?;
+    check_len(limits, Limit::ArrayLen, len)?;
 
     // Note: Do not preallocate a Vec of size `len`.
     // See https://github.com/3Hren/msgpack-rust/issues/151
     let mut vec = Vec::new();
 
-    while len > 0 {
-        vec.push(read_value_inner(rd, depth)?);
-        len -= 1;
+    for idx in 0..len {
+        match read_value_inner(rd, depth, limits) {
+            Ok(val) => vec.push(val),
+            Err(err) => return Err(rd.unwind(PathSegment::Index(idx as u64), err)),
+        }
     }
 
+    rd.leave();
     Ok(vec)
 }
 
-fn read_map_data<R: Read>(rd: &mut R, mut len: usize, depth: usize) -> Result<Vec<(Value, Value)>, Error> {
+fn read_map_data<R: ValueRead>(rd: &mut R, len: usize, depth: usize, limits: &DecodeLimits) -> Result<Vec<(Value, Value)>, Error> {
     let depth = super::decrement_depth(depth)?;
+    rd.enter()?;
+    check_len(limits, Limit::MapLen, len)?;
 
     // Note: Do not preallocate a Vec of size `len`.
     // See https://github.com/3Hren/msgpack-rust/issues/151
     let mut vec = Vec::new();
 
-    while len > 0 {
-        vec.push((read_value_inner(rd, depth)?, read_value_inner(rd, depth)?));
-        len -= 1;
+    for _ in 0..len {
+        let key = match read_value_inner(rd, depth, limits) {
+            Ok(key) => key,
+            Err(err) => return Err(rd.unwind(PathSegment::Other, err)),
+        };
+        match read_value_inner(rd, depth, limits) {
+            Ok(val) => vec.push((key, val)),
+            Err(err) => return Err(rd.unwind(key_segment(&key), err)),
+        }
     }
 
+    rd.leave();
     Ok(vec)
 }
 
-fn read_str_data<R: Read>(rd: &mut R, len: usize, depth: usize) -> Result<Utf8String, Error> {
+/// Returns the step into the value of a map with the given key.
+fn key_segment(key: &Value) -> PathSegment {
+    match *key {
+        Value::String(ref s) => s.as_str().map_or(PathSegment::Other, |s| PathSegment::Key(s.into())),
+        Value::Integer(n) => n.as_u64().map_or(PathSegment::Other, PathSegment::Index),
+        _ => PathSegment::Other,
+    }
+}
+
+fn read_str_data<R: ValueRead>(rd: &mut R, len: usize, depth: usize, limits: &DecodeLimits) -> Result<Utf8String, Error> {
     let depth = super::decrement_depth(depth)?;
 
-    match String::from_utf8(read_bin_data(rd, len, depth)?) {
+    match String::from_utf8(read_bin_data(rd, len, depth, limits, Limit::StrLen)?) {
         Ok(s) => Ok(Utf8String::from(s)),
         Err(err) => {
             let e = err.utf8_error();
@@ -55,8 +82,9 @@ fn read_str_data<R: Read>(rd: &mut R, len: usize, depth: usize) -> Result<Utf8St
     }
 }
 
-fn read_bin_data<R: Read>(rd: &mut R, len: usize, depth: usize) -> Result<Vec<u8>, Error> {
+fn read_bin_data<R: ValueRead>(rd: &mut R, len: usize, depth: usize, limits: &DecodeLimits, limit: Limit) -> Result<Vec<u8>, Error> {
     let _depth = super::decrement_depth(depth)?;
+    check_len(limits, limit, len)?;
 
     let mut buf = Vec::with_capacity(min(len, PREALLOC_MAX));
     let bytes_read = rd.take(len as u64).read_to_end(&mut buf).map_err(Error::InvalidDataRead)?;
@@ -70,17 +98,18 @@ fn read_bin_data<R: Read>(rd: &mut R, len: usize, depth: usize) -> Result<Vec<u8
     Ok(buf)
 }
 
-fn read_ext_body<R: Read>(rd: &mut R, len: usize, depth: usize) -> Result<(i8, Vec<u8>), Error> {
+fn read_ext_body<R: ValueRead>(rd: &mut R, len: usize, depth: usize, limits: &DecodeLimits) -> Result<(i8, Vec<u8>), Error> {
     let depth = super::decrement_depth(depth)?;
 
     let ty = rd.read_data_i8()?;
-    let vec = read_bin_data(rd, len, depth)?;
+    let vec = read_bin_data(rd, len, depth, limits, Limit::ExtLen)?;
 
     Ok((ty, vec))
 }
 
-fn read_value_inner<R>(rd: &mut R, depth: usize) -> Result<Value, Error> where R: Read {
+fn read_value_inner<R>(rd: &mut R, depth: usize, limits: &DecodeLimits) -> Result<Value, Error> where R: ValueRead {
     let depth = super::decrement_depth(depth)?;
+    rd.mark();
     let val = match read_marker(rd)? {
         Marker::Null => Value::Nil,
         Marker::True => Value::Boolean(true),
@@ -98,105 +127,105 @@ fn read_value_inner<R>(rd: &mut R, depth: usize) -> Result<Value, Error> where R
         Marker::F32 => Value::F32(rd.read_data_f32()?),
         Marker::F64 => Value::F64(rd.read_data_f64()?),
         Marker::FixStr(len) => {
-            let res = read_str_data(rd, len as usize, depth)?;
+            let res = read_str_data(rd, len as usize, depth, limits)?;
             Value::String(res)
         }
         Marker::Str8 => {
             let len = rd.read_data_u8()?;
-            let res = read_str_data(rd, len as usize, depth)?;
+            let res = read_str_data(rd, len as usize, depth, limits)?;
             Value::String(res)
         }
         Marker::Str16 => {
             let len = rd.read_data_u16()?;
-            let res = read_str_data(rd, len as usize, depth)?;
+            let res = read_str_data(rd, len as usize, depth, limits)?;
             Value::String(res)
         }
         Marker::Str32 => {
             let len = rd.read_data_u32()?;
-            let res = read_str_data(rd, len as usize, depth)?;
+            let res = read_str_data(rd, len as usize, depth, limits)?;
             Value::String(res)
         }
         Marker::FixArray(len) => {
-            let vec = read_array_data(rd, len as usize, depth)?;
+            let vec = read_array_data(rd, len as usize, depth, limits)?;
             Value::Array(vec)
         }
         Marker::Array16 => {
             let len = rd.read_data_u16()?;
-            let vec = read_array_data(rd, len as usize, depth)?;
+            let vec = read_array_data(rd, len as usize, depth, limits)?;
             Value::Array(vec)
         }
         Marker::Array32 => {
             let len = rd.read_data_u32()?;
-            let vec = read_array_data(rd, len as usize, depth)?;
+            let vec = read_array_data(rd, len as usize, depth, limits)?;
             Value::Array(vec)
         }
         Marker::FixMap(len) => {
-            let map = read_map_data(rd, len as usize, depth)?;
+            let map = read_map_data(rd, len as usize, depth, limits)?;
             Value::Map(map)
         }
         Marker::Map16 => {
             let len = rd.read_data_u16()?;
-            let map = read_map_data(rd, len as usize, depth)?;
+            let map = read_map_data(rd, len as usize, depth, limits)?;
             Value::Map(map)
         }
         Marker::Map32 => {
             let len = rd.read_data_u32()?;
-            let map = read_map_data(rd, len as usize, depth)?;
+            let map = read_map_data(rd, len as usize, depth, limits)?;
             Value::Map(map)
         }
         Marker::Bin8 => {
             let len = rd.read_data_u8()?;
-            let vec = read_bin_data(rd, len as usize, depth)?;
+            let vec = read_bin_data(rd, len as usize, depth, limits, Limit::BinLen)?;
             Value::Binary(vec)
         }
         Marker::Bin16 => {
             let len = rd.read_data_u16()?;
-            let vec = read_bin_data(rd, len as usize, depth)?;
+            let vec = read_bin_data(rd, len as usize, depth, limits, Limit::BinLen)?;
             Value::Binary(vec)
         }
         Marker::Bin32 => {
             let len = rd.read_data_u32()?;
-            let vec = read_bin_data(rd, len as usize, depth)?;
+            let vec = read_bin_data(rd, len as usize, depth, limits, Limit::BinLen)?;
             Value::Binary(vec)
         }
         Marker::FixExt1 => {
             let len = 1_usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::FixExt2 => {
             let len = 2_usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::FixExt4 => {
             let len = 4_usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::FixExt8 => {
             let len = 8_usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::FixExt16 => {
             let len = 16_usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::Ext8 => {
             let len = rd.read_data_u8()? as usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::Ext16 => {
             let len = rd.read_data_u16()? as usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::Ext32 => {
             let len = rd.read_data_u32()? as usize;
-            let (ty, vec) = read_ext_body(rd, len, depth)?;
+            let (ty, vec) = read_ext_body(rd, len, depth, limits)?;
             Value::Ext(ty, vec)
         }
         Marker::Reserved => Value::Nil,
@@ -220,7 +249,7 @@ fn read_value_inner<R>(rd: &mut R, depth: usize) -> Result<Value, Error> where R
 pub fn read_value<R>(rd: &mut R) -> Result<Value, Error>
     where R: Read
 {
-    read_value_inner(rd, super::MAX_DEPTH)
+    read_value_with_max_depth(rd, super::MAX_DEPTH)
 }
 
 /// Attempts to read bytes from the given reader and interpret them as a [`Value`].
@@ -238,5 +267,197 @@ pub fn read_value<R>(rd: &mut R) -> Result<Value, Error>
 pub fn read_value_with_max_depth<R>(rd: &mut R, max_depth: usize) -> Result<Value, Error>
     where R: Read
 {
-    read_value_inner(rd, max_depth)
+    read_value_inner(&mut &mut *rd, max_depth, &DecodeLimits::new())
+}
+
+/// Attempts to read bytes from the given reader and interpret them as a [`Value`], rejecting
+/// values exceeding the given limits.
+///
+/// Declared lengths are checked before reading or allocating anything for the value, so this is
+/// suitable for decoding untrusted input. Arrays and maps may be nested up to `limits.max_depth`
+/// levels deep, while scalars don't count towards it, unlike [`read_value_with_max_depth`].
+///
+/// # Errors
+///
+/// This function will return [`Error`] on any I/O error while either reading or decoding a [`Value`].
+///
+/// [`Error::LimitExceeded`] is returned as soon as a length exceeds its limit, or once reading
+/// the value would consume more than `limits.max_total_bytes` bytes, in which case the reader
+/// may have been advanced up to that many bytes. [`Error::DepthLimitExceeded`] is returned if the
+/// value is nested too deep.
+///
+/// # Examples
+///
+/// ```
+/// use rmpv::decode::{read_value_with_limits, DecodeLimits, Error, Limit};
+///
+/// let limits = DecodeLimits { max_str_len: 4, ..DecodeLimits::default() };
+///
+/// // ["abc"]
+/// let buf = [0x91, 0xa3, 0x61, 0x62, 0x63];
+/// assert!(read_value_with_limits(&mut &buf[..], &limits).is_ok());
+///
+/// // A string declaring 4 GiB of data.
+/// let buf = [0xdb, 0xff, 0xff, 0xff, 0xff];
+/// match read_value_with_limits(&mut &buf[..], &limits) {
+///     Err(Error::LimitExceeded(Limit::StrLen)) => {}
+///     other => panic!("unexpected result: {:?}", other),
+/// }
+/// ```
+#[inline(never)]
+pub fn read_value_with_limits<R>(rd: &mut R, limits: &DecodeLimits) -> Result<Value, Error>
+    where R: Read
+{
+    // Only arrays and maps count towards the depth limit here, instead of every nested call.
+    let mut rd = TrackedRead::new(rd, limits.max_total_bytes, limits.max_depth);
+    read_value_inner(&mut rd, usize::MAX, limits).map_err(|err| rd.check_exceeded(err))
+}
+
+/// Attempts to read bytes from the given reader and interpret them as a [`Value`], reporting
+/// where in the input the value failed to decode.
+///
+/// Behaves like [`read_value`] otherwise, offsets being counted from the current position of the
+/// reader.
+///
+/// # Errors
+///
+/// This function will return [`LocatedError`] wrapping the same [`Error`] as [`read_value`] would,
+/// along with the offset of the marker of the value that failed and the path to it.
+///
+/// # Examples
+///
+/// ```
+/// use rmpv::decode::{read_value_located, Error};
+///
+/// // {"users": [{"zip": <truncated string>}]}
+/// let buf = [0x81, 0xa5, 0x75, 0x73, 0x65, 0x72, 0x73, 0x91, 0x81, 0xa3, 0x7a, 0x69, 0x70, 0xa5, 0x31];
+///
+/// let err = read_value_located(&mut &buf[..]).unwrap_err();
+/// assert!(matches!(err.error, Error::InvalidDataRead(..)));
+/// assert_eq!(13, err.offset);
+/// assert_eq!(".users[0].zip", err.path.to_string());
+/// ```
+#[inline(never)]
+pub fn read_value_located<R>(rd: &mut R) -> Result<Value, LocatedError<Error>>
+    where R: Read
+{
+    let mut rd = TrackedRead::new(rd, u64::MAX, usize::MAX);
+    read_value_inner(&mut rd, super::MAX_DEPTH, &DecodeLimits::new()).map_err(|error| {
+        LocatedError {
+            error,
+            offset: rd.marker_offset,
+            path: rd.path_rev.drain(..).rev().collect::<Path>(),
+        }
+    })
+}
+
+/// A reader of values, which may keep track of where they are and how deep they are nested.
+///
+/// Plain readers track nothing, so decoding without limits or locations reads from them directly.
+trait ValueRead: Read {
+    /// Marks the start of the value whose marker is read next.
+    #[inline(always)]
+    fn mark(&mut self) {}
+
+    /// Enters an array or a map, failing if they are nested too deep.
+    #[inline(always)]
+    fn enter(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn leave(&mut self) {}
+
+    /// Records the step into the value that failed with `err` while returning it.
+    #[inline(always)]
+    fn unwind(&mut self, _segment: PathSegment, err: Error) -> Error {
+        err
+    }
+}
+
+impl<R: Read + ?Sized> ValueRead for &mut R {}
+
+/// A reader keeping track of where values start and of the path to the value that failed, failing
+/// once more than a given number of bytes is requested.
+struct TrackedRead<'r, R> {
+    rd: &'r mut R,
+    pos: u64,
+    max: u64,
+    exceeded: bool,
+    /// The number of further levels of arrays and maps allowed.
+    nesting_left: usize,
+    /// The position of the last marker read, which is the one of the value that failed on errors.
+    marker_offset: u64,
+    /// The path to the value that failed, innermost first, built while returning the error.
+    path_rev: Vec<PathSegment>,
+}
+
+impl<'r, R> TrackedRead<'r, R> {
+    fn new(rd: &'r mut R, max: u64, max_nesting: usize) -> Self {
+        TrackedRead { rd, pos: 0, max, exceeded: false, nesting_left: max_nesting, marker_offset: 0, path_rev: Vec::new() }
+    }
+
+    #[cold]
+    fn exceed(&mut self) -> io::Error {
+        self.exceeded = true;
+        io::Error::new(io::ErrorKind::Other, "total bytes limit exceeded")
+    }
+
+    #[cold]
+    fn check_exceeded(&self, err: Error) -> Error {
+        if self.exceeded {
+            Error::LimitExceeded(Limit::TotalBytes)
+        } else {
+            err
+        }
+    }
+}
+
+impl<R: Read> ValueRead for TrackedRead<'_, R> {
+    #[inline]
+    fn mark(&mut self) {
+        self.marker_offset = self.pos;
+    }
+
+    #[inline]
+    fn enter(&mut self) -> Result<(), Error> {
+        self.nesting_left = super::decrement_depth(self.nesting_left)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn leave(&mut self) {
+        self.nesting_left += 1;
+    }
+
+    #[cold]
+    fn unwind(&mut self, segment: PathSegment, err: Error) -> Error {
+        self.path_rev.push(segment);
+        err
+    }
+}
+
+impl<R: Read> Read for TrackedRead<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos == self.max {
+            return Err(self.exceed());
+        }
+        let len = min(buf.len() as u64, self.max - self.pos) as usize;
+        let read = self.rd.read(&mut buf[..len])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if buf.len() as u64 > self.max - self.pos {
+            return Err(self.exceed());
+        }
+        self.rd.read_exact(buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
 }
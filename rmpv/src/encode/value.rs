@@ -1,8 +1,8 @@
 use std::io::Write;
 
 use rmp::encode::{
-    write_array_len, write_bin, write_bool, write_ext_meta, write_f32, write_f64, write_map_len,
-    write_nil, write_sint, write_str, write_uint,
+    write_array_len, write_bin, write_bool, write_ext_meta, write_f32, write_f32_canonical,
+    write_f64, write_f64_canonical, write_map_len, write_nil, write_sint, write_str, write_uint,
 };
 
 use super::Error;
@@ -70,3 +70,71 @@ pub fn write_value<W>(wr: &mut W, val: &Value) -> Result<(), Error>
 
     Ok(())
 }
+
+/// Encodes and attempts to write the given Value in the canonical form, so that equal values are
+/// always encoded to the same bytes.
+///
+/// This differs from [`write_value`] in that floats are written as `f32` whenever that is lossless
+/// with all NaNs normalized, and map entries are sorted by the encoded bytes of their keys. See
+/// [`rmp::decode::is_canonical`] for the exact rules. Maps with duplicate keys can't be made
+/// canonical, their entries are still sorted but the output is rejected by the checker.
+///
+/// Each map is encoded into a temporary buffer to sort its entries.
+///
+/// # Examples
+///
+/// ```
+/// use rmpv::Value;
+///
+/// let val = Value::Map(vec![
+///     (Value::from("b"), Value::from(2.5f64)),
+///     (Value::from("a"), Value::from(1)),
+/// ]);
+///
+/// let mut buf = Vec::new();
+/// rmpv::encode::write_value_canonical(&mut buf, &val).unwrap();
+///
+/// assert_eq!(vec![0x82, 0xa1, 0x61, 0x01, 0xa1, 0x62, 0xca, 0x40, 0x20, 0x00, 0x00], buf);
+/// assert!(rmp::decode::is_canonical(&buf));
+/// ```
+pub fn write_value_canonical<W>(wr: &mut W, val: &Value) -> Result<(), Error>
+    where W: Write
+{
+    match *val {
+        Value::F32(val) => {
+            write_f32_canonical(wr, val)?;
+        }
+        Value::F64(val) => {
+            write_f64_canonical(wr, val)?;
+        }
+        Value::Array(ref vec) => {
+            write_array_len(wr, vec.len() as u32)?;
+            for v in vec {
+                write_value_canonical(wr, v)?;
+            }
+        }
+        Value::Map(ref map) => {
+            // Entries are encoded back to back, keeping the offset where each of them starts.
+            let mut buf = Vec::new();
+            let mut offsets = Vec::with_capacity(map.len() + 1);
+            for (key, val) in map {
+                offsets.push(buf.len());
+                write_value_canonical(&mut buf, key)?;
+                write_value_canonical(&mut buf, val)?;
+            }
+            offsets.push(buf.len());
+
+            // Encoded values are self-delimiting, so sorting whole entries sorts them by keys.
+            let mut entries: Vec<&[u8]> = offsets.windows(2).map(|w| &buf[w[0]..w[1]]).collect();
+            entries.sort_unstable();
+
+            write_map_len(wr, map.len() as u32)?;
+            for entry in entries {
+                wr.write_all(entry).map_err(Error::InvalidDataWrite)?;
+            }
+        }
+        ref val => write_value(wr, val)?,
+    }
+
+    Ok(())
+}
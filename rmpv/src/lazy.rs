@@ -0,0 +1,662 @@
+//! Random access into encoded MessagePack data without decoding all of it.
+//!
+//! Looking up a value in a [`DocRef`] skips over everything before it at the byte level, so only
+//! the markers and lengths of its siblings are read. Only the value finally found is decoded, and
+//! only if asked to.
+//!
+//! For repeated lookups in the same data, a [`DocIndex`] scans it once and then finds array
+//! elements and string keys of maps in constant time.
+//!
+//! Values can also be replaced or added with [`DocRef::set`], which copies all other bytes as
+//! they are.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+
+use rmp::decode::{read_marker, skip_value_from_marker, Bytes, RmpRead, SkipValueError, MAX_SKIP_DEPTH};
+use rmp::encode::{write_array_len, write_map_len, write_str};
+use rmp::Marker;
+
+use crate::decode::{read_value_ref, Error, MAX_DEPTH};
+use crate::encode::write_value;
+use crate::{Value, ValueRef};
+
+/// A MessagePack value at the start of a byte slice, not decoded yet.
+///
+/// Any bytes following the value are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use rmpv::lazy::DocRef;
+/// use rmpv::ValueRef;
+///
+/// // {"a": [{"b": 42}], "c": nil}
+/// let buf = [0x82, 0xa1, 0x61, 0x91, 0x81, 0xa1, 0x62, 0x2a, 0xa1, 0x63, 0xc0];
+/// let doc = DocRef::new(&buf);
+///
+/// let leaf = doc.get("a").unwrap().unwrap().index(0).unwrap().unwrap().get("b").unwrap().unwrap();
+/// assert_eq!(ValueRef::from(42), leaf.to_value_ref().unwrap());
+///
+/// let leaf = doc.pointer("/a/0/b").unwrap().unwrap();
+/// assert_eq!(ValueRef::from(42), leaf.to_value_ref().unwrap());
+///
+/// assert!(doc.pointer("/a/1").unwrap().is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DocRef<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> DocRef<'a> {
+    /// Wraps the encoded value at the start of the given slice.
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> Self {
+        DocRef { buf }
+    }
+
+    /// Reads the marker of this value, without reading anything else.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the slice is empty.
+    pub fn marker(&self) -> Result<Marker, Error> {
+        Ok(read_marker(&mut &self.buf[..])?)
+    }
+
+    /// Returns the encoded bytes of this value, without any bytes following it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is malformed or truncated.
+    pub fn as_bytes(&self) -> Result<&'a [u8], Error> {
+        let mut rd = self.buf;
+        skip(&mut rd)?;
+
+        Ok(&self.buf[..self.buf.len() - rd.len()])
+    }
+
+    /// Decodes this value, borrowing strings and binary data from the slice.
+    ///
+    /// # Errors
+    ///
+    /// See [`read_value_ref`].
+    #[inline]
+    pub fn to_value_ref(&self) -> Result<ValueRef<'a>, Error> {
+        read_value_ref(&mut &self.buf[..])
+    }
+
+    /// Looks up the value of the given string key, if this is a map.
+    ///
+    /// Returns `None` if this is not a map or if it has no such key. If the key occurs several
+    /// times, the first occurrence is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the map is malformed or truncated before the key is found.
+    pub fn get(&self, key: &str) -> Result<Option<DocRef<'a>>, Error> {
+        let mut rd = self.buf;
+        let len = match read_marker(&mut rd)? {
+            Marker::FixMap(len) => len as u32,
+            Marker::Map16 => rd.read_data_u16()? as u32,
+            Marker::Map32 => rd.read_data_u32()?,
+            _ => return Ok(None),
+        };
+
+        for _ in 0..len {
+            if read_str_key(&mut rd)? == Some(key.as_bytes()) {
+                return Ok(Some(DocRef::new(rd)));
+            }
+            skip(&mut rd)?;
+        }
+
+        Ok(None)
+    }
+
+    /// Looks up the element at the given index, if this is an array.
+    ///
+    /// Returns `None` if this is not an array or if the index is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is malformed or truncated before the element.
+    pub fn index(&self, idx: usize) -> Result<Option<DocRef<'a>>, Error> {
+        let mut rd = self.buf;
+        let len = match read_marker(&mut rd)? {
+            Marker::FixArray(len) => len as u32,
+            Marker::Array16 => rd.read_data_u16()? as u32,
+            Marker::Array32 => rd.read_data_u32()?,
+            _ => return Ok(None),
+        };
+        if idx >= len as usize {
+            return Ok(None);
+        }
+
+        for _ in 0..idx {
+            skip(&mut rd)?;
+        }
+
+        Ok(Some(DocRef::new(rd)))
+    }
+
+    /// Looks up a value by a JSON Pointer, as defined by
+    /// [RFC 6901](https://tools.ietf.org/html/rfc6901).
+    ///
+    /// Each reference token is looked up as a string key in maps and as an index in arrays. The
+    /// empty pointer refers to this value itself.
+    ///
+    /// Returns `None` if the pointer is not valid or if any of its tokens is not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any value on the way is malformed or truncated.
+    pub fn pointer(&self, pointer: &str) -> Result<Option<DocRef<'a>>, Error> {
+        let tokens = match parse_pointer(pointer) {
+            Some(tokens) => tokens,
+            None => return Ok(None),
+        };
+
+        let mut doc = *self;
+        for token in tokens {
+            match doc.child(&token)? {
+                Some(next) => doc = next,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(doc))
+    }
+
+    /// Returns a copy of the slice with the value referred to by the given JSON Pointer set to
+    /// the given one.
+    ///
+    /// Only the bytes of the value replaced are changed and the rest is copied as is, without
+    /// decoding or encoding it. Bytes following this value are copied too.
+    ///
+    /// If the last token of the pointer is a key missing from a map, a new entry is appended to
+    /// the map. If it is `-` in an array, a new element is appended to the array. In both cases
+    /// the header of that array or map is rewritten with the new length, growing it if needed.
+    ///
+    /// Returns `None` if the pointer is not valid or if it does not refer to an existing value or
+    /// a new one as described above.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any value on the way is malformed or truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmpv::lazy::DocRef;
+    /// use rmpv::Value;
+    ///
+    /// // {"id": 1}
+    /// let buf = [0x81, 0xa2, 0x69, 0x64, 0x01];
+    ///
+    /// let buf = DocRef::new(&buf).set("/id", &Value::from("a2f")).unwrap().unwrap();
+    /// // {"id": "a2f"}
+    /// assert_eq!(vec![0x81, 0xa2, 0x69, 0x64, 0xa3, 0x61, 0x32, 0x66], buf);
+    ///
+    /// let buf = DocRef::new(&buf).set("/ttl", &Value::from(5)).unwrap().unwrap();
+    /// // {"id": "a2f", "ttl": 5}
+    /// assert_eq!(vec![0x82, 0xa2, 0x69, 0x64, 0xa3, 0x61, 0x32, 0x66, 0xa3, 0x74, 0x74, 0x6c, 0x05], buf);
+    /// ```
+    pub fn set(&self, pointer: &str, val: &Value) -> Result<Option<Vec<u8>>, Error> {
+        let mut encoded = Vec::new();
+        write_value(&mut encoded, val).expect("writing to a Vec never fails");
+        self.set_encoded(pointer, &encoded)
+    }
+
+    /// Returns a copy of the slice with the value referred to by the given JSON Pointer set to
+    /// the given encoded value.
+    ///
+    /// The encoded value is copied as is, so it must be exactly one valid MessagePack value. See
+    /// [`set`](Self::set) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any value on the way is malformed or truncated.
+    pub fn set_encoded(&self, pointer: &str, encoded: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let mut tokens: Vec<_> = match parse_pointer(pointer) {
+            Some(tokens) => tokens.collect(),
+            None => return Ok(None),
+        };
+        let last = match tokens.pop() {
+            Some(last) => last,
+            None => return Ok(Some(self.splice(self, encoded)?)),
+        };
+
+        let mut parent = *self;
+        for token in &tokens {
+            match parent.child(token)? {
+                Some(next) => parent = next,
+                None => return Ok(None),
+            }
+        }
+        if let Some(target) = parent.child(&last)? {
+            return Ok(Some(self.splice(&target, encoded)?));
+        }
+
+        // Append a new entry or element, rewriting the header with the new length.
+        let mut rd = parent.buf;
+        let mut head = Vec::new();
+        match read_marker(&mut rd)? {
+            marker @ (Marker::FixMap(..) | Marker::Map16 | Marker::Map32) => {
+                let len = match container_len(&mut rd, marker)?.checked_add(1) {
+                    Some(len) => len,
+                    None => return Ok(None),
+                };
+                write_map_len(&mut head, len).expect("writing to a Vec never fails");
+                let hlen = head.len();
+                write_str(&mut head, &last).expect("writing to a Vec never fails");
+                head.extend_from_slice(encoded);
+                Ok(Some(parent.splice_container(self, rd, &head, hlen)?))
+            }
+            marker @ (Marker::FixArray(..) | Marker::Array16 | Marker::Array32) if last == "-" => {
+                let len = match container_len(&mut rd, marker)?.checked_add(1) {
+                    Some(len) => len,
+                    None => return Ok(None),
+                };
+                write_array_len(&mut head, len).expect("writing to a Vec never fails");
+                let hlen = head.len();
+                head.extend_from_slice(encoded);
+                Ok(Some(parent.splice_container(self, rd, &head, hlen)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Looks up a reference token of a JSON Pointer in this array or map.
+    fn child(&self, token: &str) -> Result<Option<DocRef<'a>>, Error> {
+        match self.marker()? {
+            Marker::FixMap(..) | Marker::Map16 | Marker::Map32 => self.get(token),
+            Marker::FixArray(..) | Marker::Array16 | Marker::Array32 => match parse_index(token) {
+                Some(idx) => self.index(idx),
+                None => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Copies this slice, replacing the value at the start of `target`, a suffix of it, with the
+    /// given bytes.
+    fn splice(&self, target: &DocRef<'a>, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        let start = self.buf.len() - target.buf.len();
+        let end = start + target.as_bytes()?.len();
+
+        let mut out = Vec::with_capacity(self.buf.len() - (end - start) + bytes.len());
+        out.extend_from_slice(&self.buf[..start]);
+        out.extend_from_slice(bytes);
+        out.extend_from_slice(&self.buf[end..]);
+        Ok(out)
+    }
+
+    /// Copies `doc`, of which this array or map is a suffix, replacing its header with the first
+    /// `hlen` bytes of `head` and appending the rest of them after its last element or entry.
+    ///
+    /// `rd` is the rest of this slice following the original header.
+    fn splice_container(&self, doc: &DocRef<'a>, rd: &[u8], head: &[u8], hlen: usize) -> Result<Vec<u8>, Error> {
+        let start = doc.buf.len() - self.buf.len();
+        let end = start + self.as_bytes()?.len();
+        let body = &doc.buf[doc.buf.len() - rd.len()..end];
+
+        let mut out = Vec::with_capacity(doc.buf.len() + head.len());
+        out.extend_from_slice(&doc.buf[..start]);
+        out.extend_from_slice(&head[..hlen]);
+        out.extend_from_slice(body);
+        out.extend_from_slice(&head[hlen..]);
+        out.extend_from_slice(&doc.buf[end..]);
+        Ok(out)
+    }
+}
+
+/// An index of all values nested in an encoded MessagePack value, for repeated lookups.
+///
+/// Building the index scans the data once, recording the offset of every value in a flat tape
+/// along with the children of every array and map. Afterwards elements of arrays are found by
+/// their index and values of maps by their string keys in constant time, and any value can be
+/// decoded as a [`ValueRef`] borrowing from the data.
+///
+/// # Examples
+///
+/// ```
+/// use rmpv::lazy::DocIndex;
+/// use rmpv::ValueRef;
+///
+/// // {"a": [{"b": 42}], "c": nil}
+/// let buf = [0x82, 0xa1, 0x61, 0x91, 0x81, 0xa1, 0x62, 0x2a, 0xa1, 0x63, 0xc0];
+/// let index = DocIndex::build(&buf).unwrap();
+///
+/// let leaf = index.root().get("a").unwrap().index(0).unwrap().get("b").unwrap();
+/// assert_eq!(ValueRef::from(42), leaf.to_value_ref().unwrap());
+/// assert_eq!(Some(2), index.root().len());
+/// assert!(index.root().get("d").is_none());
+/// ```
+#[derive(Debug)]
+pub struct DocIndex<'a> {
+    buf: &'a [u8],
+    /// All values in the order they are encoded, so the first one is the root.
+    tape: Vec<Node>,
+    /// Tape positions of the elements of arrays and of the keys and values of maps, each array
+    /// and map occupying a contiguous range.
+    children: Vec<usize>,
+    /// Tape positions of values of maps by the tape position of the map and their string key.
+    keys: HashMap<(usize, &'a [u8]), usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    offset: usize,
+    kind: NodeKind,
+    /// Number of elements or entries of arrays and maps.
+    len: usize,
+    /// Start of the range of children of arrays and maps.
+    children: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NodeKind {
+    Scalar,
+    Array,
+    Map,
+}
+
+/// An array or a map whose children are being indexed.
+struct Frame<'a> {
+    node: usize,
+    /// Number of children seen so far, counting keys and values of maps separately.
+    seen: usize,
+    total: usize,
+    /// The last key seen, if this is a map and the key is a string.
+    key: Option<&'a [u8]>,
+}
+
+impl<'a> DocIndex<'a> {
+    /// Indexes the encoded value at the start of the given slice.
+    ///
+    /// Any bytes following the value are ignored. Arrays and maps may be nested up to
+    /// [`MAX_DEPTH`] levels deep. If a map has the same string key several times, the first
+    /// occurrence is found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is malformed, truncated or nested too deep.
+    pub fn build(buf: &'a [u8]) -> Result<Self, Error> {
+        let mut index = DocIndex {
+            buf,
+            tape: Vec::new(),
+            children: Vec::new(),
+            keys: HashMap::new(),
+        };
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+        let mut rd = buf;
+
+        loop {
+            let pos = index.tape.len();
+            let offset = buf.len() - rd.len();
+            let mut key = None;
+            let (kind, len) = match read_marker(&mut rd)? {
+                Marker::FixArray(len) => (NodeKind::Array, len as usize),
+                Marker::Array16 => (NodeKind::Array, rd.read_data_u16()? as usize),
+                Marker::Array32 => (NodeKind::Array, rd.read_data_u32()? as usize),
+                Marker::FixMap(len) => (NodeKind::Map, len as usize),
+                Marker::Map16 => (NodeKind::Map, rd.read_data_u16()? as usize),
+                Marker::Map32 => (NodeKind::Map, rd.read_data_u32()? as usize),
+                marker => {
+                    // Scalars are skipped by the length in their header, remembering strings
+                    // as possible keys.
+                    let len = scalar_len(&mut rd, marker)?;
+                    let data = take(&mut rd, len)?;
+                    if let Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32 = marker {
+                        key = Some(data);
+                    }
+                    (NodeKind::Scalar, 0)
+                }
+            };
+
+            let total = if kind == NodeKind::Map { len.saturating_mul(2) } else { len };
+            // Every value takes at least one byte, so this bounds the memory reserved.
+            if total > rd.len() {
+                return Err(Error::InvalidDataRead(io::Error::new(ErrorKind::UnexpectedEof, "unexpected EOF")));
+            }
+            let children = index.children.len();
+            index.children.resize(children + total, 0);
+            index.tape.push(Node { offset, kind, len, children });
+
+            if let Some(frame) = stack.last_mut() {
+                let parent = index.tape[frame.node];
+                index.children[parent.children + frame.seen] = pos;
+                if parent.kind == NodeKind::Map {
+                    if frame.seen % 2 == 0 {
+                        frame.key = key;
+                    } else if let Some(key) = frame.key.take() {
+                        index.keys.entry((frame.node, key)).or_insert(pos);
+                    }
+                }
+                frame.seen += 1;
+            }
+
+            if total > 0 {
+                if stack.len() == MAX_DEPTH {
+                    return Err(Error::DepthLimitExceeded);
+                }
+                stack.push(Frame { node: pos, seen: 0, total, key: None });
+                continue;
+            }
+
+            // A value has been indexed completely, and so may be the arrays and maps containing it.
+            while stack.last().is_some_and(|frame| frame.seen == frame.total) {
+                stack.pop();
+            }
+            if stack.is_empty() {
+                return Ok(index);
+            }
+        }
+    }
+
+    /// Returns the indexed value.
+    #[inline]
+    pub fn root(&self) -> NodeRef<'_, 'a> {
+        NodeRef { index: self, pos: 0 }
+    }
+}
+
+/// A value in a [`DocIndex`].
+#[derive(Clone, Copy, Debug)]
+pub struct NodeRef<'i, 'a> {
+    index: &'i DocIndex<'a>,
+    pos: usize,
+}
+
+impl<'i, 'a> NodeRef<'i, 'a> {
+    fn node(&self) -> Node {
+        self.index.tape[self.pos]
+    }
+
+    fn child(&self, idx: usize) -> NodeRef<'i, 'a> {
+        NodeRef { index: self.index, pos: self.index.children[self.node().children + idx] }
+    }
+
+    /// Returns the number of elements or entries, if this is an array or a map.
+    pub fn len(&self) -> Option<usize> {
+        match self.node().kind {
+            NodeKind::Scalar => None,
+            NodeKind::Array | NodeKind::Map => Some(self.node().len),
+        }
+    }
+
+    /// Returns whether this has no elements or entries, if this is an array or a map.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Returns the element at the given index, if this is an array.
+    ///
+    /// Returns `None` if this is not an array or if the index is out of bounds.
+    pub fn index(&self, idx: usize) -> Option<NodeRef<'i, 'a>> {
+        let node = self.node();
+        if node.kind != NodeKind::Array || idx >= node.len {
+            return None;
+        }
+        Some(self.child(idx))
+    }
+
+    /// Returns the value of the given string key, if this is a map.
+    ///
+    /// Returns `None` if this is not a map or if it has no such key.
+    pub fn get(&self, key: &str) -> Option<NodeRef<'i, 'a>> {
+        let keys: &HashMap<(usize, &[u8]), usize> = &self.index.keys;
+        let pos = *keys.get(&(self.pos, key.as_bytes()))?;
+        Some(NodeRef { index: self.index, pos })
+    }
+
+    /// Returns the key and the value of the entry at the given index, if this is a map.
+    ///
+    /// Returns `None` if this is not a map or if the index is out of bounds.
+    pub fn entry(&self, idx: usize) -> Option<(NodeRef<'i, 'a>, NodeRef<'i, 'a>)> {
+        let node = self.node();
+        if node.kind != NodeKind::Map || idx >= node.len {
+            return None;
+        }
+        Some((self.child(idx * 2), self.child(idx * 2 + 1)))
+    }
+
+    /// Returns this value as a [`DocRef`], not decoded yet.
+    #[inline]
+    pub fn doc(&self) -> DocRef<'a> {
+        DocRef::new(&self.index.buf[self.node().offset..])
+    }
+
+    /// Decodes this value, borrowing strings and binary data from the indexed data.
+    ///
+    /// # Errors
+    ///
+    /// See [`read_value_ref`].
+    #[inline]
+    pub fn to_value_ref(&self) -> Result<ValueRef<'a>, Error> {
+        self.doc().to_value_ref()
+    }
+}
+
+/// Reads a map key, returning its bytes if it is a string and skipping it otherwise.
+fn read_str_key<'a>(rd: &mut &'a [u8]) -> Result<Option<&'a [u8]>, Error> {
+    let marker = read_marker(rd)?;
+    match marker {
+        Marker::FixStr(..) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
+            let len = scalar_len(rd, marker)?;
+            Ok(Some(take(rd, len)?))
+        }
+        marker => {
+            // The enclosing map takes one level.
+            skip_from_marker(rd, marker, MAX_SKIP_DEPTH - 1)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Skips one value, advancing the slice past it.
+fn skip(rd: &mut &[u8]) -> Result<(), Error> {
+    let marker = read_marker(rd)?;
+    skip_from_marker(rd, marker, MAX_SKIP_DEPTH)
+}
+
+/// Skips the rest of a value whose marker has already been read.
+///
+/// Scalars are skipped by their length alone. Arrays and maps are walked on a [`Bytes`] reader,
+/// which advances past strings and binary data without copying them.
+fn skip_from_marker(rd: &mut &[u8], marker: Marker, max_depth: usize) -> Result<(), Error> {
+    match marker {
+        Marker::FixArray(..) | Marker::Array16 | Marker::Array32 |
+        Marker::FixMap(..) | Marker::Map16 | Marker::Map32 => {
+            let mut cur = Bytes::new(rd);
+            skip_value_from_marker(&mut cur, marker, max_depth).map_err(|err| match err {
+                SkipValueError::InvalidMarkerRead(err) => Error::InvalidMarkerRead(io::Error::new(ErrorKind::UnexpectedEof, err)),
+                SkipValueError::InvalidDataRead(err) => Error::InvalidDataRead(io::Error::new(ErrorKind::UnexpectedEof, err)),
+                SkipValueError::TypeMismatch(..) => Error::InvalidMarkerRead(io::Error::new(ErrorKind::Other, "type mismatch")),
+                SkipValueError::DepthLimitExceeded => Error::DepthLimitExceeded,
+            })?;
+            *rd = cur.remaining_slice();
+            Ok(())
+        }
+        marker => {
+            let len = scalar_len(rd, marker)?;
+            take(rd, len).map(|_| ())
+        }
+    }
+}
+
+/// Reads the length header following the marker of any value except arrays and maps, returning
+/// the number of bytes of data after it.
+fn scalar_len(rd: &mut &[u8], marker: Marker) -> Result<usize, Error> {
+    Ok(match marker {
+        Marker::FixPos(..) |
+        Marker::FixNeg(..) |
+        Marker::Null |
+        Marker::True |
+        Marker::False => 0,
+        Marker::U8 | Marker::I8 => 1,
+        Marker::U16 | Marker::I16 => 2,
+        Marker::U32 | Marker::I32 | Marker::F32 => 4,
+        Marker::U64 | Marker::I64 | Marker::F64 => 8,
+        Marker::FixStr(len) => len as usize,
+        Marker::Str8 | Marker::Bin8 => rd.read_data_u8()? as usize,
+        Marker::Str16 | Marker::Bin16 => rd.read_data_u16()? as usize,
+        Marker::Str32 | Marker::Bin32 => rd.read_data_u32()? as usize,
+        // Extensions are followed by the type byte and the data.
+        Marker::FixExt1 => 2,
+        Marker::FixExt2 => 3,
+        Marker::FixExt4 => 5,
+        Marker::FixExt8 => 9,
+        Marker::FixExt16 => 17,
+        Marker::Ext8 => rd.read_data_u8()? as usize + 1,
+        Marker::Ext16 => rd.read_data_u16()? as usize + 1,
+        Marker::Ext32 => (rd.read_data_u32()? as usize).saturating_add(1),
+        _ => return Err(Error::InvalidMarkerRead(io::Error::new(ErrorKind::Other, "type mismatch"))),
+    })
+}
+
+/// Borrows the next `len` bytes of the slice.
+fn take<'a>(rd: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if len > rd.len() {
+        return Err(Error::InvalidDataRead(io::Error::new(ErrorKind::UnexpectedEof, "unexpected EOF")));
+    }
+    let (data, rest) = rd.split_at(len);
+    *rd = rest;
+
+    Ok(data)
+}
+
+/// Reads the length of an array or a map following its marker.
+fn container_len(rd: &mut &[u8], marker: Marker) -> Result<u32, Error> {
+    Ok(match marker {
+        Marker::FixArray(len) | Marker::FixMap(len) => len as u32,
+        Marker::Array16 | Marker::Map16 => rd.read_data_u16()? as u32,
+        _ => rd.read_data_u32()?,
+    })
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens, unless it is not valid.
+fn parse_pointer(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    let tokens = match pointer.strip_prefix('/') {
+        Some(tokens) => Some(tokens.split('/')),
+        None if pointer.is_empty() => None,
+        None => return None,
+    };
+
+    Some(tokens.into_iter().flatten().map(|token| {
+        if token.contains('~') {
+            Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+        } else {
+            Cow::Borrowed(token)
+        }
+    }))
+}
+
+/// Parses an array index of a JSON Pointer, which has no sign and no leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() > 1) {
+        return None;
+    }
+    token.parse().ok()
+}
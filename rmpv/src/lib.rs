@@ -18,9 +18,16 @@ use std::ops::Index;
 use std::str::Utf8Error;
 
 use num_traits::NumCast;
+use rmp::encode::{
+    size_of_array_header, size_of_bin_header, size_of_ext_header, size_of_map_header, size_of_sint,
+    size_of_str_header, size_of_uint,
+};
+
+pub use rmp::Timestamp;
 
 pub mod decode;
 pub mod encode;
+pub mod lazy;
 
 #[cfg(feature = "with-serde")]
 pub mod ext;
@@ -892,6 +899,66 @@ impl Value {
             None
         }
     }
+
+    /// If the `Value` is an Ext of the timestamp type `-1` with valid timestamp data, returns the
+    /// decoded timestamp. Returns None otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmpv::{Timestamp, Value};
+    ///
+    /// let val = Value::Ext(-1, vec![0x00, 0x00, 0x00, 0x2a]);
+    ///
+    /// assert_eq!(Some(Timestamp::from_secs(42)), val.as_timestamp());
+    /// assert_eq!(val, Value::from(Timestamp::from_secs(42)));
+    ///
+    /// assert_eq!(None, Value::Ext(42, vec![0x00, 0x00, 0x00, 0x2a]).as_timestamp());
+    /// assert_eq!(None, Value::Ext(-1, vec![0x2a]).as_timestamp());
+    /// ```
+    #[inline]
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match *self {
+            Value::Ext(Timestamp::EXT_TYPE, ref buf) => Timestamp::from_ext_data(buf),
+            _ => None,
+        }
+    }
+
+    /// Returns the exact number of bytes [`write_value`](encode::write_value) writes for this
+    /// value, without encoding it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rmpv::Value;
+    ///
+    /// let val = Value::Array(vec![Value::from(300), Value::from("le message")]);
+    ///
+    /// let mut buf = Vec::new();
+    /// rmpv::encode::write_value(&mut buf, &val).unwrap();
+    ///
+    /// assert_eq!(buf.len(), val.encoded_len());
+    /// ```
+    pub fn encoded_len(&self) -> usize {
+        match *self {
+            Value::Nil | Value::Boolean(..) => 1,
+            Value::Integer(Integer { n: IntPriv::PosInt(n) }) => size_of_uint(n),
+            Value::Integer(Integer { n: IntPriv::NegInt(n) }) => size_of_sint(n),
+            Value::F32(..) => 5,
+            Value::F64(..) => 9,
+            Value::String(Utf8String { s: Ok(ref val) }) => size_of_str_header(val.len() as u32) + val.len(),
+            Value::String(Utf8String { s: Err(ref err) }) => size_of_bin_header(err.0.len() as u32) + err.0.len(),
+            Value::Binary(ref val) => size_of_bin_header(val.len() as u32) + val.len(),
+            Value::Array(ref vec) => {
+                size_of_array_header(vec.len() as u32) + vec.iter().map(Value::encoded_len).sum::<usize>()
+            }
+            Value::Map(ref map) => {
+                size_of_map_header(map.len() as u32) +
+                    map.iter().map(|(key, val)| key.encoded_len() + val.encoded_len()).sum::<usize>()
+            }
+            Value::Ext(_, ref data) => size_of_ext_header(data.len() as u32) + data.len(),
+        }
+    }
 }
 
 static NIL: Value = Value::Nil;
@@ -1059,6 +1126,14 @@ impl<'a> From<Cow<'a, [u8]>> for Value {
     }
 }
 
+impl From<Timestamp> for Value {
+    #[inline]
+    fn from(v: Timestamp) -> Self {
+        let mut buf = [0; 12];
+        Value::Ext(Timestamp::EXT_TYPE, v.to_ext_data(&mut buf).to_vec())
+    }
+}
+
 impl From<Vec<Value>> for Value {
     #[inline]
     fn from(v: Vec<Value>) -> Self {
@@ -1223,7 +1298,10 @@ impl Display for Value {
                 write!(f, "}}")
             }
             Value::Ext(ty, ref data) => {
-                write!(f, "[{}, {:?}]", ty, data)
+                match Timestamp::from_ext_data(data) {
+                    Some(ts) if ty == Timestamp::EXT_TYPE => Display::fmt(&ts, f),
+                    _ => write!(f, "[{}, {:?}]", ty, data),
+                }
             }
         }
     }
@@ -1571,7 +1649,10 @@ impl<'a> Display for ValueRef<'a> {
                 write!(f, "}}")
             }
             ValueRef::Ext(ty, ref data) => {
-                write!(f, "[{}, {:?}]", ty, data)
+                match Timestamp::from_ext_data(data) {
+                    Some(ts) if ty == Timestamp::EXT_TYPE => Display::fmt(&ts, f),
+                    _ => write!(f, "[{}, {:?}]", ty, data),
+                }
             }
         }
     }
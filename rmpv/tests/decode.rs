@@ -1,4 +1,4 @@
-use rmpv::decode::{read_value, Error};
+use rmpv::decode::{read_value, read_value_located, read_value_with_limits, DecodeLimits, Error, Limit};
 use rmpv::Value;
 
 #[test]
@@ -397,3 +397,98 @@ fn invalid_buf_size_arr() {
         Err(e) => panic!("Unexpected error: {}", e),
     }
 }
+
+#[test]
+fn pass_within_limits() {
+    // {"a": [1, <bin 2>], "b": <ext 1>}
+    let buf: &[u8] = &[
+        0x82,
+        0xa1, 0x61, 0x92, 0x01, 0xc4, 0x02, 0x00, 0x00,
+        0xa1, 0x62, 0xd4, 0x01, 0x00,
+    ];
+    let limits = DecodeLimits {
+        max_str_len: 1,
+        max_bin_len: 2,
+        max_array_len: 2,
+        max_map_len: 2,
+        max_ext_len: 1,
+        max_total_bytes: buf.len() as u64,
+        max_depth: 16,
+    };
+
+    assert_eq!(read_value(&mut &buf[..]).unwrap(), read_value_with_limits(&mut &buf[..], &limits).unwrap());
+}
+
+#[test]
+fn fail_limits_exceeded() {
+    let cases: &[(&[u8], DecodeLimits, Limit)] = &[
+        (&[0xa2, 0x61, 0x62], DecodeLimits { max_str_len: 1, ..DecodeLimits::default() }, Limit::StrLen),
+        (&[0xc6, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_bin_len: 1024, ..DecodeLimits::default() }, Limit::BinLen),
+        (&[0x91, 0x92, 0x01, 0x02], DecodeLimits { max_array_len: 1, ..DecodeLimits::default() }, Limit::ArrayLen),
+        (&[0xdf, 0xff, 0xff, 0xff, 0xff], DecodeLimits { max_map_len: 1024, ..DecodeLimits::default() }, Limit::MapLen),
+        (&[0xd5, 0x01, 0x00, 0x00], DecodeLimits { max_ext_len: 1, ..DecodeLimits::default() }, Limit::ExtLen),
+        (&[0x93, 0x01, 0x02, 0x03], DecodeLimits { max_total_bytes: 3, ..DecodeLimits::default() }, Limit::TotalBytes),
+    ];
+
+    for (buf, limits, expected) in cases {
+        match read_value_with_limits(&mut &buf[..], limits) {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(*expected, limit),
+            other => panic!("unexpected result for {:?}: {:?}", expected, other),
+        }
+    }
+}
+
+#[test]
+fn fail_limits_total_bytes_before_allocating() {
+    // Claims 4 GiB of binary data, but only 8 bytes may be read.
+    let buf: &[u8] = &[0xc6, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00];
+    let limits = DecodeLimits { max_total_bytes: 8, ..DecodeLimits::default() };
+
+    match read_value_with_limits(&mut &buf[..], &limits) {
+        Err(Error::LimitExceeded(Limit::TotalBytes)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_limits_depth() {
+    let buf = [0x91; 8];
+    let limits = DecodeLimits { max_depth: 4, ..DecodeLimits::default() };
+
+    match read_value_with_limits(&mut &buf[..], &limits) {
+        Err(Error::DepthLimitExceeded) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_located_path() {
+    // {7: [nil, {"zip code": [1, <truncated string>]}]}
+    let buf = [
+        0x81, 0x07, 0x92, 0xc0, 0x81,
+        0xa8, 0x7a, 0x69, 0x70, 0x20, 0x63, 0x6f, 0x64, 0x65,
+        0x92, 0x01, 0xa3, 0x61,
+    ];
+
+    let err = read_value_located(&mut &buf[..]).unwrap_err();
+    assert!(matches!(err.error, Error::InvalidDataRead(..)), "{:?}", err);
+    assert_eq!(16, err.offset);
+    assert_eq!("[7][1][\"zip code\"][1]", err.path.to_string());
+}
+
+#[test]
+fn fail_located_key() {
+    // {[<truncated string>]: nil}
+    let buf = [0x81, 0x91, 0xa3, 0x61];
+
+    let err = read_value_located(&mut &buf[..]).unwrap_err();
+    assert_eq!(2, err.offset);
+    assert_eq!("[?][0]", err.path.to_string());
+}
+
+#[test]
+fn pass_located_value() {
+    let buf = [0x92, 0x01, 0xa1, 0x61];
+    let val = Value::Array(vec![Value::from(1), Value::from("a")]);
+    assert_eq!(val, read_value_located(&mut &buf[..]).unwrap());
+}
@@ -0,0 +1,115 @@
+use rmpv::encode::{write_value, write_value_canonical};
+use rmpv::Value;
+
+fn encode_canonical(val: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_value_canonical(&mut buf, val).unwrap();
+    buf
+}
+
+#[test]
+fn pack_canonical_scalars_as_write_value() {
+    let vals = [
+        Value::Nil,
+        Value::from(true),
+        Value::from(42),
+        Value::from(-300),
+        Value::from("le message"),
+        Value::from(vec![0u8; 300]),
+        Value::Ext(1, vec![0; 8]),
+    ];
+
+    for val in &vals {
+        let mut buf = Vec::new();
+        write_value(&mut buf, val).unwrap();
+
+        assert_eq!(buf, encode_canonical(val));
+        assert!(rmp::decode::is_canonical(&buf));
+    }
+}
+
+#[test]
+fn pack_canonical_floats() {
+    assert_eq!(vec![0xca, 0x3f, 0xc0, 0x00, 0x00], encode_canonical(&Value::F64(1.5)));
+    assert_eq!(vec![0xca, 0x7f, 0xc0, 0x00, 0x00], encode_canonical(&Value::F64(f64::NAN)));
+    assert_eq!(vec![0xca, 0x7f, 0xc0, 0x00, 0x00], encode_canonical(&Value::F32(-f32::NAN)));
+    assert_eq!(vec![0xcb, 0x3f, 0xb9, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+               encode_canonical(&Value::F64(0.1)));
+}
+
+#[test]
+fn pack_canonical_map_sorted_by_encoded_keys() {
+    let val = Value::Map(vec![
+        (Value::from("bc"), Value::from(3)),
+        (Value::from("a"), Value::from(2)),
+        (Value::from(1), Value::Nil),
+    ]);
+
+    assert_eq!(vec![0x83, 0x01, 0xc0, 0xa1, 0x61, 0x02, 0xa2, 0x62, 0x63, 0x03],
+               encode_canonical(&val));
+}
+
+#[test]
+fn pack_canonical_nested_maps() {
+    let inner = Value::Map(vec![
+        (Value::from("y"), Value::F64(0.25)),
+        (Value::from("x"), Value::Nil),
+    ]);
+    let val = Value::Array(vec![
+        Value::Map(vec![
+            (inner.clone(), Value::from(1)),
+            (Value::Array(vec![]), inner),
+        ]),
+    ]);
+
+    let buf = encode_canonical(&val);
+
+    let inner = [0x82, 0xa1, 0x78, 0xc0, 0xa1, 0x79, 0xca, 0x3e, 0x80, 0x00, 0x00];
+    let mut expected = vec![0x91, 0x82];
+    expected.extend_from_slice(&inner);
+    expected.push(0x01);
+    expected.push(0x90);
+    expected.extend_from_slice(&inner);
+
+    assert_eq!(expected, buf);
+    assert!(rmp::decode::is_canonical(&buf));
+}
+
+#[test]
+fn pack_canonical_equal_maps_to_same_bytes() {
+    let a = Value::Map(vec![(Value::from(1), Value::from(2)), (Value::from(3), Value::from(4))]);
+    let b = Value::Map(vec![(Value::from(3), Value::from(4)), (Value::from(1), Value::from(2))]);
+
+    assert_eq!(encode_canonical(&a), encode_canonical(&b));
+}
+
+#[test]
+fn pass_encoded_len_matches_write_value() {
+    // A string with invalid UTF-8 is written as binary.
+    let invalid_utf8 = rmpv::decode::read_value(&mut &[0xa2, 0xc3, 0x28][..]).unwrap();
+    assert!(invalid_utf8.as_str().is_none());
+
+    let vals = [
+        Value::Nil,
+        Value::from(false),
+        Value::from(u64::MAX),
+        Value::from(-129),
+        Value::from(1.5f32),
+        Value::from(1.5f64),
+        Value::from("x".repeat(40)),
+        Value::from(vec![0u8; 70000]),
+        invalid_utf8,
+        Value::Ext(-1, vec![0; 12]),
+        Value::Array((0..20).map(Value::from).collect()),
+        Value::Map(vec![
+            (Value::from("k"), Value::Array(vec![Value::Nil; 3])),
+            (Value::from(1), Value::Map(vec![])),
+        ]),
+    ];
+
+    for val in &vals {
+        let mut buf = Vec::new();
+        write_value(&mut buf, val).unwrap();
+        assert_eq!(buf.len(), val.encoded_len(), "{:?}", val);
+    }
+}
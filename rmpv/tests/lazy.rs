@@ -0,0 +1,272 @@
+use rmpv::decode::Error;
+use rmpv::encode::write_value;
+use rmpv::lazy::{DocIndex, DocRef};
+use rmpv::{Value, ValueRef};
+
+fn sample() -> Vec<u8> {
+    let val = Value::Map(vec![
+        (Value::from(1), Value::from("int key")),
+        (Value::from("items"), Value::Array(vec![
+            Value::Map(vec![(Value::from("id"), Value::from(10))]),
+            Value::Map(vec![(Value::from("id"), Value::from(20)), (Value::from("a/b"), Value::Nil)]),
+        ])),
+        (Value::from("m~n"), Value::from(true)),
+        (Value::from("blob"), Value::Binary(vec![0; 1000])),
+        (Value::from("last"), Value::from("end")),
+    ]);
+
+    let mut buf = Vec::new();
+    write_value(&mut buf, &val).unwrap();
+    buf
+}
+
+#[test]
+fn pass_get_and_index() {
+    let buf = sample();
+    let doc = DocRef::new(&buf);
+
+    assert_eq!(ValueRef::from("end"), doc.get("last").unwrap().unwrap().to_value_ref().unwrap());
+    assert!(doc.get("missing").unwrap().is_none());
+
+    let items = doc.get("items").unwrap().unwrap();
+    assert_eq!(ValueRef::from(20), items.index(1).unwrap().unwrap().get("id").unwrap().unwrap().to_value_ref().unwrap());
+    assert!(items.index(2).unwrap().is_none());
+
+    // Lookups of the wrong kind find nothing.
+    assert!(items.get("id").unwrap().is_none());
+    assert!(doc.index(0).unwrap().is_none());
+}
+
+#[test]
+fn pass_as_bytes_excludes_siblings() {
+    let buf = sample();
+    let doc = DocRef::new(&buf);
+
+    let item = doc.pointer("/items/0").unwrap().unwrap();
+    assert_eq!(&[0x81, 0xa2, 0x69, 0x64, 0x0a], item.as_bytes().unwrap());
+    assert_eq!(buf.len(), doc.as_bytes().unwrap().len());
+}
+
+#[test]
+fn pass_pointer() {
+    let buf = sample();
+    let doc = DocRef::new(&buf);
+
+    assert_eq!(Some(doc), doc.pointer("").unwrap());
+    assert_eq!(ValueRef::from(10), doc.pointer("/items/0/id").unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::Nil, doc.pointer("/items/1/a~1b").unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::Boolean(true), doc.pointer("/m~0n").unwrap().unwrap().to_value_ref().unwrap());
+
+    assert!(doc.pointer("items").unwrap().is_none());
+    assert!(doc.pointer("/items/01").unwrap().is_none());
+    assert!(doc.pointer("/items/+1").unwrap().is_none());
+    assert!(doc.pointer("/items/0/id/x").unwrap().is_none());
+    // Integer keys are not matched by pointer tokens.
+    assert!(doc.pointer("/1").unwrap().is_none());
+}
+
+#[test]
+fn fail_get_truncated() {
+    let buf = sample();
+    let doc = DocRef::new(&buf[..buf.len() - 2]);
+
+    // Keys before the truncation are still found.
+    assert!(doc.get("blob").unwrap().is_some());
+    match doc.get("missing") {
+        Err(Error::InvalidMarkerRead(..)) | Err(Error::InvalidDataRead(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_get_skips_siblings_of_every_kind() {
+    let val = Value::Map(vec![
+        (Value::from("f"), Value::from(1.5)),
+        (Value::from("x"), Value::Ext(7, vec![1, 2, 3])),
+        (Value::Array(vec![Value::from(1)]), Value::Map(vec![(Value::from("b"), Value::Binary(vec![0; 300]))])),
+        (Value::from("n"), Value::from(-1000)),
+        (Value::from("last"), Value::from("end")),
+    ]);
+    let mut buf = Vec::new();
+    write_value(&mut buf, &val).unwrap();
+    let doc = DocRef::new(&buf);
+
+    assert_eq!(ValueRef::from("end"), doc.get("last").unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::from(-1000), doc.get("n").unwrap().unwrap().to_value_ref().unwrap());
+
+    // {"a": 0xc1, "b": 1}
+    match DocRef::new(&[0x82, 0xa1, 0x61, 0xc1, 0xa1, 0x62, 0x01]).get("b") {
+        Err(Error::InvalidMarkerRead(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_empty() {
+    let doc = DocRef::new(&[]);
+
+    assert!(doc.marker().is_err());
+    assert!(doc.get("a").is_err());
+}
+
+#[test]
+fn pass_index_lookups() {
+    let buf = sample();
+    let index = DocIndex::build(&buf).unwrap();
+    let root = index.root();
+
+    assert_eq!(Some(5), root.len());
+    assert_eq!(ValueRef::from("end"), root.get("last").unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::Binary(&[0; 1000]), root.get("blob").unwrap().to_value_ref().unwrap());
+    assert!(root.get("missing").is_none());
+
+    let items = root.get("items").unwrap();
+    assert_eq!(Some(2), items.len());
+    let second = items.index(1).unwrap();
+    assert_eq!(ValueRef::from(20), second.get("id").unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::Nil, second.get("a/b").unwrap().to_value_ref().unwrap());
+    assert!(items.index(2).is_none());
+    // Keys of other maps are not found.
+    assert!(root.get("id").is_none());
+
+    let (key, val) = root.entry(0).unwrap();
+    assert_eq!(ValueRef::from(1), key.to_value_ref().unwrap());
+    assert_eq!(ValueRef::from("int key"), val.to_value_ref().unwrap());
+    assert!(root.entry(5).is_none());
+
+    assert_eq!(None, val.len());
+    assert!(val.index(0).is_none());
+    assert!(items.get("id").is_none());
+    assert_eq!(doc_pointer(&buf, "/items/0"), items.index(0).unwrap().doc().as_bytes().unwrap());
+}
+
+fn doc_pointer<'a>(buf: &'a [u8], pointer: &str) -> &'a [u8] {
+    DocRef::new(buf).pointer(pointer).unwrap().unwrap().as_bytes().unwrap()
+}
+
+#[test]
+fn pass_index_duplicate_keys_and_scalars() {
+    // {"a": 1, "a": 2}
+    let buf = [0x82, 0xa1, 0x61, 0x01, 0xa1, 0x61, 0x02];
+    let index = DocIndex::build(&buf).unwrap();
+    assert_eq!(ValueRef::from(1), index.root().get("a").unwrap().to_value_ref().unwrap());
+
+    let index = DocIndex::build(&[0x2a, 0xc0]).unwrap();
+    assert_eq!(ValueRef::from(42), index.root().to_value_ref().unwrap());
+    assert_eq!(None, index.root().len());
+    assert_eq!(None, index.root().is_empty());
+}
+
+#[test]
+fn pass_index_scalars_of_every_kind() {
+    let val = Value::Array(vec![
+        Value::from(1.5),
+        Value::Ext(7, vec![1, 2, 3]),
+        Value::Binary(vec![0; 300]),
+        Value::from(u64::MAX),
+        Value::from("end"),
+    ]);
+    let mut buf = Vec::new();
+    write_value(&mut buf, &val).unwrap();
+    let index = DocIndex::build(&buf).unwrap();
+
+    for (idx, elem) in val.as_array().unwrap().iter().enumerate() {
+        assert_eq!(elem.as_ref(), index.root().index(idx).unwrap().to_value_ref().unwrap());
+    }
+
+    match DocIndex::build(&[0x92, 0x01, 0xc1]) {
+        Err(Error::InvalidMarkerRead(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_index_truncated() {
+    let buf = sample();
+
+    for len in 0..buf.len() {
+        assert!(DocIndex::build(&buf[..len]).is_err(), "len = {}", len);
+    }
+}
+
+#[test]
+fn fail_index_huge_declared_len() {
+    // An array declaring 2^32 - 1 elements with none following.
+    match DocIndex::build(&[0xdd, 0xff, 0xff, 0xff, 0xff]) {
+        Err(Error::InvalidDataRead(..)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn fail_index_depth_limit() {
+    let buf = vec![0x91; 2000];
+
+    match DocIndex::build(&buf) {
+        Err(Error::DepthLimitExceeded) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn pass_set_replaces_only_target() {
+    let buf = sample();
+    let doc = DocRef::new(&buf);
+
+    let out = doc.set("/items/1/id", &Value::from("x".repeat(40))).unwrap().unwrap();
+    let new = DocRef::new(&out);
+    assert_eq!(ValueRef::from("x".repeat(40).as_str()), new.pointer("/items/1/id").unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::Nil, new.pointer("/items/1/a~1b").unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::from("end"), new.get("last").unwrap().unwrap().to_value_ref().unwrap());
+
+    // Everything but the replaced value is copied as is.
+    let old = doc.pointer("/items/1/id").unwrap().unwrap().as_bytes().unwrap();
+    let start = old.as_ptr() as usize - buf.as_ptr() as usize;
+    assert_eq!(&buf[..start], &out[..start]);
+    assert_eq!(&buf[start + old.len()..], &out[start + 42..]);
+}
+
+#[test]
+fn pass_set_root_keeps_trailing_bytes() {
+    let out = DocRef::new(&[0x01, 0xc0]).set("", &Value::from(300)).unwrap().unwrap();
+    assert_eq!(vec![0xcd, 0x01, 0x2c, 0xc0], out);
+}
+
+#[test]
+fn pass_set_appends_growing_header() {
+    // A map with 15 entries, the most a fixmap can hold, followed by nil.
+    let mut buf = Vec::new();
+    let val = Value::Map((0..15).map(|i| (Value::from(format!("k{}", i)), Value::from(i))).collect());
+    write_value(&mut buf, &val).unwrap();
+    buf.push(0xc0);
+
+    let out = DocRef::new(&buf).set("/new", &Value::Nil).unwrap().unwrap();
+    assert_eq!(&[0xde, 0x00, 0x10], &out[..3]);
+    assert_eq!(&buf[1..buf.len() - 1], &out[3..buf.len() + 1]);
+    assert_eq!(&[0xa3, 0x6e, 0x65, 0x77, 0xc0, 0xc0], &out[buf.len() + 1..]);
+
+    let mut expected = val.as_map().unwrap().clone();
+    expected.push((Value::from("new"), Value::Nil));
+    assert_eq!(Value::Map(expected), rmpv::decode::read_value(&mut &out[..]).unwrap());
+}
+
+#[test]
+fn pass_set_appends_to_nested_array() {
+    let buf = sample();
+
+    let out = DocRef::new(&buf).set("/items/-", &Value::from(3)).unwrap().unwrap();
+    let items = DocRef::new(&out).pointer("/items").unwrap().unwrap();
+    assert_eq!(ValueRef::from(3), items.index(2).unwrap().unwrap().to_value_ref().unwrap());
+    assert_eq!(ValueRef::from("end"), DocRef::new(&out).get("last").unwrap().unwrap().to_value_ref().unwrap());
+}
+
+#[test]
+fn pass_set_unresolved_pointer() {
+    let buf = sample();
+    let doc = DocRef::new(&buf);
+
+    assert!(doc.set("last", &Value::Nil).unwrap().is_none());
+    assert!(doc.set("/missing/a", &Value::Nil).unwrap().is_none());
+    assert!(doc.set("/items/2", &Value::Nil).unwrap().is_none());
+    assert!(doc.set("/last/a", &Value::Nil).unwrap().is_none());
+}
@@ -1,4 +1,4 @@
-use rmpv::Value;
+use rmpv::{Timestamp, Value};
 
 #[test]
 fn display_nil() {
@@ -56,6 +56,26 @@ fn display_ext() {
     assert_eq!("[1, [100, 42]]", format!("{}", Value::Ext(1, vec![100, 42])));
 }
 
+#[test]
+fn display_timestamp() {
+    assert_eq!("1970-01-01T00:00:00Z", format!("{}", Value::from(Timestamp::from_secs(0))));
+    assert_eq!("2018-01-02T03:04:05.678901234Z",
+        format!("{}", Value::from(Timestamp::new(1514862245, 678901234).unwrap())));
+    assert_eq!("1969-12-31T23:59:59.999999999Z",
+        format!("{}", Value::from(Timestamp::new(-1, 999999999).unwrap())));
+    assert_eq!("2000-02-29T00:00:00Z", format!("{}", Value::from(Timestamp::from_secs(951782400))));
+    // Data of an invalid length is displayed as an ordinary ext.
+    assert_eq!("[-1, [42]]", format!("{}", Value::Ext(-1, vec![42])));
+}
+
+#[test]
+fn from_timestamp() {
+    assert_eq!(Value::Ext(-1, vec![0x00, 0x00, 0x00, 0x2a]), Value::from(Timestamp::from_secs(42)));
+    assert_eq!(Value::Ext(-1, vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+        Value::from(Timestamp::from_secs(1 << 32)));
+    assert_eq!(Some(Timestamp::new(-1, 5).unwrap()), Value::from(Timestamp::new(-1, 5).unwrap()).as_timestamp());
+}
+
 #[test]
 fn from_bool() {
     assert_eq!(Value::Boolean(true), Value::from(true));